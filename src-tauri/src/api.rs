@@ -0,0 +1,329 @@
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tracing::{error, info, warn};
+
+use crate::core;
+use crate::live::{self, LiveEvent};
+use crate::metrics;
+use crate::pricing::CostMode;
+use crate::reports::{self, BlockUsage, DailyUsage, DateRange, MonthlyUsage, SessionUsage};
+use crate::settings::{get_settings_store, load_settings, LocalApiSettings};
+use crate::usage::UsageEntry;
+
+struct RunningServer {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// Tracks the local HTTP API server so settings changes can restart it
+#[derive(Default)]
+pub struct LocalApiState {
+    running: Mutex<Option<RunningServer>>,
+}
+
+#[derive(Deserialize)]
+struct ReportQuery {
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    app.manage(LocalApiState::default());
+
+    // Settings from before tokens were required may enable the API without one
+    let mut settings = load_settings(app);
+    if settings.local_api.enabled && !has_token(&settings.local_api) {
+        let saved = ensure_token(&mut settings.local_api, None)
+            .and_then(|()| settings.save_to_store(&*get_settings_store(app)?));
+        match saved {
+            Ok(()) => info!("Generated a local API token"),
+            Err(e) => warn!("Failed to set up a local API token: {}", e),
+        }
+    }
+
+    // A bad bind address or busy port should not prevent the app from starting
+    if let Err(e) = apply_settings(app, &settings.local_api) {
+        warn!("Local API not started: {}", e);
+    }
+
+    Ok(())
+}
+
+fn has_token(settings: &LocalApiSettings) -> bool {
+    settings.token.as_deref().is_some_and(|token| !token.is_empty())
+}
+
+/// Gives enabled settings a bearer token: the one they carry, else `stored`, else a new
+/// random one. Settings saved from the webview omit the token, so the stored one is kept.
+pub fn ensure_token(settings: &mut LocalApiSettings, stored: Option<String>) -> Result<(), String> {
+    if has_token(settings) {
+        return Ok(());
+    }
+    settings.token = stored.filter(|token| !token.is_empty());
+    if settings.token.is_none() && settings.enabled {
+        let mut bytes = [0u8; 32];
+        getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate local API token: {}", e))?;
+        settings.token = Some(bytes.iter().map(|byte| format!("{:02x}", byte)).collect());
+    }
+    Ok(())
+}
+
+/// Compares tokens in time independent of where they first differ
+pub fn tokens_match(expected: &str, provided: &str) -> bool {
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Whether a `Host` header names this machine. Anything else, such as a DNS name rebound
+/// to 127.0.0.1 by a web page, is refused.
+pub fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    name.eq_ignore_ascii_case("localhost") || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Starts, stops or rebinds the server to match the given settings
+pub fn apply_settings(app: &AppHandle, settings: &LocalApiSettings) -> Result<(), String> {
+    let state = app.state::<LocalApiState>();
+    let mut running = state.running.lock().unwrap();
+
+    let addr = if settings.enabled {
+        Some(loopback_addr(settings)?)
+    } else {
+        None
+    };
+
+    if running.as_ref().map(|server| server.addr) == addr {
+        return Ok(());
+    }
+
+    if let Some(server) = running.take() {
+        let _ = server.shutdown.send(());
+        info!("Stopped local API on {}", server.addr);
+    }
+
+    let Some(addr) = addr else {
+        return Ok(());
+    };
+
+    // Bind synchronously so port conflicts are reported back to the caller
+    let listener = std::net::TcpListener::bind(addr)
+        .map_err(|e| format!("Failed to bind local API to {}: {}", addr, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure local API socket: {}", e))?;
+
+    let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+    let router = router(app.clone());
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to start local API: {}", e);
+                return;
+            }
+        };
+
+        let result = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+
+        if let Err(e) = result {
+            error!("Local API server error: {}", e);
+        }
+    });
+
+    info!("Local API listening on http://{}", addr);
+    *running = Some(RunningServer { addr, shutdown });
+    Ok(())
+}
+
+fn loopback_addr(settings: &LocalApiSettings) -> Result<SocketAddr, String> {
+    let ip: IpAddr = settings
+        .bind_address
+        .parse()
+        .map_err(|_| format!("Invalid bind address: {}", settings.bind_address))?;
+
+    if !ip.is_loopback() {
+        return Err(format!(
+            "Local API only binds to loopback addresses, got {}",
+            settings.bind_address
+        ));
+    }
+
+    Ok(SocketAddr::new(ip, settings.port))
+}
+
+fn router(app: AppHandle) -> Router {
+    let protected = Router::new()
+        .route("/v1/daily", get(daily))
+        .route("/v1/monthly", get(monthly))
+        .route("/v1/sessions", get(sessions))
+        .route("/v1/blocks/active", get(active_block))
+        .route("/v1/stream", get(stream))
+        .route("/metrics", get(prometheus_metrics))
+        .route_layer(middleware::from_fn_with_state(app.clone(), require_token));
+
+    Router::new()
+        .route("/v1/health", get(health))
+        .merge(protected)
+        .layer(middleware::from_fn(require_loopback_host))
+        .with_state(app)
+}
+
+async fn require_loopback_host(request: Request, next: Next) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok());
+
+    if !host.is_some_and(is_loopback_host) {
+        return ApiError(StatusCode::FORBIDDEN, "Host must be a loopback address".to_string()).into_response();
+    }
+
+    next.run(request).await
+}
+
+async fn require_token(State(app): State<AppHandle>, request: Request, next: Next) -> Response {
+    // Read the token on every request so changing it takes effect without a restart
+    let expected = load_settings(&app).local_api.token.unwrap_or_default();
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    // A missing token refuses every request rather than allowing them all
+    let valid = !expected.is_empty() && provided.is_some_and(|provided| tokens_match(&expected, provided));
+    if !valid {
+        return ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token".to_string()).into_response();
+    }
+
+    next.run(request).await
+}
+
+async fn load(app: &AppHandle, query: &ReportQuery) -> Result<(Vec<UsageEntry>, CostMode), ApiError> {
+    let range = DateRange::parse(query.since.as_deref(), query.until.as_deref())
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e))?;
+    let mode = CostMode::parse(query.mode.as_deref().unwrap_or(&load_settings(app).cost_mode));
+
+    let entries = scan().await?.iter().filter(|entry| range.contains(entry)).cloned().collect();
+
+    Ok((entries, mode))
+}
+
+/// Entries from the last scan, rescanned only once the file watcher reported a change
+async fn scan() -> Result<Arc<Vec<UsageEntry>>, ApiError> {
+    tauri::async_runtime::spawn_blocking(core::cached_entries)
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+        "claudeDirectories": core::discover_claude_paths().len(),
+    }))
+}
+
+async fn daily(
+    State(app): State<AppHandle>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Json<Vec<DailyUsage>>, ApiError> {
+    let (entries, mode) = load(&app, &query).await?;
+    Ok(Json(reports::daily_report(&entries, mode)))
+}
+
+async fn monthly(
+    State(app): State<AppHandle>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Json<Vec<MonthlyUsage>>, ApiError> {
+    let (entries, mode) = load(&app, &query).await?;
+    Ok(Json(reports::monthly_report(&entries, mode)))
+}
+
+async fn sessions(
+    State(app): State<AppHandle>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Json<Vec<SessionUsage>>, ApiError> {
+    let (entries, mode) = load(&app, &query).await?;
+    Ok(Json(reports::session_report(&entries, mode)))
+}
+
+async fn active_block(
+    State(app): State<AppHandle>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Json<Option<BlockUsage>>, ApiError> {
+    let (entries, mode) = load(&app, &query).await?;
+    Ok(Json(reports::active_block(&entries, mode, Utc::now())))
+}
+
+async fn prometheus_metrics(State(app): State<AppHandle>) -> Result<Response, ApiError> {
+    let mode = CostMode::parse(&load_settings(&app).cost_mode);
+    let entries = scan().await?;
+    let body = metrics::render(&entries, mode, core::last_load_stats().as_ref(), Utc::now());
+
+    Ok(([(header::CONTENT_TYPE, metrics::CONTENT_TYPE)], body).into_response())
+}
+
+/// Server-Sent Events feed of newly parsed entries and active block updates
+async fn stream(State(app): State<AppHandle>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let initial = LiveEvent::ActiveBlock { block: live::active_block(&app) };
+    let updates = BroadcastStream::new(live::subscribe(&app)).filter_map(|event| event.ok());
+
+    let events = tokio_stream::once(initial)
+        .chain(updates)
+        .map(|event| Ok(sse_event(&event)));
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+fn sse_event(event: &LiveEvent) -> Event {
+    let name = match event {
+        LiveEvent::Entries { .. } => "entries",
+        LiveEvent::ActiveBlock { .. } => "activeBlock",
+    };
+    Event::default()
+        .event(name)
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().comment("serialization failed"))
+}
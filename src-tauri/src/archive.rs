@@ -0,0 +1,387 @@
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Instant, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
+
+use crate::core;
+use crate::scanner::Scanner;
+use crate::settings::ArchiveSettings;
+use crate::usage::{self, UsageEntry};
+
+const INDEX_FILE: &str = "index.json";
+const SEGMENT_PREFIX: &str = "usage-";
+const SEGMENT_SUFFIX: &str = ".ndjson.gz";
+
+static ARCHIVE: OnceLock<Archive> = OnceLock::new();
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How far into a transcript the archive has read, and the size and mtime it had then
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileStamp {
+    offset: u64,
+    len: u64,
+    modified_ms: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    files: HashMap<String, FileStamp>,
+}
+
+#[derive(Default)]
+struct ArchiveState {
+    loaded: bool,
+    index: Index,
+    seen: HashSet<String>,
+}
+
+/// Append-only copy of the usage records from every transcript seen, so usage survives
+/// Claude Code deleting old transcripts. Records are stored as gzip-compressed NDJSON in one
+/// segment per month; each append adds a new gzip member, so a crash mid-write can only
+/// damage the records being written.
+pub struct Archive {
+    dir: PathBuf,
+    state: Mutex<ArchiveState>,
+}
+
+/// Identifies a record across transcripts; falls back to time and size for lines without ids
+pub fn archive_key(entry: &UsageEntry) -> String {
+    entry
+        .dedup_key()
+        .unwrap_or_else(|| format!("{}:{}", entry.timestamp.to_rfc3339(), entry.total_tokens()))
+}
+
+impl Archive {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            state: Mutex::new(ArchiveState::default()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Copies usage records appended to `path` since it was last archived. Returns how many
+    /// new records were written.
+    pub fn archive_file(&self, path: &Path) -> io::Result<usize> {
+        let metadata = fs::metadata(path)?;
+        let len = metadata.len();
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+
+        let mut state = self.lock();
+        let key = path.to_string_lossy().to_string();
+        let previous = state.index.files.get(&key).copied();
+        if previous.is_some_and(|stamp| stamp.len == len && stamp.modified_ms == modified_ms) {
+            return Ok(0);
+        }
+
+        // Transcripts only grow; anything else means the file was replaced, so start over
+        let start = previous.map(|stamp| stamp.offset).filter(|&offset| offset <= len).unwrap_or(0);
+        let (entries, consumed) = read_complete_lines(path, start, len)?;
+
+        let mut fresh: BTreeMap<String, Vec<(String, UsageEntry)>> = BTreeMap::new();
+        let mut batch = HashSet::new();
+        for entry in entries {
+            let entry_key = archive_key(&entry);
+            if state.seen.contains(&entry_key) || !batch.insert(entry_key.clone()) {
+                continue;
+            }
+            let month = entry.timestamp.format("%Y-%m").to_string();
+            fresh.entry(month).or_default().push((entry_key, entry));
+        }
+
+        let mut archived = 0;
+        for (month, records) in fresh {
+            if let Err(e) = self.append_segment(&month, records.iter().map(|(_, entry)| entry)) {
+                // The segment may now end in a partial member; repair it on the next pass
+                state.loaded = false;
+                return Err(e);
+            }
+            archived += records.len();
+            state.seen.extend(records.into_iter().map(|(entry_key, _)| entry_key));
+        }
+
+        state.index.files.insert(
+            key,
+            FileStamp {
+                offset: start + consumed,
+                len,
+                modified_ms,
+            },
+        );
+        self.save_index(&state.index)?;
+        Ok(archived)
+    }
+
+    /// Archives every transcript below the given Claude directories. Returns how many new
+    /// records were written.
+    pub fn archive_all(&self, claude_paths: &[PathBuf]) -> usize {
+        let roots: Vec<PathBuf> = claude_paths.iter().map(|path| path.join("projects")).collect();
+        let files = match Scanner::new().discover(&roots) {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to list transcripts to archive: {}", e);
+                return 0;
+            }
+        };
+
+        files
+            .iter()
+            .map(|file| {
+                self.archive_file(file).unwrap_or_else(|e| {
+                    warn!(path = %file.display(), "Failed to archive transcript: {}", e);
+                    0
+                })
+            })
+            .sum()
+    }
+
+    /// Every archived record, oldest first
+    pub fn entries(&self) -> Vec<UsageEntry> {
+        let mut seen = HashSet::new();
+        let mut entries: Vec<UsageEntry> = self
+            .segments()
+            .iter()
+            .flat_map(|segment| read_segment(segment).0)
+            .filter(|entry| seen.insert(archive_key(entry)))
+            .collect();
+        entries.sort_by_key(|entry| entry.timestamp);
+        entries
+    }
+
+    /// Locks the state, reading the index and archived keys from disk on first use
+    fn lock(&self) -> MutexGuard<'_, ArchiveState> {
+        let mut state = self.state.lock().unwrap();
+        if !state.loaded {
+            state.loaded = true;
+            state.index = match fs::read(self.dir.join(INDEX_FILE)) {
+                Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                    // Without the index every transcript is re-read; the keys still prevent duplicates
+                    warn!("Ignoring unreadable archive index: {}", e);
+                    Index::default()
+                }),
+                Err(_) => Index::default(),
+            };
+            state.seen.clear();
+            for segment in self.segments() {
+                let (entries, intact) = read_segment(&segment);
+                if !intact {
+                    // Records appended after a damaged member would be unreadable, so rewrite
+                    // the segment with what survived
+                    warn!(path = %segment.display(), recovered = entries.len(), "Repairing archive segment");
+                    if let Err(e) = rewrite_segment(&segment, &entries) {
+                        warn!(path = %segment.display(), "Failed to repair archive segment: {}", e);
+                    }
+                }
+                state.seen.extend(entries.iter().map(archive_key));
+            }
+        }
+        state
+    }
+
+    fn segments(&self) -> Vec<PathBuf> {
+        let mut segments: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(SEGMENT_PREFIX) && name.ends_with(SEGMENT_SUFFIX))
+            })
+            .collect();
+        segments.sort();
+        segments
+    }
+
+    fn append_segment<'a>(&self, month: &str, entries: impl Iterator<Item = &'a UsageEntry>) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}{}{}", SEGMENT_PREFIX, month, SEGMENT_SUFFIX));
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        write_member(file, entries)
+    }
+
+    fn save_index(&self, index: &Index) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(INDEX_FILE);
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_vec(index)?)?;
+        fs::rename(temp, path)
+    }
+}
+
+/// Parses the newline-terminated lines between `start` and `len`, leaving a line still being
+/// written for the next pass. Returns the entries and the number of bytes consumed.
+fn read_complete_lines(path: &Path, start: u64, len: u64) -> io::Result<(Vec<UsageEntry>, u64)> {
+    let mut file = File::open(path)?;
+    let terminated = len == 0 || {
+        let mut last = [0u8; 1];
+        file.seek(SeekFrom::Start(len - 1))?;
+        file.read_exact(&mut last)?;
+        last[0] == b'\n'
+    };
+    file.seek(SeekFrom::Start(start))?;
+
+    let end = len - start;
+    let mut consumed = 0;
+    let parsed = usage::parse_reader(BufReader::new(file.take(end)), path, |read| {
+        if !terminated && consumed + read as u64 >= end {
+            return false;
+        }
+        consumed += read as u64;
+        true
+    })?;
+    Ok((parsed.entries, consumed))
+}
+
+/// Writes the entries as one gzip member
+fn write_member<'a>(file: File, entries: impl Iterator<Item = &'a UsageEntry>) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    for entry in entries {
+        serde_json::to_writer(&mut encoder, entry)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()?.sync_data()
+}
+
+/// Replaces a segment with a single member holding `entries`
+fn rewrite_segment(path: &Path, entries: &[UsageEntry]) -> io::Result<()> {
+    let temp = path.with_extension("gz.tmp");
+    write_member(File::create(&temp)?, entries.iter())?;
+    fs::rename(temp, path)
+}
+
+/// Reads one segment, keeping whatever precedes a truncated or corrupt tail. The flag is
+/// `false` when such a tail was found.
+fn read_segment(path: &Path) -> (Vec<UsageEntry>, bool) {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            warn!(path = %path.display(), "Failed to open archive segment: {}", e);
+            return (Vec::new(), true);
+        }
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(MultiGzDecoder::new(file)).lines() {
+        match line {
+            Ok(line) => {
+                if let Ok(entry) = serde_json::from_str::<UsageEntry>(&line) {
+                    entries.push(entry);
+                }
+            }
+            Err(e) => {
+                warn!(path = %path.display(), "Archive segment is truncated: {}", e);
+                return (entries, false);
+            }
+        }
+    }
+    (entries, true)
+}
+
+/// Adds archived records missing from `live`, e.g. because their transcript was deleted.
+/// Returns how many were added.
+pub fn merge(live: &mut Vec<UsageEntry>, archived: Vec<UsageEntry>) -> usize {
+    let known: HashSet<String> = live.iter().map(archive_key).collect();
+    let before = live.len();
+    live.extend(archived.into_iter().filter(|entry| !known.contains(&archive_key(entry))));
+    let added = live.len() - before;
+    if added > 0 {
+        live.sort_by_key(|entry| entry.timestamp);
+    }
+    added
+}
+
+/// Opens the archive in the app data directory and catches up on transcripts written while
+/// the app was closed
+pub fn init(app: &AppHandle, settings: &ArchiveSettings) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = ARCHIVE.set(Archive::new(dir.join("archive")));
+        }
+        Err(e) => {
+            warn!("Usage archive disabled, no app data directory: {}", e);
+            return;
+        }
+    }
+    apply_settings(settings);
+}
+
+/// Turns archiving on or off; turning it on archives every transcript not yet archived
+pub fn apply_settings(settings: &ArchiveSettings) {
+    let was_enabled = ENABLED.swap(settings.enabled, Ordering::Relaxed);
+    if settings.enabled && !was_enabled {
+        if let Some(archive) = ARCHIVE.get() {
+            std::thread::spawn(move || {
+                let started = Instant::now();
+                let archived = archive.archive_all(&core::discover_claude_paths());
+                info!(
+                    archived,
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    dir = %archive.dir().display(),
+                    "Usage archive up to date"
+                );
+            });
+        }
+    }
+}
+
+fn active() -> Option<&'static Archive> {
+    ARCHIVE.get().filter(|_| ENABLED.load(Ordering::Relaxed))
+}
+
+/// Archives new records from a transcript the watcher saw created or modified
+pub fn file_changed(path: &Path) {
+    let Some(archive) = active() else {
+        return;
+    };
+    match archive.archive_file(path) {
+        Ok(0) => {}
+        Ok(archived) => debug!(path = %path.display(), archived, "Archived usage"),
+        Err(e) => warn!(path = %path.display(), "Failed to archive transcript: {}", e),
+    }
+}
+
+/// Archived records, or none when archiving is disabled
+pub fn archived_entries() -> Vec<UsageEntry> {
+    active().map(Archive::entries).unwrap_or_default()
+}
+
+/// Renders stored records missing from the given transcripts as one usage-only transcript,
+/// for callers that hand raw transcripts to the frontend
+pub fn missing_transcript(transcripts: &[String], stored: &[UsageEntry]) -> Option<String> {
+    if stored.is_empty() {
+        return None;
+    }
+
+    let live: HashSet<String> = transcripts
+        .iter()
+        .flat_map(|content| usage::parse_content(content, Path::new("")).entries)
+        .map(|entry| archive_key(&entry))
+        .collect();
+    let lines: Vec<String> = stored
+        .iter()
+        .filter(|entry| !live.contains(&archive_key(entry)))
+        .map(UsageEntry::to_transcript_line)
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
@@ -0,0 +1,441 @@
+use chrono::{NaiveDate, NaiveTime, Utc};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::core;
+use crate::export::{self, ExportFormat, OverwritePolicy, ReportKind};
+use crate::fixtures::{self, FixtureDir, FixtureRng, GenerateOptions};
+use crate::import::{self, Imports};
+use crate::pricing::CostMode;
+use crate::reports::DateRange;
+use crate::sync;
+use crate::team;
+
+const GENERATE_USAGE: &str = "\
+Usage: claude-deck generate-fixtures --output <dir> [options]
+
+Writes synthetic Claude Code transcripts into <dir>/projects for load and UI testing.
+Point CLAUDE_CONFIG_DIR at <dir> to load them.
+
+Options:
+  --output <dir>         Claude config directory to write into (required)
+  --projects <n>         Number of projects (default 5)
+  --sessions <n>         Sessions per project (default 10)
+  --messages <n>         Assistant messages per session (default 100)
+  --since <YYYY-MM-DD>   Earliest session start (default 30 days ago)
+  --until <YYYY-MM-DD>   Latest session start (default now)
+  --models <mix>         Weighted model mix, e.g. sonnet=70,opus=25,haiku=5
+  --cost-ratio <0..1>    Fraction of messages carrying costUSD (default 0.5)
+  --malformed <0..1>     Fraction of assistant lines written corrupt (default 0)
+  --seed <n>             Random seed for reproducible output (default 1)
+  --append-rate <n>      After generating, append n messages per second to a live session
+  --duration <secs>      Stop live appending after this many seconds (default: run until killed)
+";
+
+const EXPORT_USAGE: &str = "\
+Usage: claude-deck export --report <report> --output <file> [options]
+
+Renders a report from the Claude data directories without starting the desktop app.
+The report is written to a temporary file next to <file> and renamed into place.
+
+Options:
+  --report <report>      daily, monthly, session, blocks, models, projects, machines, users,
+                         cache or entries (required)
+  --output <file>        File to write (required)
+  --format <format>      csv, json, ndjson, markdown, html, xlsx, parquet or arrow
+                         (default: from the --output extension, else csv)
+  --since <YYYY-MM-DD>   First day to include
+  --until <YYYY-MM-DD>   Last day to include
+  --mode <mode>          Cost mode: auto, calculate or display (default auto)
+  --overwrite <policy>   fail, replace or rename when <file> exists (default fail)
+  --team <dir>           Report on the member snapshots in <dir> instead of this machine
+  --anonymize            With --team, label users user-1, user-2, ... instead of by name
+";
+
+const IMPORT_USAGE: &str = "\
+Usage: claude-deck import --machine <label> <file>
+
+Imports usage recorded on another machine into the desktop app's data directory.
+<file> is `ccusage daily|monthly|session|blocks --json` output or a JSON or NDJSON
+export of the entries report. Records already imported are skipped by message ID.
+
+Options:
+  --machine <label>      Source machine, e.g. laptop or devbox (required)
+";
+
+const SNAPSHOT_USAGE: &str = "\
+Usage: claude-deck snapshot --user <label> --output <file> [options]
+
+Writes this machine's usage, without any message text, as a member snapshot for a team
+roll-up. Collect the snapshots of every member in one directory and point the team
+directory setting or `export --team` at it.
+
+Options:
+  --user <label>         Your name in team reports, e.g. alice (required)
+  --output <file>        File to write, conventionally <user>.<machine>.claudedeck.ndjson.gz
+                         (required)
+  --machine <label>      This machine's label (default: the host name)
+  --overwrite <policy>   fail, replace or rename when <file> exists (default fail)
+";
+
+struct GenerateArgs {
+    output: PathBuf,
+    options: GenerateOptions,
+    append_rate: Option<f64>,
+    duration: Option<Duration>,
+}
+
+/// Whether `args` name one of the subcommands `run` handles
+pub fn is_command(args: &[String]) -> bool {
+    matches!(
+        args.first().map(String::as_str),
+        Some("generate-fixtures" | "export" | "import" | "snapshot")
+    )
+}
+
+/// Handles command line subcommands. Returns the process exit code, or `None` when the
+/// arguments should start the desktop app instead.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first().map(String::as_str) {
+        Some("generate-fixtures") => Some(exit_code(generate_fixtures(&args[1..]))),
+        Some("export") => Some(exit_code(export(&args[1..]))),
+        Some("import") => Some(exit_code(import(&args[1..]))),
+        Some("snapshot") => Some(exit_code(snapshot(&args[1..]))),
+        _ => None,
+    }
+}
+
+/// Prints a subcommand's error and maps its outcome to the process exit code
+fn exit_code(result: Result<(), String>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            2
+        }
+    }
+}
+
+fn export(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print!("{}", EXPORT_USAGE);
+        return Ok(());
+    }
+
+    let mut report = None;
+    let mut output = None;
+    let mut format = None;
+    let (mut since, mut until) = (None, None);
+    let mut mode = CostMode::Auto;
+    let mut overwrite = OverwritePolicy::Fail;
+    let mut team = None;
+    let mut anonymize = false;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if flag == "--anonymize" {
+            anonymize = true;
+            continue;
+        }
+        let value = args
+            .next()
+            .map(String::as_str)
+            .ok_or_else(|| format!("Missing value for {}\n\n{}", flag, EXPORT_USAGE))?;
+        match flag.as_str() {
+            "--report" => report = Some(ReportKind::parse(value)?),
+            "--output" => output = Some(PathBuf::from(value)),
+            "--format" => format = Some(ExportFormat::parse(value)?),
+            "--since" => since = Some(value),
+            "--until" => until = Some(value),
+            "--mode" => mode = CostMode::parse(value),
+            "--overwrite" => overwrite = OverwritePolicy::parse(value)?,
+            "--team" => team = Some(PathBuf::from(value)),
+            other => return Err(format!("Unknown option: {}\n\n{}", other, EXPORT_USAGE)),
+        }
+    }
+
+    let report = report.ok_or_else(|| format!("--report is required\n\n{}", EXPORT_USAGE))?;
+    let output = output.ok_or_else(|| format!("--output is required\n\n{}", EXPORT_USAGE))?;
+    let format = format.unwrap_or_else(|| {
+        output
+            .extension()
+            .and_then(|extension| ExportFormat::parse(&extension.to_string_lossy()).ok())
+            .unwrap_or(ExportFormat::Csv)
+    });
+    let range = DateRange::parse(since, until)?;
+
+    if anonymize && team.is_none() {
+        return Err(format!("--anonymize requires --team\n\n{}", EXPORT_USAGE));
+    }
+
+    let started = Instant::now();
+    let mut entries = match &team {
+        Some(dir) => {
+            let data = team::load_team(dir, anonymize)?;
+            for skipped in &data.skipped {
+                eprintln!("Skipped {}: {}", skipped.file, skipped.error);
+            }
+            data.entries
+        }
+        None => core::load_entries(),
+    };
+    entries.retain(|entry| range.contains(entry));
+    let path = export::export_to_file(&output, overwrite, report, format, &range, &entries, mode, Utc::now())
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    println!(
+        "Wrote {} report from {} entries to {} in {:.2?}",
+        report.name(),
+        entries.len(),
+        path.display(),
+        started.elapsed()
+    );
+    Ok(())
+}
+
+fn import(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print!("{}", IMPORT_USAGE);
+        return Ok(());
+    }
+
+    let mut machine = None;
+    let mut file = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--machine" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for --machine\n\n{}", IMPORT_USAGE))?;
+                machine = Some(value.as_str());
+            }
+            other if other.starts_with("--") => {
+                return Err(format!("Unknown option: {}\n\n{}", other, IMPORT_USAGE));
+            }
+            path if file.is_none() => file = Some(PathBuf::from(path)),
+            extra => return Err(format!("Unexpected argument: {}\n\n{}", extra, IMPORT_USAGE)),
+        }
+    }
+
+    let machine = machine.ok_or_else(|| format!("--machine is required\n\n{}", IMPORT_USAGE))?;
+    let file = file.ok_or_else(|| format!("A file to import is required\n\n{}", IMPORT_USAGE))?;
+    let dir = import::default_dir().ok_or("No data directory to store imports in")?;
+    let summary = Imports::new(&dir).import_file(&file, machine)?;
+    println!(
+        "Imported {} of {} records for {} into {} ({} already imported)",
+        summary.added,
+        summary.records,
+        summary.machine,
+        dir.display(),
+        summary.duplicates
+    );
+    Ok(())
+}
+
+fn snapshot(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print!("{}", SNAPSHOT_USAGE);
+        return Ok(());
+    }
+
+    let mut user = None;
+    let mut machine = None;
+    let mut output = None;
+    let mut overwrite = OverwritePolicy::Fail;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .map(String::as_str)
+            .ok_or_else(|| format!("Missing value for {}\n\n{}", flag, SNAPSHOT_USAGE))?;
+        match flag.as_str() {
+            "--user" => user = Some(team::validate_user(value)?),
+            "--machine" => machine = Some(import::validate_machine(value)?),
+            "--output" => output = Some(PathBuf::from(value)),
+            "--overwrite" => overwrite = OverwritePolicy::parse(value)?,
+            other => return Err(format!("Unknown option: {}\n\n{}", other, SNAPSHOT_USAGE)),
+        }
+    }
+
+    let user = user.ok_or_else(|| format!("--user is required\n\n{}", SNAPSHOT_USAGE))?;
+    let output = output.ok_or_else(|| format!("--output is required\n\n{}", SNAPSHOT_USAGE))?;
+    let machine = machine
+        .or_else(sync::host_name)
+        .ok_or("Could not determine a machine label; pass --machine")?;
+
+    let entries: Vec<_> = core::load_entries()
+        .into_iter()
+        .filter(|entry| entry.machine.is_none())
+        .collect();
+    let path = export::write_atomically(&output, overwrite, |out| {
+        team::write_snapshot(out, &user, &machine, &entries, Utc::now())
+    })
+    .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    println!(
+        "Wrote {} records for {} on {} to {}",
+        entries.len(),
+        user,
+        machine,
+        path.display()
+    );
+    Ok(())
+}
+
+fn generate_fixtures(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print!("{}", GENERATE_USAGE);
+        return Ok(());
+    }
+
+    let args = parse_generate_args(args)?;
+    let dir = FixtureDir::new(&args.output);
+
+    let started = Instant::now();
+    let summary = fixtures::generate(&dir, &args.options)
+        .map_err(|e| format!("Failed to write fixtures: {}", e))?;
+    println!(
+        "Wrote {} lines across {} session files to {} in {:.2?}",
+        summary.lines,
+        summary.files,
+        args.output.display(),
+        started.elapsed()
+    );
+
+    if let Some(rate) = args.append_rate {
+        append_live(&dir, &args.options, rate, args.duration)?;
+    }
+
+    Ok(())
+}
+
+/// Appends messages stamped with the current time to one session, simulating an active
+/// Claude Code conversation
+fn append_live(
+    dir: &FixtureDir,
+    options: &GenerateOptions,
+    rate: f64,
+    duration: Option<Duration>,
+) -> Result<(), String> {
+    let session_id = format!("live-{}", Utc::now().timestamp());
+    let mut writer = dir
+        .session("-synthetic-project-live", &session_id)
+        .map_err(|e| format!("Failed to open live session: {}", e))?;
+    let mut rng = FixtureRng::new(options.seed.wrapping_add(1));
+    let interval = Duration::from_secs_f64(1.0 / rate);
+
+    println!("Appending {} messages/s to {}", rate, writer.path().display());
+
+    let started = Instant::now();
+    let mut count = 0u64;
+    while duration.is_none_or(|duration| started.elapsed() < duration) {
+        let now = Utc::now();
+        let model = rng.weighted(&options.models).to_string();
+        let message = fixtures::random_message(
+            &mut rng,
+            now,
+            &model,
+            &format!("{}-{}", session_id, count),
+            options.cost_ratio,
+        );
+
+        writer
+            .user(now, "Synthetic prompt")
+            .and_then(|_| writer.assistant(&message))
+            .map_err(|e| format!("Failed to append: {}", e))?;
+        count += 1;
+
+        std::thread::sleep(interval);
+    }
+
+    println!("Appended {} messages", count);
+    Ok(())
+}
+
+fn parse_generate_args(args: &[String]) -> Result<GenerateArgs, String> {
+    let mut output = None;
+    let mut options = GenerateOptions::default();
+    let mut append_rate = None;
+    let mut duration = None;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .map(String::as_str)
+                .ok_or_else(|| format!("Missing value for {}\n\n{}", flag, GENERATE_USAGE))
+        };
+
+        match flag.as_str() {
+            "--output" => output = Some(PathBuf::from(value()?)),
+            "--projects" => options.projects = number(flag, value()?)?,
+            "--sessions" => options.sessions = number(flag, value()?)?,
+            "--messages" => options.messages = number(flag, value()?)?,
+            "--since" => options.start = date(flag, value()?)?,
+            "--until" => options.end = date(flag, value()?)?,
+            "--models" => options.models = model_mix(value()?)?,
+            "--cost-ratio" => options.cost_ratio = ratio(flag, value()?)?,
+            "--malformed" => options.malformed_ratio = ratio(flag, value()?)?,
+            "--seed" => options.seed = number(flag, value()?)?,
+            "--append-rate" => {
+                let rate: f64 = number(flag, value()?)?;
+                // NaN and infinity parse as floats but give no usable interval
+                if !(rate.is_finite() && rate > 0.0) || Duration::try_from_secs_f64(1.0 / rate).is_err() {
+                    return Err("--append-rate must be a positive number".to_string());
+                }
+                append_rate = Some(rate);
+            }
+            "--duration" => duration = Some(Duration::from_secs(number(flag, value()?)?)),
+            other => return Err(format!("Unknown option: {}\n\n{}", other, GENERATE_USAGE)),
+        }
+    }
+
+    let output = output.ok_or_else(|| format!("--output is required\n\n{}", GENERATE_USAGE))?;
+    if options.start > options.end {
+        return Err("--since must not be after --until".to_string());
+    }
+
+    Ok(GenerateArgs {
+        output,
+        options,
+        append_rate,
+        duration,
+    })
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+
+fn ratio(flag: &str, value: &str) -> Result<f64, String> {
+    let ratio: f64 = number(flag, value)?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(format!("{} must be between 0 and 1", flag));
+    }
+    Ok(ratio)
+}
+
+fn date(flag: &str, value: &str) -> Result<chrono::DateTime<Utc>, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        .map_err(|_| format!("Invalid date for {}: {} (expected YYYY-MM-DD)", flag, value))
+}
+
+/// Parses `name=weight` pairs; a bare name gets weight 1
+fn model_mix(value: &str) -> Result<Vec<(String, u32)>, String> {
+    let mut models = Vec::new();
+    for part in value.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let (name, weight) = match part.split_once('=') {
+            Some((name, weight)) => (name, number("--models", weight)?),
+            None => (part, 1),
+        };
+        models.push((fixtures::model_alias(name).to_string(), weight));
+    }
+
+    if models.iter().all(|(_, weight)| *weight == 0) {
+        return Err("--models needs at least one model with a non-zero weight".to_string());
+    }
+    Ok(models)
+}
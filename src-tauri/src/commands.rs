@@ -1,48 +1,378 @@
 use crate::core;
-use crate::settings::{AppSettings, get_settings_store};
+use crate::export::{self, ExportFormat, ReportKind};
+use crate::file_monitor::{self, WatcherHealth};
+use crate::fs_scope;
+use crate::import::{self, ImportSummary, ImportedMachine};
+use crate::logging::{self, LogEntry};
+use crate::pricing::CostMode;
+use crate::reports::{
+    self, BlockUsage, CacheReport, DailyUsage, DateRange, MachineUsage, ModelUsage, MonthlyUsage, SessionUsage,
+};
+use crate::scanner::CANCELLED;
+use crate::settings::{AppSettings, get_settings_store, load_settings};
+use crate::sync::SyncStatus;
+use crate::team::{self, TeamData, TeamReport};
+use crate::usage::UsageEntry;
+use std::path::PathBuf;
 use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tracing::{debug, error};
 
 #[tauri::command]
 pub async fn load_usage_entries(
     since_date: Option<String>,
     app: AppHandle,
 ) -> Result<Vec<String>, String> {
-    println!("[commands.rs] load_usage_entries called with since_date: {:?}", since_date);
+    debug!(?since_date, "load_usage_entries called");
     let result = core::load_usage_data(since_date, &app).await;
     match &result {
-        Ok(data) => println!("[commands.rs] load_usage_entries returning {} entries", data.len()),
-        Err(e) => println!("[commands.rs] load_usage_entries error: {}", e),
+        Ok(data) => debug!("load_usage_entries returning {} files", data.len()),
+        Err(e) if e == CANCELLED => debug!("load_usage_entries cancelled"),
+        Err(e) => error!("load_usage_entries failed: {}", e),
     }
     result
 }
 
+/// Loads entries within the date range and resolves the cost mode, defaulting to settings.
+/// A new call for the same `report` cancels a scan still running for an earlier one.
+async fn report_entries(
+    report: &'static str,
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: &AppHandle,
+) -> Result<(Vec<UsageEntry>, CostMode), String> {
+    let range = DateRange::parse(since.as_deref(), until.as_deref())?;
+    let mode = CostMode::parse(mode.as_deref().unwrap_or(&load_settings(app).cost_mode));
+    let app_handle = app.clone();
+    let mut entries =
+        tauri::async_runtime::spawn_blocking(move || core::load_entries_with_progress(&app_handle, report))
+            .await
+            .map_err(|e| format!("Failed to load usage entries: {}", e))??;
+    entries.retain(|entry| range.contains(entry));
+    Ok((entries, mode))
+}
+
+/// Reads the team directory from settings and keeps the records within the date range
+async fn team_entries(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: &AppHandle,
+) -> Result<(TeamData, CostMode), String> {
+    let range = DateRange::parse(since.as_deref(), until.as_deref())?;
+    let settings = load_settings(app);
+    let mode = CostMode::parse(mode.as_deref().unwrap_or(&settings.cost_mode));
+    if settings.team.directory.trim().is_empty() {
+        return Err("No team directory configured".to_string());
+    }
+    let dir = PathBuf::from(settings.team.directory.trim());
+    let mut data = tauri::async_runtime::spawn_blocking(move || team::load_team(&dir, settings.team.anonymize))
+        .await
+        .map_err(|e| format!("Failed to load team usage: {}", e))??;
+    data.entries.retain(|entry| range.contains(entry));
+    Ok((data, mode))
+}
+
 #[tauri::command]
-pub async fn export_data(
-    format: String,
-    content: String,
-    default_filename: String,
+pub async fn get_daily_report(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
     app: AppHandle,
-) -> Result<(), String> {
-    core::export_data(format, content, default_filename, &app).await
+) -> Result<Vec<DailyUsage>, String> {
+    let (entries, mode) = report_entries("get_daily_report", since, until, mode, &app).await?;
+    Ok(reports::daily_report(&entries, mode))
+}
+
+#[tauri::command]
+pub async fn get_monthly_report(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<MonthlyUsage>, String> {
+    let (entries, mode) = report_entries("get_monthly_report", since, until, mode, &app).await?;
+    Ok(reports::monthly_report(&entries, mode))
+}
+
+#[tauri::command]
+pub async fn get_session_report(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<SessionUsage>, String> {
+    let (entries, mode) = report_entries("get_session_report", since, until, mode, &app).await?;
+    Ok(reports::session_report(&entries, mode))
+}
+
+#[tauri::command]
+pub async fn get_active_block(mode: Option<String>, app: AppHandle) -> Result<Option<BlockUsage>, String> {
+    let (entries, mode) = report_entries("get_active_block", None, None, mode, &app).await?;
+    Ok(reports::active_block(&entries, mode, chrono::Utc::now()))
+}
+
+/// Usage per model with token composition, cost share, cache hit ratio and average tokens
+/// per request
+#[tauri::command]
+pub async fn get_model_report(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<ModelUsage>, String> {
+    let (entries, mode) = report_entries("get_model_report", since, until, mode, &app).await?;
+    Ok(reports::model_report(&entries, mode))
+}
+
+/// Prompt cache efficiency overall, per day and per project, with poorly reused sessions
+#[tauri::command]
+pub async fn get_cache_report(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: AppHandle,
+) -> Result<CacheReport, String> {
+    let (entries, mode) = report_entries("get_cache_report", since, until, mode, &app).await?;
+    Ok(reports::cache_report(&entries, mode))
+}
+
+#[tauri::command]
+pub async fn get_machine_report(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<MachineUsage>, String> {
+    let (entries, mode) = report_entries("get_machine_report", since, until, mode, &app).await?;
+    Ok(reports::machine_report(&entries, mode))
+}
+
+/// Rolls up the member snapshots in the team directory into per-user, per-project and
+/// per-model usage with leaderboards
+#[tauri::command]
+pub async fn get_team_report(
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    app: AppHandle,
+) -> Result<TeamReport, String> {
+    let (data, mode) = team_entries(since, until, mode, &app).await?;
+    Ok(team::team_report(data, mode))
+}
+
+/// Writes this machine's usage as a member snapshot for a team roll-up to `path`, or where
+/// the user chooses when `path` is omitted. Returns the path written, or `None` when the
+/// save dialog was cancelled.
+#[tauri::command]
+pub async fn export_team_snapshot(
+    path: Option<String>,
+    overwrite: Option<String>,
+    app: AppHandle,
+) -> Result<Option<String>, String> {
+    let settings = load_settings(&app);
+    let user = team::validate_user(&settings.team.user_name)
+        .map_err(|_| "Set a user name for team snapshots first".to_string())?;
+    let machine = crate::sync::machine_name(&settings.sync).unwrap_or_else(|| "machine".to_string());
+    let default_filename = team::snapshot_file_name(&user, &machine);
+    let Some((path, overwrite)) = core::export_destination(path, overwrite, &default_filename, &app).await? else {
+        return Ok(None);
+    };
+
+    let path = tauri::async_runtime::spawn_blocking(move || {
+        // Only this machine's own usage; other machines write their own snapshots
+        let entries: Vec<UsageEntry> = core::load_entries()
+            .into_iter()
+            .filter(|entry| entry.machine.is_none())
+            .collect();
+        export::write_atomically(&path, overwrite, |out| {
+            team::write_snapshot(out, &user, &machine, &entries, chrono::Utc::now())
+        })
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?
+    .map_err(|e| format!("Failed to write team snapshot: {}", e))?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Imports ccusage `--json` output or a ClaudeDeck JSON or NDJSON entries export from
+/// `path`, or from a file the user picks when `path` is omitted, tagging it with `machine`.
+/// Returns `None` when the open dialog was cancelled.
+#[tauri::command]
+pub async fn import_usage(
+    machine: String,
+    path: Option<String>,
+    app: AppHandle,
+) -> Result<Option<ImportSummary>, String> {
+    let machine = import::validate_machine(&machine)?;
+    let path = match path {
+        // Explicit paths are held to the same directories as exports
+        Some(path) => {
+            let allowed = fs_scope::export_directories(&load_settings(&app).export.allowed_directories);
+            fs_scope::check_export_path(&path, &allowed)?
+        }
+        None => {
+            let app = app.clone();
+            let picked = tauri::async_runtime::spawn_blocking(move || core::pick_open_path(&app))
+                .await
+                .map_err(|e| format!("Open dialog failed: {}", e))??;
+            let Some(path) = picked else {
+                return Ok(None);
+            };
+            path
+        }
+    };
+
+    let summary = tauri::async_runtime::spawn_blocking(move || import::store()?.import_file(&path, &machine))
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))??;
+    core::invalidate_entries();
+    debug!(?summary, "import_usage");
+    Ok(Some(summary))
+}
+
+#[tauri::command]
+pub async fn get_imports() -> Result<Vec<ImportedMachine>, String> {
+    Ok(import::store()?.machines())
+}
+
+/// Deletes the usage imported for `machine`. Returns `false` when there was none.
+#[tauri::command]
+pub async fn remove_import(machine: String) -> Result<bool, String> {
+    let removed = import::store()?.remove(&machine)?;
+    core::invalidate_entries();
+    Ok(removed)
+}
+
+/// The outcome of the most recent folder sync, or `None` before the first or when sync is off
+#[tauri::command]
+pub async fn get_sync_status() -> Result<Option<SyncStatus>, String> {
+    Ok(crate::sync::status())
+}
+
+/// Writes this machine's snapshot to the sync folder and reads the other machines' now
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::sync::sync_now(&app))
+        .await
+        .map_err(|e| format!("Sync task failed: {}", e))?
+}
+
+/// Cancels a running scan by the `loadId` from its `load-progress` events, or every running
+/// scan when no id is given. Returns how many scans were cancelled.
+#[tauri::command]
+pub async fn cancel_load(load_id: Option<u64>, app: AppHandle) -> Result<usize, String> {
+    let cancelled = app.state::<core::ActiveLoads>().cancel(load_id);
+    debug!(?load_id, cancelled, "cancel_load");
+    Ok(cancelled)
+}
+
+/// Renders a report with the backend report engine and streams it to `path`, or where the
+/// user chooses when `path` is omitted. `format` defaults to the `defaultExportFormat`
+/// setting. With `team` set the report covers the team directory instead of this machine's
+/// usage. Returns the path written, or `None` when the save dialog was cancelled.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_report(
+    report: String,
+    format: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    path: Option<String>,
+    overwrite: Option<String>,
+    team: Option<bool>,
+    app: AppHandle,
+) -> Result<Option<String>, String> {
+    let report = ReportKind::parse(&report)?;
+    let format = ExportFormat::parse(format.as_deref().unwrap_or(&load_settings(&app).default_export_format))?;
+    let range = DateRange::parse(since.as_deref(), until.as_deref())?;
+    let default_filename = export::file_name(report, &range, format);
+    let Some((path, overwrite)) = core::export_destination(path, overwrite, &default_filename, &app).await? else {
+        return Ok(None);
+    };
+    let (entries, mode) = if team.unwrap_or(false) {
+        let (data, mode) = team_entries(since, until, mode, &app).await?;
+        (data.entries, mode)
+    } else {
+        report_entries("export_report", since, until, mode, &app).await?
+    };
+
+    let path = tauri::async_runtime::spawn_blocking(move || {
+        export::export_to_file(&path, overwrite, report, format, &range, &entries, mode, chrono::Utc::now())
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?
+    .map_err(|e| format!("Failed to write export: {}", e))?;
+    Ok(Some(path.to_string_lossy().to_string()))
 }
 
 #[tauri::command]
 pub async fn get_settings(app: AppHandle) -> Result<AppSettings, String> {
-    println!("[commands.rs] get_settings called");
     let store = get_settings_store(&app)?;
     let settings = AppSettings::from_store(&store);
-    println!("[commands.rs] get_settings returning: {:?}", settings);
+    // The settings hold the local API token, so only non-secret fields are logged
+    debug!(
+        cost_mode = %settings.cost_mode,
+        privacy_mode = settings.privacy_mode,
+        "get_settings returning"
+    );
     Ok(settings)
 }
 
 #[tauri::command]
 pub async fn save_settings(
-    settings: AppSettings,
+    mut settings: AppSettings,
     app: AppHandle,
 ) -> Result<(), String> {
+    // Privacy mode forbids custom data directories in the fs scope, so refuse ones like `/`
+    // or `$HOME` that would take the export directories with them
+    fs_scope::check_data_directories(&settings.custom_data_directories)?;
+    fs_scope::check_export_directories(&settings.export.allowed_directories)?;
+    crate::scheduler::check_jobs(&settings.scheduled_exports)?;
+    crate::sync::check_settings(&settings.sync)?;
+    team::check_settings(&settings.team)?;
+    crate::logging::check_settings(&settings.logging)?;
     let store = get_settings_store(&app)?;
+    let stored_token = AppSettings::from_store(&store).local_api.token;
+    crate::api::ensure_token(&mut settings.local_api, stored_token)?;
     settings.save_to_store(&store)?;
-    Ok(())
+    fs_scope::protect_data_directories(&app, settings.privacy_mode, &settings.custom_data_directories);
+
+    // The settings are saved, so every subsystem is updated even if one fails
+    let mut errors = Vec::new();
+    if let Err(e) = crate::logging::apply_settings(&settings.logging) {
+        errors.push(e);
+    }
+    // A busy port is reported without holding back the rest
+    if let Err(e) = crate::api::apply_settings(&app, &settings.local_api) {
+        errors.push(e);
+    }
+    file_monitor::apply_settings(&settings.file_monitor);
+    crate::archive::apply_settings(&settings.archive);
+    crate::scheduler::apply_settings(&settings.scheduled_exports);
+    crate::sync::apply_settings(&settings.sync);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Settings saved, but {}", errors.join("; ")))
+    }
+}
+
+#[tauri::command]
+pub async fn get_logs(
+    level: Option<String>,
+    target: Option<String>,
+    search: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    logging::recent_logs(level.as_deref(), target.as_deref(), search.as_deref(), limit)
+}
+
+/// Reports which Claude directories are watched, how, and any recent watcher error
+#[tauri::command]
+pub async fn get_watcher_health() -> Result<WatcherHealth, String> {
+    Ok(file_monitor::health())
 }
 
 #[tauri::command]
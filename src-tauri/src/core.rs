@@ -1,30 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
-use std::fs;
-use tauri::AppHandle;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, info};
+
+use crate::archive;
+use crate::export::OverwritePolicy;
+use crate::fs_scope;
+use crate::import;
+use crate::scanner::{CancelToken, ScanProgress, Scanner};
+use crate::settings::load_settings;
+use crate::sync;
+use crate::usage::UsageEntry;
 
 /// Discovers Claude data directories
 pub fn discover_claude_paths() -> Vec<PathBuf> {
+    discover_claude_paths_from(std::env::var("CLAUDE_CONFIG_DIR").ok().as_deref(), dirs::home_dir().as_deref())
+}
+
+/// Like `discover_claude_paths`, given the `CLAUDE_CONFIG_DIR` value and home directory
+pub fn discover_claude_paths_from(config_dir: Option<&str>, home: Option<&Path>) -> Vec<PathBuf> {
+    claude_path_candidates_from(config_dir, home)
+        .into_iter()
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Every location Claude data may live in, whether or not it exists yet
+pub fn claude_path_candidates() -> Vec<PathBuf> {
+    claude_path_candidates_from(std::env::var("CLAUDE_CONFIG_DIR").ok().as_deref(), dirs::home_dir().as_deref())
+}
+
+/// Like `claude_path_candidates`, given the `CLAUDE_CONFIG_DIR` value and home directory
+pub fn claude_path_candidates_from(config_dir: Option<&str>, home: Option<&Path>) -> Vec<PathBuf> {
     let mut paths = Vec::new();
     
     // Check CLAUDE_CONFIG_DIR environment variable
-    if let Ok(env_paths) = std::env::var("CLAUDE_CONFIG_DIR") {
-        for path in env_paths.split(',') {
-            let path = PathBuf::from(path.trim());
-            if path.exists() {
-                paths.push(path);
-            }
+    if let Some(env_paths) = config_dir {
+        for path in env_paths.split(',').map(str::trim).filter(|path| !path.is_empty()) {
+            paths.push(PathBuf::from(path));
         }
     }
     
     // Check default locations
-    if let Some(home) = dirs::home_dir() {
+    if let Some(home) = home {
         let default_paths = vec![
             home.join(".config/claude"),
             home.join(".claude"),
         ];
         
         for path in default_paths {
-            if path.exists() && !paths.contains(&path) {
+            if !paths.contains(&path) {
                 paths.push(path);
             }
         }
@@ -33,117 +63,348 @@ pub fn discover_claude_paths() -> Vec<PathBuf> {
     paths
 }
 
-/// Alias for file_monitor.rs compatibility
-pub fn get_claude_directories() -> Vec<PathBuf> {
-    discover_claude_paths()
-}
-
 /// Loads usage data from JSONL files
 pub async fn load_usage_data(
     since_date: Option<String>,
-    _app: &AppHandle,
+    app: &AppHandle,
 ) -> Result<Vec<String>, String> {
     let claude_paths = discover_claude_paths();
     
-    println!("[core.rs] Discovered Claude paths: {:?}", claude_paths);
+    debug!("Discovered Claude paths: {:?}", claude_paths);
     
     if claude_paths.is_empty() {
-        println!("[core.rs] No Claude data directories found. Returning empty array.");
+        info!("No Claude data directories found. Returning archived, imported and synced usage only.");
         // Return empty array instead of error to allow app to load
-        return Ok(Vec::new());
+        return Ok(archive::missing_transcript(&[], &stored_entries()).into_iter().collect());
     }
     
-    let mut all_files = Vec::new();
+    // TODO: Implement date filtering based on file modification time
+    let _ = since_date;
     
-    for claude_path in &claude_paths {
-        let projects_path = claude_path.join("projects");
-        println!("[core.rs] Checking projects path: {:?}", projects_path);
-        
-        if !projects_path.exists() {
-            println!("[core.rs] Projects path does not exist, skipping");
-            continue;
+    // A newer request for the same data makes any scan still running stale
+    let load = Load::begin(app, "load_usage_entries");
+    let scanner = load.scanner();
+    let privacy_mode = load_settings(app).privacy_mode;
+    let file_contents = tauri::async_runtime::spawn_blocking(move || {
+        if privacy_mode {
+            // Parsing keeps only usage fields, so no message text is held or sent
+            return scan(scanner, &claude_paths).map(|entries| usage_transcripts(&entries));
         }
-        
-        // Find all .jsonl files recursively
-        if let Ok(entries) = find_jsonl_files(&projects_path) {
-            println!("[core.rs] Found {} JSONL files in {:?}", entries.len(), projects_path);
-            all_files.extend(entries);
-        }
-    }
-    
-    println!("[core.rs] Total JSONL files found: {}", all_files.len());
+        let mut contents = scanner.read_transcripts(&claude_paths)?;
+        // Usage from transcripts Claude Code has since deleted lives on in the archive, and
+        // usage from other machines comes from imports and the sync folder
+        contents.extend(archive::missing_transcript(&contents, &stored_entries()));
+        Ok::<_, String>(contents)
+    })
+    .await
+    .map_err(|e| format!("Failed to read usage files: {}", e))??;
     
-    // Filter by date if provided
-    let filtered_files = if let Some(_date) = since_date {
-        // TODO: Implement date filtering based on file modification time
-        all_files
-    } else {
-        all_files
-    };
-    
-    // Read file contents
-    let mut file_contents = Vec::new();
-    for file_path in &filtered_files {
-        if let Ok(content) = fs::read_to_string(&file_path) {
-            file_contents.push(content);
-        } else {
-            println!("[core.rs] Failed to read file: {:?}", file_path);
+    debug!("Successfully read {} files", file_contents.len());
+    Ok(file_contents)
+}
+
+/// Renders entries as usage-only transcripts, one per session, in the shape the frontend parses
+pub fn usage_transcripts(entries: &[UsageEntry]) -> Vec<String> {
+    let mut sessions: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+    for entry in entries {
+        sessions
+            .entry((&entry.project, &entry.session_id))
+            .or_default()
+            .push(entry.to_transcript_line());
+    }
+    sessions.into_values().map(|lines| lines.join("\n")).collect()
+}
+
+/// Payload of the `load-progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadProgress {
+    pub load_id: u64,
+    /// The command that started the scan, e.g. `load_usage_entries`
+    pub command: String,
+    #[serde(flatten)]
+    pub progress: ScanProgress,
+}
+
+/// Scans in flight, keyed by load id, so stale ones can be cancelled
+#[derive(Default)]
+pub struct ActiveLoads {
+    next_id: AtomicU64,
+    loads: Mutex<HashMap<u64, (String, CancelToken)>>,
+}
+
+impl ActiveLoads {
+    /// Cancels the given load, or every load when `load_id` is `None`. Returns how many
+    /// were cancelled.
+    pub fn cancel(&self, load_id: Option<u64>) -> usize {
+        let loads = self.loads.lock().unwrap();
+        let mut cancelled = 0;
+        for (id, (_, token)) in loads.iter() {
+            if load_id.is_none_or(|load_id| load_id == *id) {
+                token.cancel();
+                cancelled += 1;
+            }
         }
+        cancelled
     }
-    
-    println!("[core.rs] Successfully read {} files", file_contents.len());
-    Ok(file_contents)
 }
 
-/// Recursively finds all JSONL files in a directory
-fn find_jsonl_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
-    let mut files = Vec::new();
-    
-    if dir.is_dir() {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.is_dir() {
-                if let Ok(mut nested) = find_jsonl_files(&path) {
-                    files.append(&mut nested);
+/// Registration of one running scan; dropping it removes the scan from `ActiveLoads`
+struct Load {
+    app: AppHandle,
+    id: u64,
+    command: String,
+    token: CancelToken,
+}
+
+impl Load {
+    /// Registers a scan under `key`, cancelling earlier scans with the same key
+    fn begin(app: &AppHandle, key: &str) -> Self {
+        let loads = app.state::<ActiveLoads>();
+        let id = loads.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let token = CancelToken::new();
+
+        let mut active = loads.loads.lock().unwrap();
+        for (stale_id, (stale_key, stale)) in active.iter() {
+            if stale_key == key {
+                debug!(load_id = stale_id, "Superseding {}", key);
+                stale.cancel();
+            }
+        }
+        active.insert(id, (key.to_string(), token.clone()));
+
+        Self {
+            app: app.clone(),
+            id,
+            command: key.to_string(),
+            token,
+        }
+    }
+
+    /// A scanner that honours this load's token and emits `load-progress` events
+    fn scanner(&self) -> Scanner {
+        let app = self.app.clone();
+        let load_id = self.id;
+        let command = self.command.clone();
+        let token = self.token.clone();
+        Scanner::new()
+            .cancel_token(self.token.clone())
+            .on_progress(move |progress| {
+                // A superseded scan stops reporting so it can't overwrite its replacement's progress
+                if token.is_cancelled() {
+                    return;
                 }
-            } else if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                files.push(path);
+                let _ = app.emit(
+                    "load-progress",
+                    LoadProgress {
+                        load_id,
+                        command: command.clone(),
+                        progress: progress.clone(),
+                    },
+                );
+            })
+    }
+}
+
+impl Drop for Load {
+    fn drop(&mut self) {
+        self.app.state::<ActiveLoads>().loads.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Health figures from the most recent call to `load_entries`
+#[derive(Debug, Clone, Default)]
+pub struct LoadStats {
+    pub files_scanned: usize,
+    pub parse_errors: usize,
+    pub duration: Duration,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+static LAST_LOAD: Mutex<Option<LoadStats>> = Mutex::new(None);
+
+/// Returns the stats recorded by the last completed scan, if any
+pub fn last_load_stats() -> Option<LoadStats> {
+    LAST_LOAD.lock().unwrap().clone()
+}
+
+/// The result of a full scan, reused until invalidated or older than `max_age`
+pub struct EntryCache {
+    max_age: Duration,
+    generation: AtomicU64,
+    cached: Mutex<Option<CachedScan>>,
+}
+
+struct CachedScan {
+    generation: u64,
+    loaded_at: Instant,
+    entries: Arc<Vec<UsageEntry>>,
+}
+
+impl EntryCache {
+    pub const fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            generation: AtomicU64::new(0),
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached entries, calling `load` when there are none or they are stale
+    pub fn get(&self, load: impl FnOnce() -> Vec<UsageEntry>) -> Arc<Vec<UsageEntry>> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        if let Some(cached) = &*self.cached.lock().unwrap() {
+            if cached.generation == generation && cached.loaded_at.elapsed() < self.max_age {
+                return cached.entries.clone();
             }
         }
+
+        let entries = Arc::new(load());
+        // A change during the scan may be missing from it, so it is used once but not kept
+        if self.generation.load(Ordering::SeqCst) == generation {
+            *self.cached.lock().unwrap() = Some(CachedScan {
+                generation,
+                loaded_at: Instant::now(),
+                entries: entries.clone(),
+            });
+        }
+        entries
     }
-    
-    Ok(files)
+
+    /// Drops the cached entries so the next `get` scans again
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        *self.cached.lock().unwrap() = None;
+    }
+}
+
+/// Caps how long a scan is reused, for when file watching is off and nothing invalidates it
+const ENTRY_CACHE_MAX_AGE: Duration = Duration::from_secs(60);
+
+static ENTRY_CACHE: EntryCache = EntryCache::new(ENTRY_CACHE_MAX_AGE);
+
+/// Entries from all Claude data directories, rescanned only after `invalidate_entries`
+/// or once the last scan is a minute old
+pub fn cached_entries() -> Arc<Vec<UsageEntry>> {
+    ENTRY_CACHE.get(load_entries)
+}
+
+/// Marks the cached entries stale after transcripts, imports or synced usage changed
+pub fn invalidate_entries() {
+    ENTRY_CACHE.invalidate();
+}
+
+/// Loads and parses usage entries from all Claude data directories, oldest first
+pub fn load_entries() -> Vec<UsageEntry> {
+    load_entries_from(&discover_claude_paths())
+}
+
+/// Loads and parses usage entries from the given Claude data directories, oldest first
+pub fn load_entries_from(claude_paths: &[PathBuf]) -> Vec<UsageEntry> {
+    // Without a cancel token the scan cannot fail
+    scan(Scanner::new(), claude_paths).unwrap_or_default()
+}
+
+/// Like `load_entries`, reporting progress to the UI and cancellable through `cancel_load`.
+/// A newer load with the same `key` cancels this one.
+pub fn load_entries_with_progress(app: &AppHandle, key: &str) -> Result<Vec<UsageEntry>, String> {
+    let load = Load::begin(app, key);
+    scan(load.scanner(), &discover_claude_paths())
+}
+
+fn scan(scanner: Scanner, claude_paths: &[PathBuf]) -> Result<Vec<UsageEntry>, String> {
+    let started = Instant::now();
+    let mut result = scanner.scan(claude_paths)?;
+    let stored = archive::merge(&mut result.entries, stored_entries());
+    if stored > 0 {
+        debug!(stored, "Merged archived, imported and synced usage");
+    }
+
+    *LAST_LOAD.lock().unwrap() = Some(LoadStats {
+        files_scanned: result.files_scanned,
+        parse_errors: result.parse_errors,
+        duration: started.elapsed(),
+        finished_at: Some(Utc::now()),
+    });
+
+    Ok(result.entries)
+}
+
+/// Archived, imported and synced records, deduplicated, oldest first
+fn stored_entries() -> Vec<UsageEntry> {
+    let mut stored = archive::archived_entries();
+    archive::merge(&mut stored, import::imported_entries());
+    archive::merge(&mut stored, sync::peer_entries());
+    stored
+}
+
+/// Finds all JSONL files below a directory, following symlinks without looping
+pub fn find_jsonl_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    Scanner::new()
+        .discover(&[dir.to_path_buf()])
+        .map_err(std::io::Error::other)
 }
 
-/// Exports data in the specified format
-pub async fn export_data(
-    _format: String,
-    content: String,
-    default_filename: String,
+/// Where an export goes. An explicit `path` must lie in an allowed export directory and
+/// defaults to failing when the file exists. Without one the user picks a file in the save
+/// dialog, which has already asked before replacing it. `None` means the dialog was cancelled.
+pub async fn export_destination(
+    path: Option<String>,
+    overwrite: Option<String>,
+    default_filename: &str,
     app: &AppHandle,
-) -> Result<(), String> {
+) -> Result<Option<(PathBuf, OverwritePolicy)>, String> {
+    let overwrite = overwrite.as_deref().map(OverwritePolicy::parse).transpose()?;
+    if let Some(path) = path {
+        let allowed = fs_scope::export_directories(&load_settings(app).export.allowed_directories);
+        let path = fs_scope::check_export_path(&path, &allowed)?;
+        return Ok(Some((path, overwrite.unwrap_or(OverwritePolicy::Fail))));
+    }
+
+    // The dialog blocks until the user answers, so keep it off the async runtime
+    let app = app.clone();
+    let default_filename = default_filename.to_string();
+    let picked = tauri::async_runtime::spawn_blocking(move || pick_save_path(&default_filename, &app))
+        .await
+        .map_err(|e| format!("Save dialog failed: {}", e))??;
+    Ok(picked.map(|path| (path, overwrite.unwrap_or(OverwritePolicy::Replace))))
+}
+
+/// Shows the save dialog and adds the chosen file to the fs plugin scope. Returns
+/// `None` when the dialog was cancelled. Blocks until the dialog closes.
+pub fn pick_save_path(default_filename: &str, app: &AppHandle) -> Result<Option<PathBuf>, String> {
     use tauri_plugin_dialog::DialogExt;
-    
-    let dialog = app.dialog();
-    
-    // Show save dialog
-    let file_handle = dialog
+
+    let file_handle = app
+        .dialog()
         .file()
-        .set_file_name(&default_filename)
+        .set_file_name(default_filename)
         .blocking_save_file();
-    
-    if let Some(file_path) = file_handle {
-        // Write content to file
-        match &file_path {
-            tauri_plugin_dialog::FilePath::Path(path) => {
-                fs::write(path, content)
-                    .map_err(|e| format!("Failed to save file: {}", e))?;
-            }
-            _ => return Err("Invalid file path".to_string()),
+
+    match file_handle {
+        Some(tauri_plugin_dialog::FilePath::Path(path)) => {
+            fs_scope::allow_export(app, &path);
+            Ok(Some(path))
         }
+        Some(_) => Err("Invalid file path".to_string()),
+        None => Ok(None),
     }
-    
-    Ok(())
-}
\ No newline at end of file
+}
+
+/// Shows the open dialog for a JSON or NDJSON file. Returns `None` when the dialog was
+/// cancelled. Blocks until the dialog closes.
+pub fn pick_open_path(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let file_handle = app
+        .dialog()
+        .file()
+        .add_filter("Usage data", &["json", "ndjson", "jsonl"])
+        .blocking_pick_file();
+
+    match file_handle {
+        Some(tauri_plugin_dialog::FilePath::Path(path)) => Ok(Some(path)),
+        Some(_) => Err("Invalid file path".to_string()),
+        None => Ok(None),
+    }
+}
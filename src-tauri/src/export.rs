@@ -0,0 +1,973 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+use crate::pricing::CostMode;
+use crate::reports::{self, DateRange, TokenTotals};
+use crate::usage::UsageEntry;
+
+mod columnar;
+mod xlsx;
+
+/// Most rows drawn in the charts of an HTML export
+const MAX_CHART_BARS: usize = 60;
+
+/// A report that can be exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportKind {
+    Daily,
+    Monthly,
+    Session,
+    Blocks,
+    Models,
+    Projects,
+    Machines,
+    Users,
+    /// Prompt cache efficiency per project
+    Cache,
+    /// Every usage entry, one row each
+    Entries,
+}
+
+impl ReportKind {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "daily" => Ok(ReportKind::Daily),
+            "monthly" => Ok(ReportKind::Monthly),
+            "session" | "sessions" => Ok(ReportKind::Session),
+            "blocks" | "block" => Ok(ReportKind::Blocks),
+            "models" | "model" => Ok(ReportKind::Models),
+            "projects" | "project" => Ok(ReportKind::Projects),
+            "machines" | "machine" => Ok(ReportKind::Machines),
+            "users" | "user" => Ok(ReportKind::Users),
+            "cache" => Ok(ReportKind::Cache),
+            "entries" | "entry" => Ok(ReportKind::Entries),
+            _ => Err(format!("Unknown report: {}", value)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ReportKind::Daily => "daily",
+            ReportKind::Monthly => "monthly",
+            ReportKind::Session => "session",
+            ReportKind::Blocks => "blocks",
+            ReportKind::Models => "models",
+            ReportKind::Projects => "projects",
+            ReportKind::Machines => "machines",
+            ReportKind::Users => "users",
+            ReportKind::Cache => "cache",
+            ReportKind::Entries => "entries",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            ReportKind::Daily => "Daily Usage",
+            ReportKind::Monthly => "Monthly Usage",
+            ReportKind::Session => "Session Usage",
+            ReportKind::Blocks => "Usage Blocks",
+            ReportKind::Models => "Usage by Model",
+            ReportKind::Projects => "Usage by Project",
+            ReportKind::Machines => "Usage by Machine",
+            ReportKind::Users => "Usage by User",
+            ReportKind::Cache => "Cache Efficiency by Project",
+            ReportKind::Entries => "Usage Entries",
+        }
+    }
+
+    /// Worksheet name in XLSX workbooks
+    fn sheet_name(self) -> &'static str {
+        match self {
+            ReportKind::Daily => "Daily",
+            ReportKind::Monthly => "Monthly",
+            ReportKind::Session => "Sessions",
+            ReportKind::Blocks => "Blocks",
+            ReportKind::Models => "Models",
+            ReportKind::Projects => "Projects",
+            ReportKind::Machines => "Machines",
+            ReportKind::Users => "Users",
+            ReportKind::Cache => "Cache",
+            ReportKind::Entries => "Entries",
+        }
+    }
+
+    /// Rows are periods of time, so the HTML export charts them
+    fn is_timeline(self) -> bool {
+        matches!(self, ReportKind::Daily | ReportKind::Monthly | ReportKind::Blocks)
+    }
+}
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Ndjson,
+    Markdown,
+    Html,
+    /// Excel workbook with one sheet per report
+    Xlsx,
+    Parquet,
+    /// Arrow IPC file
+    Arrow,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "ndjson" | "jsonl" => Ok(ExportFormat::Ndjson),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "html" | "htm" => Ok(ExportFormat::Html),
+            "xlsx" | "excel" => Ok(ExportFormat::Xlsx),
+            "parquet" => Ok(ExportFormat::Parquet),
+            "arrow" | "ipc" | "feather" => Ok(ExportFormat::Arrow),
+            _ => Err(format!("Unknown export format: {}", value)),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Arrow => "arrow",
+        }
+    }
+}
+
+/// Value type of a column, used for alignment and typed binary formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    List,
+    Date,
+    Count,
+    Cost,
+    /// A fraction between 0 and 1, shown as a percentage
+    Ratio,
+    /// A per-row average or rate, which is not summed into totals
+    Mean,
+    Time,
+    Flag,
+}
+
+impl ColumnKind {
+    fn is_numeric(self) -> bool {
+        matches!(self, ColumnKind::Count | ColumnKind::Cost | ColumnKind::Ratio | ColumnKind::Mean)
+    }
+}
+
+/// A typed table cell; each format decides how to render it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    /// A missing optional value
+    Null,
+    Text(String),
+    List(Vec<String>),
+    Date(NaiveDate),
+    Count(u64),
+    Cost(f64),
+    Ratio(f64),
+    Mean(f64),
+    Time(DateTime<Utc>),
+    Flag(bool),
+}
+
+impl Cell {
+    fn optional(value: Option<&str>) -> Self {
+        value.map_or(Cell::Null, |value| Cell::Text(value.to_string()))
+    }
+
+    /// Plain text used by CSV
+    fn plain(&self) -> String {
+        match self {
+            Cell::Null => String::new(),
+            Cell::Text(text) => text.clone(),
+            Cell::List(items) => items.join(", "),
+            Cell::Date(date) => date.format("%Y-%m-%d").to_string(),
+            Cell::Count(count) => count.to_string(),
+            Cell::Cost(cost) => format!("{:.6}", cost),
+            Cell::Ratio(ratio) => format!("{:.4}", ratio),
+            Cell::Mean(mean) => format!("{:.1}", mean),
+            Cell::Time(time) => time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            Cell::Flag(flag) => flag.to_string(),
+        }
+    }
+
+    /// Human-readable text used by Markdown and HTML
+    fn display(&self) -> String {
+        match self {
+            Cell::Count(count) => group_thousands(*count),
+            Cell::Cost(cost) => format!("${:.2}", cost),
+            Cell::Ratio(ratio) => format!("{:.1}%", ratio * 100.0),
+            Cell::Mean(mean) => {
+                let tenths = (mean * 10.0).round() as u64;
+                format!("{}.{}", group_thousands(tenths / 10), tenths % 10)
+            }
+            Cell::Time(time) => time.format("%Y-%m-%d %H:%M UTC").to_string(),
+            Cell::Flag(flag) => if *flag { "Yes" } else { "No" }.to_string(),
+            other => other.plain(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Cell::Null => Value::Null,
+            Cell::Text(text) => json!(text),
+            Cell::List(items) => json!(items),
+            Cell::Date(_) | Cell::Time(_) => json!(self.plain()),
+            Cell::Count(count) => json!(count),
+            Cell::Cost(cost) => json!((cost * 1e6).round() / 1e6),
+            Cell::Ratio(ratio) => json!((ratio * 1e4).round() / 1e4),
+            Cell::Mean(mean) => json!((mean * 10.0).round() / 10.0),
+            Cell::Flag(flag) => json!(flag),
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match self {
+            Cell::Count(count) => *count as f64,
+            Cell::Cost(value) | Cell::Ratio(value) | Cell::Mean(value) => *value,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    /// camelCase key, matching the report command field names
+    pub key: &'static str,
+    pub header: &'static str,
+    pub kind: ColumnKind,
+}
+
+const fn column(key: &'static str, header: &'static str, kind: ColumnKind) -> Column {
+    Column { key, header, kind }
+}
+
+const TOTAL_COLUMNS: [Column; 6] = [
+    column("inputTokens", "Input Tokens", ColumnKind::Count),
+    column("outputTokens", "Output Tokens", ColumnKind::Count),
+    column("cacheCreationTokens", "Cache Creation Tokens", ColumnKind::Count),
+    column("cacheReadTokens", "Cache Read Tokens", ColumnKind::Count),
+    column("totalTokens", "Total Tokens", ColumnKind::Count),
+    column("totalCost", "Total Cost (USD)", ColumnKind::Cost),
+];
+
+fn total_cells(totals: &TokenTotals) -> [Cell; 6] {
+    [
+        Cell::Count(totals.input_tokens),
+        Cell::Count(totals.output_tokens),
+        Cell::Count(totals.cache_creation_tokens),
+        Cell::Count(totals.cache_read_tokens),
+        Cell::Count(totals.total_tokens),
+        Cell::Cost(totals.total_cost),
+    ]
+}
+
+/// Rows of a table, produced lazily so large exports never hold every row at once
+pub type Rows<'a> = Box<dyn Iterator<Item = Vec<Cell>> + Send + 'a>;
+
+/// A report as rows of typed cells, shared by every export format
+pub struct Table<'a> {
+    pub title: String,
+    pub subtitle: String,
+    pub columns: Vec<Column>,
+    pub rows: Rows<'a>,
+    /// Whether rows are periods of time worth charting
+    pub timeline: bool,
+}
+
+fn column_index(columns: &[Column], key: &str) -> Option<usize> {
+    columns.iter().position(|column| column.key == key)
+}
+
+/// Running sums of the numeric columns, `None` for the others
+struct Totals(Vec<Option<Cell>>);
+
+impl Totals {
+    fn new(columns: &[Column]) -> Self {
+        Totals(
+            columns
+                .iter()
+                .map(|column| match column.kind {
+                    ColumnKind::Count => Some(Cell::Count(0)),
+                    ColumnKind::Cost => Some(Cell::Cost(0.0)),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    fn add(&mut self, row: &[Cell]) {
+        for (total, cell) in self.0.iter_mut().zip(row) {
+            match (total, cell) {
+                (Some(Cell::Count(sum)), Cell::Count(count)) => *sum += count,
+                (Some(Cell::Cost(sum)), Cell::Cost(cost)) => *sum += cost,
+                _ => {}
+            }
+        }
+    }
+
+    /// Footer labels: the sums, with "Total" in an otherwise empty first cell
+    fn labels(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(index, total)| match total {
+                Some(total) => total.display(),
+                None if index == 0 => "Total".to_string(),
+                None => String::new(),
+            })
+            .collect()
+    }
+}
+
+/// Describes a date range for report headings
+pub fn describe_range(range: &DateRange) -> String {
+    match (range.since, range.until) {
+        (Some(since), Some(until)) => format!("{} to {}", since, until),
+        (Some(since), None) => format!("Since {}", since),
+        (None, Some(until)) => format!("Until {}", until),
+        (None, None) => "All dates".to_string(),
+    }
+}
+
+/// Suggested file name, e.g. `claude-daily-report-2025-06-01-to-2025-06-30.csv`
+pub fn file_name(report: ReportKind, range: &DateRange, format: ExportFormat) -> String {
+    let mut name = format!("claude-{}-report", report.name());
+    if let Some(since) = range.since {
+        name.push_str(&format!("-{}", since));
+    }
+    if let Some(until) = range.until {
+        name.push_str(&format!("-to-{}", until));
+    }
+    format!("{}.{}", name, format.extension())
+}
+
+fn with_totals(mut row: Vec<Cell>, totals: &TokenTotals) -> Vec<Cell> {
+    row.extend(total_cells(totals));
+    row
+}
+
+/// Aggregates `entries` into the requested report with the shared report engine.
+/// Entry rows are read from `entries` as they are written, newest first.
+pub fn report_table<'a>(
+    report: ReportKind,
+    range: &DateRange,
+    entries: &'a [UsageEntry],
+    mode: CostMode,
+    now: DateTime<Utc>,
+) -> Table<'a> {
+    use ColumnKind::*;
+
+    let (mut columns, rows): (Vec<Column>, Rows<'a>) = match report {
+        ReportKind::Daily => (
+            vec![column("date", "Date", Date), column("models", "Models", List)],
+            Box::new(reports::daily_report(entries, mode).into_iter().map(|day| {
+                let date = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+                    .map_or_else(|_| Cell::Text(day.date), Cell::Date);
+                with_totals(vec![date, Cell::List(day.models)], &day.totals)
+            })),
+        ),
+        ReportKind::Monthly => (
+            vec![column("month", "Month", Text), column("models", "Models", List)],
+            Box::new(reports::monthly_report(entries, mode).into_iter().map(|month| {
+                with_totals(vec![Cell::Text(month.month), Cell::List(month.models)], &month.totals)
+            })),
+        ),
+        ReportKind::Session => (
+            vec![
+                column("sessionId", "Session", Text),
+                column("projectPath", "Project", Text),
+                column("models", "Models", List),
+            ],
+            Box::new(reports::session_report(entries, mode).into_iter().map(|session| {
+                let mut row = with_totals(
+                    vec![
+                        Cell::Text(session.session_id),
+                        Cell::Text(session.project_path),
+                        Cell::List(session.models),
+                    ],
+                    &session.totals,
+                );
+                row.push(Cell::Time(session.last_activity));
+                row.push(Cell::List(session.versions));
+                row
+            })),
+        ),
+        ReportKind::Blocks => (
+            vec![
+                column("startTime", "Start", Time),
+                column("endTime", "End", Time),
+                column("models", "Models", List),
+            ],
+            Box::new(reports::block_report(entries, mode, now).into_iter().map(|block| {
+                let mut row = with_totals(
+                    vec![Cell::Time(block.start_time), Cell::Time(block.end_time), Cell::List(block.models)],
+                    &block.totals,
+                );
+                row.push(Cell::Flag(block.is_active));
+                row
+            })),
+        ),
+        ReportKind::Models => (
+            vec![column("model", "Model", Text)],
+            Box::new(reports::model_report(entries, mode).into_iter().map(|model| {
+                let mut row = with_totals(vec![Cell::Text(model.model)], &model.totals);
+                row.push(Cell::Count(model.requests));
+                row.push(Cell::Ratio(model.cost_share));
+                row.push(Cell::Ratio(model.cache_hit_ratio));
+                row.push(Cell::Mean(model.average_tokens_per_request));
+                row
+            })),
+        ),
+        ReportKind::Projects => (
+            vec![column("projectPath", "Project", Text), column("models", "Models", List)],
+            Box::new(reports::project_report(entries, mode).into_iter().map(|project| {
+                let mut row =
+                    with_totals(vec![Cell::Text(project.project_path), Cell::List(project.models)], &project.totals);
+                row.push(Cell::Count(project.sessions as u64));
+                row.push(Cell::Time(project.last_activity));
+                row
+            })),
+        ),
+        ReportKind::Machines => (
+            vec![column("machine", "Machine", Text), column("models", "Models", List)],
+            Box::new(reports::machine_report(entries, mode).into_iter().map(|machine| {
+                let mut row = with_totals(vec![Cell::Text(machine.machine), Cell::List(machine.models)], &machine.totals);
+                row.push(Cell::Count(machine.sessions as u64));
+                row.push(Cell::Time(machine.last_activity));
+                row
+            })),
+        ),
+        ReportKind::Users => (
+            vec![
+                column("user", "User", Text),
+                column("machines", "Machines", List),
+                column("models", "Models", List),
+            ],
+            Box::new(reports::user_report(entries, mode).into_iter().map(|user| {
+                let mut row = with_totals(
+                    vec![Cell::Text(user.user), Cell::List(user.machines), Cell::List(user.models)],
+                    &user.totals,
+                );
+                row.push(Cell::Count(user.sessions as u64));
+                row.push(Cell::Count(user.projects as u64));
+                row.push(Cell::Time(user.last_activity));
+                row
+            })),
+        ),
+        ReportKind::Cache => (
+            vec![column("projectPath", "Project", Text)],
+            Box::new(reports::cache_report(entries, mode).projects.into_iter().map(|project| {
+                let cache = project.cache;
+                let mut row = with_totals(vec![Cell::Text(project.project_path)], &cache.totals);
+                row.push(Cell::Count(project.sessions as u64));
+                row.push(Cell::Count(project.poor_reuse_sessions as u64));
+                row.push(Cell::Ratio(cache.cache_read_ratio));
+                row.push(Cell::Mean(cache.write_amortization));
+                row.push(Cell::Cost(cache.uncached_cost));
+                row.push(Cell::Cost(cache.estimated_savings));
+                row
+            })),
+        ),
+        ReportKind::Entries => (
+            vec![
+                column("timestamp", "Timestamp", Time),
+                column("projectPath", "Project", Text),
+                column("sessionId", "Session", Text),
+                column("model", "Model", Text),
+            ],
+            Box::new(entries.iter().rev().map(move |entry| {
+                let mut totals = TokenTotals::default();
+                totals.add(entry, mode);
+                let mut row = with_totals(
+                    vec![
+                        Cell::Time(entry.timestamp),
+                        Cell::Text(entry.project.clone()),
+                        Cell::Text(entry.session_id.clone()),
+                        Cell::optional(entry.model.as_deref()),
+                    ],
+                    &totals,
+                );
+                row.push(Cell::optional(entry.message_id.as_deref()));
+                row.push(Cell::optional(entry.request_id.as_deref()));
+                row.push(Cell::optional(entry.version.as_deref()));
+                row
+            })),
+        ),
+    };
+
+    columns.extend(TOTAL_COLUMNS);
+    match report {
+        ReportKind::Session => columns.extend([
+            column("lastActivity", "Last Activity", Time),
+            column("versions", "Versions", List),
+        ]),
+        ReportKind::Blocks => columns.push(column("isActive", "Active", Flag)),
+        ReportKind::Models => columns.extend([
+            column("requests", "Requests", Count),
+            column("costShare", "Cost Share", Ratio),
+            column("cacheHitRatio", "Cache Hit Ratio", Ratio),
+            column("averageTokensPerRequest", "Avg Tokens / Request", Mean),
+        ]),
+        ReportKind::Projects | ReportKind::Machines => columns.extend([
+            column("sessions", "Sessions", Count),
+            column("lastActivity", "Last Activity", Time),
+        ]),
+        ReportKind::Users => columns.extend([
+            column("sessions", "Sessions", Count),
+            column("projects", "Projects", Count),
+            column("lastActivity", "Last Activity", Time),
+        ]),
+        ReportKind::Cache => columns.extend([
+            column("sessions", "Sessions", Count),
+            column("poorReuseSessions", "Poor Reuse Sessions", Count),
+            column("cacheReadRatio", "Cache Read Ratio", Ratio),
+            column("writeAmortization", "Reads per Cache Write", Mean),
+            column("uncachedCost", "Uncached Cost (USD)", Cost),
+            column("estimatedSavings", "Estimated Savings (USD)", Cost),
+        ]),
+        ReportKind::Entries => columns.extend([
+            column("messageId", "Message ID", Text),
+            column("requestId", "Request ID", Text),
+            column("version", "Version", Text),
+        ]),
+        _ => {}
+    }
+
+    Table {
+        title: report.title().to_string(),
+        subtitle: describe_range(range),
+        columns,
+        rows,
+        timeline: report.is_timeline(),
+    }
+}
+
+/// Renders a report straight into `out`, streaming rows as they are produced.
+/// XLSX workbooks carry the requested report plus the daily, monthly, model and
+/// project summaries, each on its own sheet.
+pub fn write_report<W: Write + Seek + Send>(
+    out: &mut W,
+    report: ReportKind,
+    format: ExportFormat,
+    range: &DateRange,
+    entries: &[UsageEntry],
+    mode: CostMode,
+    now: DateTime<Utc>,
+) -> io::Result<()> {
+    let table = || report_table(report, range, entries, mode, now);
+    match format {
+        ExportFormat::Xlsx => xlsx::write_workbook(out, report, range, entries, mode, now),
+        ExportFormat::Parquet => columnar::write_parquet(out, table()),
+        ExportFormat::Arrow => columnar::write_arrow(out, table()),
+        _ => write_table(out, table(), format, now),
+    }
+}
+
+/// Streams a report to `path` through `write_atomically`. Returns the path written.
+#[allow(clippy::too_many_arguments)]
+pub fn export_to_file(
+    path: &Path,
+    overwrite: OverwritePolicy,
+    report: ReportKind,
+    format: ExportFormat,
+    range: &DateRange,
+    entries: &[UsageEntry],
+    mode: CostMode,
+    now: DateTime<Utc>,
+) -> io::Result<PathBuf> {
+    write_atomically(path, overwrite, |out| write_report(out, report, format, range, entries, mode, now))
+}
+
+/// What to do when an export's destination already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    Fail,
+    Replace,
+    /// Write to the first free `name (n).ext` instead
+    Rename,
+}
+
+impl OverwritePolicy {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "fail" => Ok(OverwritePolicy::Fail),
+            "replace" => Ok(OverwritePolicy::Replace),
+            "rename" => Ok(OverwritePolicy::Rename),
+            _ => Err(format!("Unknown overwrite policy: {}", value)),
+        }
+    }
+}
+
+/// Most `name (n).ext` candidates tried by `OverwritePolicy::Rename`
+const MAX_RENAME_ATTEMPTS: u32 = 1000;
+
+fn numbered(path: &Path, n: u32) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{} ({}).{}", stem, n, extension.to_string_lossy()),
+        None => format!("{} ({})", stem, n),
+    };
+    path.with_file_name(name)
+}
+
+/// Writes a file into a temporary file in the destination directory, then renames it into
+/// place, so readers never see a partial export and a failed export leaves nothing behind.
+/// Returns the final path, which differs from `path` when `Rename` picked a free name.
+pub fn write_atomically(
+    path: &Path,
+    overwrite: OverwritePolicy,
+    write: impl FnOnce(&mut BufWriter<&mut File>) -> io::Result<()>,
+) -> io::Result<PathBuf> {
+    if overwrite == OverwritePolicy::Fail && path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", path.display()),
+        ));
+    }
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut temp = tempfile::Builder::new().prefix(".claude-deck-").suffix(".tmp").tempfile_in(dir)?;
+
+    let mut out = BufWriter::new(temp.as_file_mut());
+    write(&mut out)?;
+    out.into_inner().map_err(io::IntoInnerError::into_error)?.sync_all()?;
+
+    let persisted = match overwrite {
+        OverwritePolicy::Replace => temp.persist(path),
+        // Fails instead of replacing a file created since the check above
+        OverwritePolicy::Fail => temp.persist_noclobber(path),
+        OverwritePolicy::Rename => return persist_renamed(temp, path),
+    };
+    persisted.map(|_| path.to_path_buf()).map_err(|e| e.error)
+}
+
+/// Moves `temp` to `path`, or to the first `name (n).ext` that does not exist yet
+fn persist_renamed(mut temp: NamedTempFile, path: &Path) -> io::Result<PathBuf> {
+    let mut candidate = path.to_path_buf();
+    for n in 1..=MAX_RENAME_ATTEMPTS {
+        match temp.persist_noclobber(&candidate) {
+            Ok(_) => return Ok(candidate),
+            Err(e) if e.error.kind() == io::ErrorKind::AlreadyExists => {
+                temp = e.file;
+                candidate = numbered(path, n);
+            }
+            Err(e) => return Err(e.error),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("No free file name for {}", path.display()),
+    ))
+}
+
+/// Renders a table in one of the text formats
+pub fn write_table(out: &mut impl Write, table: Table, format: ExportFormat, now: DateTime<Utc>) -> io::Result<()> {
+    match format {
+        ExportFormat::Csv => write_csv(out, table),
+        ExportFormat::Json => write_json(out, table),
+        ExportFormat::Ndjson => write_ndjson(out, table),
+        ExportFormat::Markdown => write_markdown(out, table, now),
+        ExportFormat::Html => write_html(out, table, now),
+        other => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a text format", other.extension()),
+        )),
+    }
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break (RFC 4180)
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv(out: &mut impl Write, table: Table) -> io::Result<()> {
+    let header: Vec<String> = table.columns.iter().map(|column| csv_field(column.header)).collect();
+    write!(out, "{}\r\n", header.join(","))?;
+    for row in table.rows {
+        let fields: Vec<String> = row.iter().map(|cell| csv_field(&cell.plain())).collect();
+        write!(out, "{}\r\n", fields.join(","))?;
+    }
+    Ok(())
+}
+
+fn row_object(columns: &[Column], row: &[Cell]) -> Value {
+    let object: Map<String, Value> = columns
+        .iter()
+        .zip(row)
+        .map(|(column, cell)| (column.key.to_string(), cell.to_json()))
+        .collect();
+    Value::Object(object)
+}
+
+fn write_json(out: &mut impl Write, table: Table) -> io::Result<()> {
+    let mut first = true;
+    write!(out, "[")?;
+    for row in table.rows {
+        let object = serde_json::to_string_pretty(&row_object(&table.columns, &row))?;
+        write!(out, "{}\n  {}", if first { "" } else { "," }, object.replace('\n', "\n  "))?;
+        first = false;
+    }
+    writeln!(out, "{}]", if first { "" } else { "\n" })
+}
+
+fn write_ndjson(out: &mut impl Write, table: Table) -> io::Result<()> {
+    for row in table.rows {
+        serde_json::to_writer(&mut *out, &row_object(&table.columns, &row))?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn write_markdown(out: &mut impl Write, table: Table, now: DateTime<Utc>) -> io::Result<()> {
+    writeln!(out, "# {}", table.title)?;
+    writeln!(out)?;
+    writeln!(out, "{} · generated {}", table.subtitle, now.format("%Y-%m-%d %H:%M UTC"))?;
+    writeln!(out)?;
+
+    let headers: Vec<String> = table.columns.iter().map(|column| markdown_cell(column.header)).collect();
+    writeln!(out, "| {} |", headers.join(" | "))?;
+    let alignment: Vec<&str> = table
+        .columns
+        .iter()
+        .map(|column| if column.kind.is_numeric() { "---:" } else { "---" })
+        .collect();
+    writeln!(out, "| {} |", alignment.join(" | "))?;
+
+    let mut totals = Totals::new(&table.columns);
+    let mut empty = true;
+    for row in table.rows {
+        totals.add(&row);
+        empty = false;
+        let cells: Vec<String> = row.iter().map(|cell| markdown_cell(&cell.display())).collect();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    if !empty {
+        let labels: Vec<String> = totals
+            .labels()
+            .into_iter()
+            .map(|label| if label.is_empty() { label } else { format!("**{}**", label) })
+            .collect();
+        writeln!(out, "| {} |", labels.join(" | "))?;
+    }
+    Ok(())
+}
+
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;margin:2rem;color:#1f2328}\
+h1{margin-bottom:.25rem}.muted{color:#656d76}\
+.summary{display:flex;gap:2rem;margin:1.5rem 0}.summary div{font-size:1.5rem;font-weight:600}\
+.summary span{display:block;font-size:.8rem;font-weight:400;color:#656d76}\
+figure{margin:1.5rem 0}figcaption{font-weight:600;margin-bottom:.5rem}\
+.legend span{margin-right:1rem;font-size:.8rem}.legend i{display:inline-block;width:.7rem;height:.7rem;margin-right:.3rem}\
+table{border-collapse:collapse;width:100%;font-size:.85rem}th,td{padding:.4rem .6rem;border-bottom:1px solid #d0d7de;text-align:left}\
+td.num,th.num{text-align:right;font-variant-numeric:tabular-nums}tfoot td{font-weight:600}";
+
+/// One stacked series of an SVG bar chart
+struct Series<'a> {
+    name: &'a str,
+    color: &'a str,
+    values: Vec<f64>,
+}
+
+/// Renders a stacked bar chart as inline SVG, one bar per label
+fn svg_bar_chart(labels: &[String], series: &[Series]) -> String {
+    let (width, height, bottom) = (800.0, 220.0, 20.0);
+    let totals: Vec<f64> = (0..labels.len())
+        .map(|index| series.iter().map(|s| s.values[index]).sum())
+        .collect();
+    let max = totals.iter().cloned().fold(0.0, f64::max);
+    if labels.is_empty() || max <= 0.0 {
+        return String::new();
+    }
+
+    let slot = width / labels.len() as f64;
+    let bar = (slot * 0.8).max(1.0);
+    let scale = (height - bottom) / max;
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {} {}\" width=\"100%\" role=\"img\" xmlns=\"http://www.w3.org/2000/svg\">",
+        width, height
+    );
+    for (index, label) in labels.iter().enumerate() {
+        let x = index as f64 * slot + (slot - bar) / 2.0;
+        let mut y = height - bottom;
+        for s in series {
+            let h = s.values[index] * scale;
+            y -= h;
+            svg.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{} · {}</title></rect>",
+                x, y, bar, h, s.color, escape_html(label), escape_html(s.name)
+            ));
+        }
+    }
+    // Label the first and last bars so the time axis is readable
+    let last = labels.len() - 1;
+    svg.push_str(&format!(
+        "<text x=\"0\" y=\"{}\" font-size=\"11\" fill=\"#656d76\">{}</text>",
+        height - 4.0,
+        escape_html(&labels[0])
+    ));
+    if last > 0 {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"11\" fill=\"#656d76\" text-anchor=\"end\">{}</text>",
+            width,
+            height - 4.0,
+            escape_html(&labels[last])
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Values of a numeric column for the charted rows, oldest first
+fn chart_values(columns: &[Column], rows: &[Vec<Cell>], key: &str) -> Vec<f64> {
+    let Some(index) = column_index(columns, key) else {
+        return Vec::new();
+    };
+    rows.iter().take(MAX_CHART_BARS).rev().map(|row| row[index].as_f64()).collect()
+}
+
+fn write_html(out: &mut impl Write, table: Table, now: DateTime<Utc>) -> io::Result<()> {
+    // The summary and charts precede the table, so HTML is the one format that holds every row
+    let rows: Vec<Vec<Cell>> = table.rows.collect();
+    let mut totals = Totals::new(&table.columns);
+    rows.iter().for_each(|row| totals.add(row));
+    let columns = &table.columns;
+
+    let title = escape_html(&table.title);
+    write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>ClaudeDeck · {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        title, HTML_STYLE
+    )?;
+    writeln!(out, "<h1>{}</h1>", title)?;
+    writeln!(
+        out,
+        "<p class=\"muted\">{} · generated {}</p>",
+        escape_html(&table.subtitle),
+        now.format("%Y-%m-%d %H:%M UTC")
+    )?;
+
+    let total = |key: &str| column_index(columns, key).and_then(|index| totals.0[index].clone());
+    writeln!(out, "<section class=\"summary\">")?;
+    if let Some(cost) = total("totalCost") {
+        writeln!(out, "<div>{}<span>Total cost</span></div>", cost.display())?;
+    }
+    if let Some(tokens) = total("totalTokens") {
+        writeln!(out, "<div>{}<span>Total tokens</span></div>", tokens.display())?;
+    }
+    writeln!(out, "<div>{}<span>Rows</span></div>", rows.len())?;
+    writeln!(out, "</section>")?;
+
+    if table.timeline {
+        let labels: Vec<String> = rows
+            .iter()
+            .take(MAX_CHART_BARS)
+            .rev()
+            .map(|row| row[0].display())
+            .collect();
+        let cost_chart = svg_bar_chart(
+            &labels,
+            &[Series {
+                name: "Cost",
+                color: "#8250df",
+                values: chart_values(columns, &rows, "totalCost"),
+            }],
+        );
+        if !cost_chart.is_empty() {
+            writeln!(out, "<figure><figcaption>Cost</figcaption>{}</figure>", cost_chart)?;
+        }
+
+        let token_series = [
+            Series { name: "Input", color: "#0969da", values: chart_values(columns, &rows, "inputTokens") },
+            Series { name: "Output", color: "#1a7f37", values: chart_values(columns, &rows, "outputTokens") },
+            Series { name: "Cache creation", color: "#bf8700", values: chart_values(columns, &rows, "cacheCreationTokens") },
+            Series { name: "Cache read", color: "#cf222e", values: chart_values(columns, &rows, "cacheReadTokens") },
+        ];
+        let token_chart = svg_bar_chart(&labels, &token_series);
+        if !token_chart.is_empty() {
+            let legend: String = token_series
+                .iter()
+                .map(|s| format!("<span><i style=\"background:{}\"></i>{}</span>", s.color, s.name))
+                .collect();
+            writeln!(
+                out,
+                "<figure><figcaption>Tokens</figcaption><div class=\"legend\">{}</div>{}</figure>",
+                legend, token_chart
+            )?;
+        }
+    }
+
+    let class = |index: usize| if columns[index].kind.is_numeric() { " class=\"num\"" } else { "" };
+
+    writeln!(out, "<table>\n<thead><tr>")?;
+    for (index, column) in columns.iter().enumerate() {
+        write!(out, "<th{}>{}</th>", class(index), escape_html(column.header))?;
+    }
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+    for row in &rows {
+        write!(out, "<tr>")?;
+        for (index, cell) in row.iter().enumerate() {
+            write!(out, "<td{}>{}</td>", class(index), escape_html(&cell.display()))?;
+        }
+        writeln!(out, "</tr>")?;
+    }
+    writeln!(out, "</tbody>")?;
+    if !rows.is_empty() {
+        write!(out, "<tfoot><tr>")?;
+        for (index, label) in totals.labels().iter().enumerate() {
+            write!(out, "<td{}>{}</td>", class(index), escape_html(label))?;
+        }
+        writeln!(out, "</tr></tfoot>")?;
+    }
+    writeln!(out, "</table>\n</body>\n</html>")
+}
+
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
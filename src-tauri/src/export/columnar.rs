@@ -0,0 +1,160 @@
+use arrow_array::builder::{
+    ArrayBuilder, BooleanBuilder, Date32Builder, Float64Builder, ListBuilder, StringBuilder,
+    TimestampMillisecondBuilder, UInt64Builder,
+};
+use arrow_array::RecordBatch;
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, NaiveDate};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use super::{Cell, Column, ColumnKind, Table};
+
+/// Rows buffered before a record batch is handed to the writer
+const BATCH_ROWS: usize = 8192;
+
+fn data_type(kind: ColumnKind) -> DataType {
+    match kind {
+        ColumnKind::Text => DataType::Utf8,
+        ColumnKind::List => DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+        ColumnKind::Date => DataType::Date32,
+        ColumnKind::Count => DataType::UInt64,
+        ColumnKind::Cost | ColumnKind::Ratio | ColumnKind::Mean => DataType::Float64,
+        ColumnKind::Time => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        ColumnKind::Flag => DataType::Boolean,
+    }
+}
+
+fn schema(columns: &[Column]) -> SchemaRef {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|column| Field::new(column.key, data_type(column.kind), true))
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+fn days_since_epoch(date: &NaiveDate) -> i32 {
+    (*date - DateTime::UNIX_EPOCH.date_naive()).num_days() as i32
+}
+
+/// Column builder for one record batch
+enum Builder {
+    Text(StringBuilder),
+    List(ListBuilder<StringBuilder>),
+    Date(Date32Builder),
+    Count(UInt64Builder),
+    Cost(Float64Builder),
+    Ratio(Float64Builder),
+    Mean(Float64Builder),
+    Time(TimestampMillisecondBuilder),
+    Flag(BooleanBuilder),
+}
+
+impl Builder {
+    fn new(kind: ColumnKind) -> Self {
+        match kind {
+            ColumnKind::Text => Builder::Text(StringBuilder::new()),
+            ColumnKind::List => Builder::List(ListBuilder::new(StringBuilder::new())),
+            ColumnKind::Date => Builder::Date(Date32Builder::new()),
+            ColumnKind::Count => Builder::Count(UInt64Builder::new()),
+            ColumnKind::Cost => Builder::Cost(Float64Builder::new()),
+            ColumnKind::Ratio => Builder::Ratio(Float64Builder::new()),
+            ColumnKind::Mean => Builder::Mean(Float64Builder::new()),
+            ColumnKind::Time => Builder::Time(TimestampMillisecondBuilder::new().with_timezone("UTC")),
+            ColumnKind::Flag => Builder::Flag(BooleanBuilder::new()),
+        }
+    }
+
+    /// Appends a cell, storing null when it does not match the column type
+    fn append(&mut self, cell: &Cell) {
+        match (self, cell) {
+            (Builder::Text(builder), Cell::Text(text)) => builder.append_value(text),
+            (Builder::Text(builder), _) => builder.append_null(),
+            (Builder::List(builder), Cell::List(items)) => {
+                items.iter().for_each(|item| builder.values().append_value(item));
+                builder.append(true);
+            }
+            (Builder::List(builder), _) => builder.append(false),
+            (Builder::Date(builder), Cell::Date(date)) => builder.append_value(days_since_epoch(date)),
+            (Builder::Date(builder), _) => builder.append_null(),
+            (Builder::Count(builder), Cell::Count(count)) => builder.append_value(*count),
+            (Builder::Count(builder), _) => builder.append_null(),
+            (Builder::Cost(builder), Cell::Cost(cost)) => builder.append_value(*cost),
+            (Builder::Cost(builder), _) => builder.append_null(),
+            (Builder::Ratio(builder), Cell::Ratio(ratio)) => builder.append_value(*ratio),
+            (Builder::Ratio(builder), _) => builder.append_null(),
+            (Builder::Mean(builder), Cell::Mean(mean)) => builder.append_value(*mean),
+            (Builder::Mean(builder), _) => builder.append_null(),
+            (Builder::Time(builder), Cell::Time(time)) => builder.append_value(time.timestamp_millis()),
+            (Builder::Time(builder), _) => builder.append_null(),
+            (Builder::Flag(builder), Cell::Flag(flag)) => builder.append_value(*flag),
+            (Builder::Flag(builder), _) => builder.append_null(),
+        }
+    }
+
+    fn builder(&mut self) -> &mut dyn ArrayBuilder {
+        match self {
+            Builder::Text(builder) => builder,
+            Builder::List(builder) => builder,
+            Builder::Date(builder) => builder,
+            Builder::Count(builder) => builder,
+            Builder::Cost(builder) => builder,
+            Builder::Ratio(builder) => builder,
+            Builder::Mean(builder) => builder,
+            Builder::Time(builder) => builder,
+            Builder::Flag(builder) => builder,
+        }
+    }
+}
+
+/// Streams the table rows as record batches of at most `BATCH_ROWS` rows
+fn write_batches(
+    table: Table,
+    schema: &SchemaRef,
+    mut write: impl FnMut(RecordBatch) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut builders: Vec<Builder> = table.columns.iter().map(|column| Builder::new(column.kind)).collect();
+    let mut flush = |builders: &mut [Builder]| -> io::Result<()> {
+        let arrays = builders.iter_mut().map(|builder| builder.builder().finish()).collect();
+        write(RecordBatch::try_new(schema.clone(), arrays).map_err(io::Error::other)?)
+    };
+
+    let mut buffered = 0;
+    for row in table.rows {
+        builders.iter_mut().zip(&row).for_each(|(builder, cell)| builder.append(cell));
+        buffered += 1;
+        if buffered == BATCH_ROWS {
+            flush(&mut builders)?;
+            buffered = 0;
+        }
+    }
+    if buffered > 0 {
+        flush(&mut builders)?;
+    }
+    Ok(())
+}
+
+/// Writes the table as a Snappy-compressed Parquet file
+pub(super) fn write_parquet<W: Write + Send>(out: &mut W, table: Table) -> io::Result<()> {
+    let schema = schema(&table.columns);
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_created_by(format!("ClaudeDeck {}", env!("CARGO_PKG_VERSION")))
+        .build();
+    let mut writer = ArrowWriter::try_new(out, schema.clone(), Some(properties)).map_err(io::Error::other)?;
+    write_batches(table, &schema, |batch| writer.write(&batch).map_err(io::Error::other))?;
+    writer.close().map_err(io::Error::other)?;
+    Ok(())
+}
+
+/// Writes the table as an Arrow IPC file
+pub(super) fn write_arrow<W: Write>(out: &mut W, table: Table) -> io::Result<()> {
+    let schema = schema(&table.columns);
+    let mut writer = FileWriter::try_new(out, &schema).map_err(io::Error::other)?;
+    write_batches(table, &schema, |batch| writer.write(&batch).map_err(io::Error::other))?;
+    writer.finish().map_err(io::Error::other)
+}
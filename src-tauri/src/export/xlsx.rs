@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use rust_xlsxwriter::{Format, FormatAlign, Workbook, Worksheet, XlsxError};
+use std::io::{self, Seek, Write};
+
+use super::{report_table, Cell, ColumnKind, ReportKind, Table};
+use crate::pricing::CostMode;
+use crate::reports::DateRange;
+use crate::usage::UsageEntry;
+
+/// Summary sheets every workbook carries after the requested report
+const SUMMARY_SHEETS: [ReportKind; 4] = [
+    ReportKind::Daily,
+    ReportKind::Monthly,
+    ReportKind::Models,
+    ReportKind::Projects,
+];
+
+/// Last row index Excel accepts
+const MAX_ROW: u32 = 1_048_575;
+
+/// Cell formats shared by every sheet
+struct Formats {
+    header: Format,
+    count: Format,
+    mean: Format,
+    cost: Format,
+    ratio: Format,
+    date: Format,
+    time: Format,
+}
+
+impl Formats {
+    fn new() -> Self {
+        Self {
+            header: Format::new().set_bold(),
+            count: Format::new().set_num_format("#,##0"),
+            mean: Format::new().set_num_format("#,##0.0"),
+            cost: Format::new().set_num_format("$#,##0.00"),
+            ratio: Format::new().set_num_format("0.0%"),
+            date: Format::new().set_num_format("yyyy-mm-dd").set_align(FormatAlign::Left),
+            time: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss").set_align(FormatAlign::Left),
+        }
+    }
+}
+
+fn xlsx_error(error: XlsxError) -> io::Error {
+    io::Error::other(error)
+}
+
+fn column_width(kind: ColumnKind) -> f64 {
+    match kind {
+        ColumnKind::Text => 28.0,
+        ColumnKind::List => 36.0,
+        ColumnKind::Date => 12.0,
+        ColumnKind::Count | ColumnKind::Cost | ColumnKind::Ratio | ColumnKind::Mean => 16.0,
+        ColumnKind::Time => 20.0,
+        ColumnKind::Flag => 8.0,
+    }
+}
+
+/// Writes the requested report and the summary reports as sheets of one workbook.
+/// Sheets use constant-memory mode, so rows are flushed to disk as they are written.
+pub(super) fn write_workbook<W: Write + Seek + Send>(
+    out: &mut W,
+    report: ReportKind,
+    range: &DateRange,
+    entries: &[UsageEntry],
+    mode: CostMode,
+    now: DateTime<Utc>,
+) -> io::Result<()> {
+    let formats = Formats::new();
+    let mut workbook = Workbook::new();
+
+    let mut sheets = vec![report];
+    sheets.extend(SUMMARY_SHEETS.iter().filter(|&&kind| kind != report));
+    for kind in sheets {
+        let worksheet = workbook.add_worksheet_with_constant_memory();
+        worksheet.set_name(kind.sheet_name()).map_err(xlsx_error)?;
+        write_sheet(worksheet, report_table(kind, range, entries, mode, now), &formats)?;
+    }
+
+    workbook.save_to_writer(out).map_err(xlsx_error)
+}
+
+fn write_sheet(worksheet: &mut Worksheet, table: Table, formats: &Formats) -> io::Result<()> {
+    for (index, column) in table.columns.iter().enumerate() {
+        let index = index as u16;
+        worksheet
+            .set_column_width(index, column_width(column.kind))
+            .and_then(|worksheet| worksheet.write_string_with_format(0, index, column.header, &formats.header))
+            .map_err(xlsx_error)?;
+    }
+    worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+
+    for (row, cells) in (1..).zip(table.rows) {
+        if row > MAX_ROW {
+            return Err(io::Error::other("Report has more rows than an Excel worksheet can hold"));
+        }
+        for (col, cell) in (0..).zip(&cells) {
+            write_cell(worksheet, row, col, cell, formats).map_err(xlsx_error)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_cell(worksheet: &mut Worksheet, row: u32, col: u16, cell: &Cell, formats: &Formats) -> Result<(), XlsxError> {
+    match cell {
+        Cell::Null => return Ok(()),
+        Cell::Text(text) => worksheet.write_string(row, col, text)?,
+        Cell::List(items) => worksheet.write_string(row, col, items.join(", "))?,
+        Cell::Date(date) => worksheet.write_datetime_with_format(row, col, date, &formats.date)?,
+        Cell::Count(count) => worksheet.write_number_with_format(row, col, *count as f64, &formats.count)?,
+        Cell::Cost(cost) => worksheet.write_number_with_format(row, col, *cost, &formats.cost)?,
+        Cell::Ratio(ratio) => worksheet.write_number_with_format(row, col, *ratio, &formats.ratio)?,
+        Cell::Mean(mean) => worksheet.write_number_with_format(row, col, *mean, &formats.mean)?,
+        Cell::Time(time) => worksheet.write_datetime_with_format(row, col, time.naive_utc(), &formats.time)?,
+        Cell::Flag(flag) => worksheet.write_boolean(row, col, *flag)?,
+    };
+    Ok(())
+}
@@ -1,76 +1,709 @@
-use notify::{Watcher, RecursiveMode, Event, EventKind};
-use std::sync::mpsc::channel;
+use chrono::{DateTime, Utc};
+use notify::{Watcher, RecursiveMode, Event, EventKind, PollWatcher, RecommendedWatcher};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
 
-#[derive(Clone, serde::Serialize)]
-struct FileChangeEvent {
-    path: String,
-    kind: String,
+use crate::settings::{load_settings, FileMonitorSettings};
+
+/// A path that keeps changing is still flushed after this many debounce windows
+const MAX_WAIT_WINDOWS: u32 = 4;
+
+static DEBOUNCE_MS: AtomicU64 = AtomicU64::new(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
 }
 
-pub fn init(app: AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // Get Claude data directories
-    let directories = crate::core::get_claude_directories();
-    
-    if directories.is_empty() {
-        println!("No Claude directories found for monitoring");
-        return Ok(());
-    }
-    
-    // Create a channel to receive file system events
-    let (tx, rx) = channel();
-    
-    // Create a watcher
-    let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
-        if let Ok(event) = res {
-            let _ = tx.send(event);
+/// Payload of the `files-changed` event: every JSONL path that changed within one
+/// debounce window, grouped by kind
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeBatch {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl FileChangeBatch {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+struct Pending {
+    kind: ChangeKind,
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+/// Size and modification time used to drop events for files that did not actually change
+type Stamp = (u64, Option<SystemTime>);
+
+/// Merges raw watcher events per path until the path has been quiet for a window
+#[derive(Default)]
+pub struct Debouncer {
+    pending: HashMap<PathBuf, Pending>,
+    stamps: HashMap<PathBuf, Stamp>,
+}
+
+impl Debouncer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, path: PathBuf, kind: ChangeKind, now: Instant) {
+        let Some(pending) = self.pending.get_mut(&path) else {
+            self.pending.insert(
+                path,
+                Pending {
+                    kind,
+                    first_seen: now,
+                    last_seen: now,
+                },
+            );
+            return;
+        };
+
+        let merged = match (pending.kind, kind) {
+            // Created and deleted within the window: nothing to report
+            (ChangeKind::Created, ChangeKind::Removed) => None,
+            (ChangeKind::Created, _) => Some(ChangeKind::Created),
+            (ChangeKind::Removed, ChangeKind::Created | ChangeKind::Modified) => Some(ChangeKind::Modified),
+            (_, kind) => Some(kind),
+        };
+
+        match merged {
+            Some(kind) => {
+                pending.kind = kind;
+                pending.last_seen = now;
+            }
+            None => {
+                self.pending.remove(&path);
+            }
+        }
+    }
+
+    /// When the oldest pending path becomes due, if anything is pending
+    pub fn next_deadline(&self, window: Duration) -> Option<Instant> {
+        self.pending
+            .values()
+            .map(|pending| due_at(pending, window))
+            .min()
+    }
+
+    /// Takes every path that has been quiet for `window`, or pending too long, and drops
+    /// those whose size and mtime match what was last reported
+    pub fn flush(&mut self, window: Duration, now: Instant) -> FileChangeBatch {
+        let due: Vec<(PathBuf, ChangeKind)> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| due_at(pending, window) <= now)
+            .map(|(path, pending)| (path.clone(), pending.kind))
+            .collect();
+
+        let mut batch = FileChangeBatch::default();
+        for (path, kind) in due {
+            self.pending.remove(&path);
+
+            let Some(stamp) = stamp(&path) else {
+                // Only report removals of files we reported before, or saw removed directly
+                if self.stamps.remove(&path).is_some() || kind == ChangeKind::Removed {
+                    batch.removed.push(path.to_string_lossy().to_string());
+                }
+                continue;
+            };
+
+            if self.stamps.insert(path.clone(), stamp) == Some(stamp) {
+                continue;
+            }
+
+            let list = match kind {
+                ChangeKind::Created => &mut batch.created,
+                // A removal followed by a file at the same path is a replacement
+                ChangeKind::Modified | ChangeKind::Removed => &mut batch.modified,
+            };
+            list.push(path.to_string_lossy().to_string());
+        }
+
+        batch.created.sort();
+        batch.modified.sort();
+        batch.removed.sort();
+        batch
+    }
+}
+
+fn due_at(pending: &Pending, window: Duration) -> Instant {
+    (pending.last_seen + window).min(pending.first_seen + window * MAX_WAIT_WINDOWS)
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()))
+}
+
+/// Applies new monitor settings. The debounce window takes effect immediately; a change
+/// to polling re-establishes the watches.
+pub fn apply_settings(settings: &FileMonitorSettings) {
+    DEBOUNCE_MS.store(settings.debounce_ms, Ordering::Relaxed);
+    let poll_ms = settings.poll_interval_secs.max(1) * 1000;
+    let changed = FORCE_POLLING.swap(settings.force_polling, Ordering::Relaxed) != settings.force_polling
+        || POLL_INTERVAL_MS.swap(poll_ms, Ordering::Relaxed) != poll_ms;
+
+    if changed {
+        if let Some(control) = CONTROL.get() {
+            let _ = control.send(Signal::Reconfigure);
+        }
+    }
+}
+
+fn debounce_window() -> Duration {
+    Duration::from_millis(DEBOUNCE_MS.load(Ordering::Relaxed))
+}
+
+/// Current watcher state for `get_watcher_health`
+pub fn health() -> WatcherHealth {
+    HEALTH.lock().unwrap().clone().unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatcherState {
+    /// Every Claude directory has a native watch
+    Watching,
+    /// At least one directory is polled or failed to watch
+    Degraded,
+    /// No Claude directory exists yet; parents are watched for one to appear
+    Waiting,
+    #[default]
+    Stopped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchMode {
+    Native,
+    Poll,
+    /// Watching the nearest existing parent of a missing Claude directory
+    Parent,
+    /// The inotify watch limit was hit: recent projects are watched natively and the
+    /// whole directory is polled
+    Limited,
+    Failed,
+}
+
+/// Diagnostic shown in the UI when the inotify watch limit forced a degraded mode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchLimit {
+    /// `fs.inotify.max_user_watches`, when it can be read
+    pub max_user_watches: Option<u64>,
+    pub recent_projects: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStatus {
+    pub path: String,
+    pub mode: WatchMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherHealth {
+    pub state: WatcherState,
+    pub watches: Vec<WatchStatus>,
+    pub debounce_ms: u64,
+    /// Times the watches were rebuilt after an error, a directory appearing or vanishing,
+    /// or a settings change
+    pub restarts: u32,
+    pub last_event_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch_limit: Option<WatchLimit>,
+}
+
+enum Signal {
+    Event(Event),
+    Error(notify::Error),
+    Reconfigure,
+}
+
+/// How often watched directories are checked for having vanished or appeared
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Minimum gap between rebuilding watches after an error
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Projects active within this period keep native watches when the inotify limit is hit
+const RECENT_PROJECT_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Cap on natively watched projects in limited mode, leaving watches for other apps
+const MAX_RECENT_PROJECTS: usize = 32;
+
+static FORCE_POLLING: AtomicBool = AtomicBool::new(false);
+static POLL_INTERVAL_MS: AtomicU64 = AtomicU64::new(5000);
+static CONTROL: OnceLock<Sender<Signal>> = OnceLock::new();
+static HEALTH: Mutex<Option<WatcherHealth>> = Mutex::new(None);
+
+/// The watchers currently installed, kept alive for as long as they should deliver events
+struct Watches {
+    _native: Option<RecommendedWatcher>,
+    _poll: Option<PollWatcher>,
+    /// Claude directories with a recursive watch
+    targets: Vec<PathBuf>,
+    /// Claude directories that do not exist yet
+    missing: Vec<PathBuf>,
+    status: Vec<WatchStatus>,
+    watch_limit: Option<WatchLimit>,
+}
+
+impl Watches {
+    fn establish(tx: &Sender<Signal>) -> Self {
+        let force_polling = FORCE_POLLING.load(Ordering::Relaxed);
+        let handler = |tx: Sender<Signal>| {
+            move |res: Result<Event, notify::Error>| {
+                let _ = tx.send(match res {
+                    Ok(event) => Signal::Event(event),
+                    Err(e) => Signal::Error(e),
+                });
+            }
+        };
+
+        let mut native = match notify::recommended_watcher(handler(tx.clone())) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("Native file watching unavailable, polling instead: {}", e);
+                None
+            }
+        };
+        let mut poll: Option<PollWatcher> = None;
+        let mut poll_watch = |path: &Path, mode: RecursiveMode| -> notify::Result<()> {
+            if poll.is_none() {
+                let config = notify::Config::default()
+                    .with_poll_interval(Duration::from_millis(POLL_INTERVAL_MS.load(Ordering::Relaxed)));
+                poll = Some(PollWatcher::new(handler(tx.clone()), config)?);
+            }
+            poll.as_mut().unwrap().watch(path, mode)
+        };
+
+        let mut watches = Watches {
+            _native: None,
+            _poll: None,
+            targets: Vec::new(),
+            missing: Vec::new(),
+            status: Vec::new(),
+            watch_limit: None,
+        };
+        let mut parents = Vec::new();
+
+        for dir in crate::core::claude_path_candidates() {
+            if !dir.is_dir() {
+                if let Some(parent) = dir.ancestors().skip(1).find(|p| p.is_dir()) {
+                    if !parents.contains(&parent.to_path_buf()) {
+                        parents.push(parent.to_path_buf());
+                    }
+                }
+                watches.missing.push(dir);
+                continue;
+            }
+
+            let mut recent_projects = None;
+            let reason = if force_polling {
+                Some("polling forced in settings".to_string())
+            } else if is_network_fs(&dir) {
+                Some("network file system".to_string())
+            } else {
+                match native.as_mut().map(|w| w.watch(&dir, RecursiveMode::Recursive)) {
+                    Some(Ok(())) => None,
+                    Some(Err(e)) if is_watch_limit(&e) => {
+                        let watcher = native.as_mut().unwrap();
+                        // Release the part of the tree watched before the limit was hit
+                        let _ = watcher.unwatch(&dir);
+                        let watched = watch_recent_projects(watcher, &dir);
+                        recent_projects = Some(watched);
+                        Some(format!(
+                            "inotify watch limit reached; {} recently active projects watched natively, the rest polled",
+                            watched
+                        ))
+                    }
+                    Some(Err(e)) => Some(e.to_string()),
+                    None => Some("native watcher unavailable".to_string()),
+                }
+            };
+
+            let (mode, reason) = match reason {
+                None => (WatchMode::Native, None),
+                Some(reason) => match poll_watch(&dir, RecursiveMode::Recursive) {
+                    Ok(()) if recent_projects.is_some() => (WatchMode::Limited, Some(reason)),
+                    Ok(()) => (WatchMode::Poll, Some(reason)),
+                    Err(e) => (WatchMode::Failed, Some(format!("{}; polling failed: {}", reason, e))),
+                },
+            };
+            match mode {
+                WatchMode::Native => info!("Watching directory: {:?}", dir),
+                _ => warn!("Watching {:?} with {:?}: {}", dir, mode, reason.as_deref().unwrap_or_default()),
+            }
+
+            if let Some(watched) = recent_projects {
+                let limit = watches.watch_limit.get_or_insert_with(|| WatchLimit {
+                    max_user_watches: max_user_watches(),
+                    recent_projects: 0,
+                });
+                limit.recent_projects += watched;
+            }
+            watches.status.push(WatchStatus {
+                path: dir.to_string_lossy().to_string(),
+                mode,
+                reason,
+            });
+            watches.targets.push(dir);
         }
-    })?;
-    
-    // Watch all Claude directories
-    for dir in &directories {
-        if dir.exists() {
-            watcher.watch(dir, RecursiveMode::Recursive)?;
-            println!("Watching directory: {:?}", dir);
+
+        // Watch the nearest existing parent so a fresh Claude install is noticed
+        for parent in parents {
+            let result = match native.as_mut() {
+                Some(watcher) => watcher.watch(&parent, RecursiveMode::NonRecursive),
+                None => poll_watch(&parent, RecursiveMode::NonRecursive),
+            };
+            let (mode, reason) = match result {
+                Ok(()) => (WatchMode::Parent, Some("waiting for a Claude directory to appear".to_string())),
+                Err(e) => (WatchMode::Failed, Some(e.to_string())),
+            };
+            debug!("Watching {:?} for Claude directories", parent);
+            watches.status.push(WatchStatus {
+                path: parent.to_string_lossy().to_string(),
+                mode,
+                reason,
+            });
         }
+
+        watches._native = native;
+        watches._poll = poll;
+        watches
+    }
+
+    fn state(&self) -> WatcherState {
+        let modes: Vec<WatchMode> = self.status.iter().map(|s| s.mode).collect();
+        if self.targets.is_empty() {
+            if modes.contains(&WatchMode::Parent) {
+                WatcherState::Waiting
+            } else {
+                WatcherState::Stopped
+            }
+        } else if modes.iter().all(|&m| m == WatchMode::Native || m == WatchMode::Parent) {
+            WatcherState::Watching
+        } else {
+            WatcherState::Degraded
+        }
+    }
+
+    /// Whether the set of existing Claude directories differs from what is watched
+    fn is_stale(&self) -> bool {
+        self.targets.iter().any(|dir| !dir.is_dir()) || self.missing.iter().any(|dir| dir.is_dir())
+    }
+
+    /// Whether an event under a watched parent may be a Claude directory appearing
+    fn affects_missing(&self, event: &Event) -> bool {
+        event
+            .paths
+            .iter()
+            .any(|path| self.missing.iter().any(|dir| dir.starts_with(path)))
     }
-    
-    // Spawn a thread to handle file system events
-    thread::spawn(move || {
-        let _watcher = watcher; // Keep watcher alive
-        
-        while let Ok(event) = rx.recv() {
-            match event.kind {
-                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+}
+
+pub fn init(app: AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    apply_settings(&load_settings(&app).file_monitor);
+
+    let (tx, rx) = channel();
+    let _ = CONTROL.set(tx.clone());
+
+    let watches = Watches::establish(&tx);
+    update_health(&app, &watches, |_| {});
+    if watches.targets.is_empty() {
+        warn!("No Claude directories found; waiting for one to be created");
+    }
+
+    thread::spawn(move || run(app, tx, rx, watches));
+    Ok(())
+}
+
+/// Feeds watcher events through the debouncer and rebuilds the watches when Claude
+/// directories appear or vanish, after watcher errors, or when settings change
+fn run(app: AppHandle, tx: Sender<Signal>, rx: Receiver<Signal>, mut watches: Watches) {
+    let mut debouncer = Debouncer::new();
+    let mut rebuild = false;
+    let mut retry_at = Instant::now();
+    let mut next_check = Instant::now() + CHECK_INTERVAL;
+
+    loop {
+        let window = debounce_window();
+        let wake_at = debouncer
+            .next_deadline(window)
+            .map_or(next_check, |deadline| deadline.min(next_check));
+        let wake_at = if rebuild { wake_at.min(retry_at) } else { wake_at };
+
+        match rx.recv_timeout(wake_at.saturating_duration_since(Instant::now())) {
+            Ok(Signal::Event(event)) => {
+                if watches.affects_missing(&event) {
+                    rebuild = true;
+                }
+
+                let kind = match event.kind {
+                    EventKind::Create(_) => Some(ChangeKind::Created),
+                    EventKind::Modify(_) => Some(ChangeKind::Modified),
+                    EventKind::Remove(_) => Some(ChangeKind::Removed),
+                    _ => None,
+                };
+
+                // Only JSONL transcripts are of interest
+                if let Some(kind) = kind {
+                    let now = Instant::now();
                     for path in event.paths {
-                        // Only emit events for JSONL files
                         if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                            let event_kind = match event.kind {
-                                EventKind::Create(_) => "created",
-                                EventKind::Modify(_) => "modified",
-                                EventKind::Remove(_) => "removed",
-                                _ => continue,
-                            };
-                            
-                            let file_event = FileChangeEvent {
-                                path: path.to_string_lossy().to_string(),
-                                kind: event_kind.to_string(),
-                            };
-                            
-                            // Emit event to frontend
-                            let _ = app.emit("file-changed", file_event);
+                            debouncer.push(path, kind, now);
                         }
                     }
+                    update_health(&app, &watches, |health| health.last_event_at = Some(Utc::now()));
                 }
-                _ => {}
             }
-            
-            // Small delay to batch rapid changes
-            thread::sleep(Duration::from_millis(100));
+            Ok(Signal::Error(e)) => {
+                warn!("File watcher error: {}", e);
+                update_health(&app, &watches, |health| health.last_error = Some(e.to_string()));
+                rebuild = true;
+            }
+            Ok(Signal::Reconfigure) => {
+                rebuild = true;
+                retry_at = Instant::now();
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
         }
-    });
-    
-    Ok(())
-}
\ No newline at end of file
+
+        let now = Instant::now();
+        if now >= next_check {
+            next_check = now + CHECK_INTERVAL;
+            rebuild |= watches.is_stale();
+        }
+
+        if rebuild && now >= retry_at {
+            let previous = std::mem::take(&mut watches.targets);
+            drop(watches);
+            watches = Watches::establish(&tx);
+            info!("Re-established file watches ({:?})", watches.state());
+
+            // Files in a newly watched directory were written before the watch existed
+            for dir in watches.targets.iter().filter(|dir| !previous.contains(dir)) {
+                for path in crate::core::find_jsonl_files(&dir.join("projects")).unwrap_or_default() {
+                    debouncer.push(path, ChangeKind::Created, now);
+                }
+            }
+
+            update_health(&app, &watches, |health| health.restarts += 1);
+            rebuild = false;
+            retry_at = now + RETRY_DELAY;
+        }
+
+        let batch = debouncer.flush(window, Instant::now());
+        if !batch.is_empty() {
+            dispatch(&app, &batch);
+        }
+    }
+}
+
+/// Records the current watches and tells the UI with a `watcher-health` event when the
+/// state or the watch limit diagnostic changed
+fn update_health(app: &AppHandle, watches: &Watches, update: impl FnOnce(&mut WatcherHealth)) {
+    let mut guard = HEALTH.lock().unwrap();
+    let health = guard.get_or_insert_with(WatcherHealth::default);
+    let before = (health.state, health.watch_limit.clone());
+
+    health.state = watches.state();
+    health.watches = watches.status.clone();
+    health.watch_limit = watches.watch_limit.clone();
+    health.debounce_ms = DEBOUNCE_MS.load(Ordering::Relaxed);
+    update(health);
+
+    if before != (health.state, health.watch_limit.clone()) {
+        let _ = app.emit("watcher-health", health.clone());
+    }
+}
+
+fn is_watch_limit(error: &notify::Error) -> bool {
+    matches!(error.kind, notify::ErrorKind::MaxFilesWatch)
+}
+
+#[cfg(target_os = "linux")]
+fn max_user_watches() -> Option<u64> {
+    fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn max_user_watches() -> Option<u64> {
+    None
+}
+
+/// Natively watches the most recently active project directories until the limit is hit
+/// again, plus `projects` itself so new projects are noticed. Returns how many projects
+/// are watched.
+fn watch_recent_projects(watcher: &mut RecommendedWatcher, claude_dir: &Path) -> usize {
+    let projects = claude_dir.join("projects");
+    if watcher.watch(&projects, RecursiveMode::NonRecursive).is_err() {
+        return 0;
+    }
+
+    let cutoff = SystemTime::now() - RECENT_PROJECT_AGE;
+    let mut recent: Vec<(SystemTime, PathBuf)> = fs::read_dir(&projects)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| {
+            let active = last_activity(&entry.path())?;
+            (active >= cutoff).then(|| (active, entry.path()))
+        })
+        .collect();
+    recent.sort_by_key(|(active, _)| Reverse(*active));
+
+    let mut watched = 0;
+    for (_, dir) in recent.into_iter().take(MAX_RECENT_PROJECTS) {
+        match watcher.watch(&dir, RecursiveMode::Recursive) {
+            Ok(()) => watched += 1,
+            Err(e) if is_watch_limit(&e) => {
+                let _ = watcher.unwatch(&dir);
+                break;
+            }
+            Err(e) => debug!("Failed to watch {:?}: {}", dir, e),
+        }
+    }
+    watched
+}
+
+/// Newest modification time of a project directory or the transcripts directly inside it.
+/// Appending to a session does not touch the directory's own mtime.
+fn last_activity(dir: &Path) -> Option<SystemTime> {
+    let own = fs::metadata(dir).and_then(|m| m.modified()).ok();
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.metadata().and_then(|m| m.modified()).ok())
+        .chain(own)
+        .max()
+}
+
+/// inotify and FSEvents do not see changes made by other machines on network mounts
+#[cfg(target_os = "linux")]
+fn is_network_fs(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    const NFS: i64 = 0x6969;
+    const SMB: i64 = 0x517b;
+    const CIFS: i64 = 0xff53_4d42;
+    const SMB2: i64 = 0xfe53_4d42;
+    const FUSE: i64 = 0x6573_5546;
+
+    let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return false;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    let fs_type = stat.f_type as i64;
+    if fs_type == FUSE {
+        // Most FUSE mounts, such as encrypted home directories, are local
+        let mounts = fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+        return is_remote_fuse_mount(&mounts, path);
+    }
+    [NFS, SMB, CIFS, SMB2].contains(&fs_type)
+}
+
+/// FUSE file systems that serve files from another machine
+const REMOTE_FUSE_TYPES: [&str; 11] = [
+    "sshfs", "rclone", "s3fs", "gcsfuse", "curlftpfs", "smbnetfs", "glusterfs", "ceph-fuse", "goofys", "blobfuse",
+    "juicefs",
+];
+
+/// Whether `path` lies on a FUSE mount of a remote file system, going by the innermost
+/// mount containing it in `mounts`, in the format of `/proc/self/mounts`
+pub fn is_remote_fuse_mount(mounts: &str, path: &Path) -> bool {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = fields.next()?;
+            // Spaces in mount points are written as `\040`
+            let mount_point = PathBuf::from(fields.next()?.replace("\\040", " "));
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point).then_some((mount_point, device, fs_type))
+        })
+        .max_by_key(|(mount_point, _, _)| mount_point.components().count())
+        .is_some_and(|(_, device, fs_type)| {
+            // Older mounts say `fuse` and name the helper in the device, e.g. `sshfs#host:/dir`
+            let subtype = fs_type
+                .strip_prefix("fuse.")
+                .or_else(|| device.split_once('#').map(|(helper, _)| helper))
+                .unwrap_or(device);
+            REMOTE_FUSE_TYPES.iter().any(|remote| subtype.starts_with(remote))
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn is_network_fs(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return false;
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    matches!(name.to_bytes(), b"nfs" | b"smbfs" | b"afpfs" | b"webdav" | b"macfuse" | b"osxfuse")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn is_network_fs(_path: &Path) -> bool {
+    false
+}
+
+fn dispatch(app: &AppHandle, batch: &FileChangeBatch) {
+    debug!(
+        created = batch.created.len(),
+        modified = batch.modified.len(),
+        removed = batch.removed.len(),
+        "Files changed"
+    );
+    crate::core::invalidate_entries();
+
+    // Stream appended usage to local API subscribers
+    for path in &batch.removed {
+        crate::live::file_removed(app, Path::new(path));
+    }
+    for path in batch.created.iter().chain(&batch.modified) {
+        crate::live::file_changed(app, Path::new(path));
+        crate::archive::file_changed(Path::new(path));
+    }
+
+    let _ = app.emit("files-changed", batch);
+}
@@ -0,0 +1,310 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::json;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Claude Code version stamped on generated lines
+pub const FIXTURE_VERSION: &str = "1.0.17";
+
+/// A synthetic assistant response carrying token usage
+#[derive(Debug, Clone)]
+pub struct FixtureMessage {
+    pub timestamp: DateTime<Utc>,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: Option<f64>,
+    pub message_id: String,
+    pub request_id: String,
+}
+
+impl FixtureMessage {
+    pub fn new(timestamp: DateTime<Utc>, model: &str, id: &str) -> Self {
+        Self {
+            timestamp,
+            model: model.to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            cost_usd: None,
+            message_id: format!("msg_{}", id),
+            request_id: format!("req_{}", id),
+        }
+    }
+
+    pub fn tokens(mut self, input: u64, output: u64) -> Self {
+        self.input_tokens = input;
+        self.output_tokens = output;
+        self
+    }
+
+    pub fn cache(mut self, creation: u64, read: u64) -> Self {
+        self.cache_creation_tokens = creation;
+        self.cache_read_tokens = read;
+        self
+    }
+
+    pub fn cost(mut self, cost_usd: f64) -> Self {
+        self.cost_usd = Some(cost_usd);
+        self
+    }
+}
+
+/// A synthetic Claude config directory (the equivalent of `~/.claude`)
+pub struct FixtureDir {
+    root: PathBuf,
+}
+
+impl FixtureDir {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Opens `projects/<project>/<session_id>.jsonl` for appending, creating it if needed
+    pub fn session(&self, project: &str, session_id: &str) -> io::Result<SessionWriter> {
+        let dir = self.root.join("projects").join(project);
+        fs::create_dir_all(&dir)?;
+
+        let path = dir.join(format!("{}.jsonl", session_id));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        Ok(SessionWriter {
+            file,
+            path,
+            session_id: session_id.to_string(),
+            // Claude Code names project directories after the cwd with `/` replaced by `-`
+            cwd: project.replace('-', "/"),
+        })
+    }
+}
+
+/// Appends transcript lines to one session file, one write per line
+pub struct SessionWriter {
+    file: File,
+    path: PathBuf,
+    session_id: String,
+    cwd: String,
+}
+
+impl SessionWriter {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn assistant(&mut self, message: &FixtureMessage) -> io::Result<()> {
+        let line = assistant_line(message, &self.session_id, &self.cwd);
+        self.raw_line(&line)
+    }
+
+    pub fn user(&mut self, timestamp: DateTime<Utc>, text: &str) -> io::Result<()> {
+        let line = json!({
+            "parentUuid": null,
+            "isSidechain": false,
+            "userType": "external",
+            "cwd": self.cwd,
+            "sessionId": self.session_id,
+            "version": FIXTURE_VERSION,
+            "type": "user",
+            "message": { "role": "user", "content": text },
+            "uuid": format!("user-{}", timestamp.timestamp_millis()),
+            "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        });
+        self.raw_line(&line.to_string())
+    }
+
+    /// Writes a line verbatim, e.g. to simulate truncated or corrupt output
+    pub fn raw_line(&mut self, line: &str) -> io::Result<()> {
+        self.file.write_all(format!("{}\n", line).as_bytes())
+    }
+}
+
+/// Renders an assistant transcript line in the shape Claude Code writes
+pub fn assistant_line(message: &FixtureMessage, session_id: &str, cwd: &str) -> String {
+    let mut line = json!({
+        "parentUuid": format!("parent-{}", message.message_id),
+        "isSidechain": false,
+        "userType": "external",
+        "cwd": cwd,
+        "sessionId": session_id,
+        "version": FIXTURE_VERSION,
+        "type": "assistant",
+        "message": {
+            "id": message.message_id,
+            "type": "message",
+            "role": "assistant",
+            "model": message.model,
+            "content": [{ "type": "text", "text": "Synthetic response" }],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {
+                "input_tokens": message.input_tokens,
+                "cache_creation_input_tokens": message.cache_creation_tokens,
+                "cache_read_input_tokens": message.cache_read_tokens,
+                "output_tokens": message.output_tokens,
+                "service_tier": "standard",
+            },
+        },
+        "requestId": message.request_id,
+        "uuid": format!("uuid-{}", message.message_id),
+        "timestamp": message.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+    });
+
+    if let Some(cost) = message.cost_usd {
+        line["costUSD"] = json!(cost);
+    }
+
+    line.to_string()
+}
+
+/// Model aliases accepted in a generated model mix
+pub fn model_alias(name: &str) -> &str {
+    match name {
+        "sonnet" => "claude-sonnet-4-20250514",
+        "opus" => "claude-opus-4-20250514",
+        "haiku" => "claude-3-5-haiku-20241022",
+        other => other,
+    }
+}
+
+/// Shape of a generated data set
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    pub projects: usize,
+    pub sessions: usize,
+    pub messages: usize,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Model names with relative weights
+    pub models: Vec<(String, u32)>,
+    /// Fraction of assistant lines that carry `costUSD`
+    pub cost_ratio: f64,
+    /// Fraction of lines replaced by corrupt JSON
+    pub malformed_ratio: f64,
+    pub seed: u64,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        let end = Utc::now();
+        Self {
+            projects: 5,
+            sessions: 10,
+            messages: 100,
+            start: end - chrono::Duration::days(30),
+            end,
+            models: vec![
+                (model_alias("sonnet").to_string(), 70),
+                (model_alias("opus").to_string(), 25),
+                (model_alias("haiku").to_string(), 5),
+            ],
+            cost_ratio: 0.5,
+            malformed_ratio: 0.0,
+            seed: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GenerateSummary {
+    pub files: usize,
+    pub lines: usize,
+}
+
+/// Small deterministic xorshift generator so fixtures are reproducible from a seed
+pub struct FixtureRng(u64);
+
+impl FixtureRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform value in `low..high`
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low).max(1)
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        (self.next_u64() % 1_000_000) as f64 / 1_000_000.0 < probability
+    }
+
+    pub fn weighted<'a>(&mut self, choices: &'a [(String, u32)]) -> &'a str {
+        let total: u64 = choices.iter().map(|(_, weight)| u64::from(*weight)).sum();
+        let mut pick = self.range(0, total.max(1));
+        for (name, weight) in choices {
+            if pick < u64::from(*weight) {
+                return name;
+            }
+            pick -= u64::from(*weight);
+        }
+        &choices[0].0
+    }
+}
+
+/// Builds a randomised assistant message for `model`
+pub fn random_message(rng: &mut FixtureRng, timestamp: DateTime<Utc>, model: &str, id: &str, cost_ratio: f64) -> FixtureMessage {
+    let message = FixtureMessage::new(timestamp, model, id)
+        .tokens(rng.range(1, 4_000), rng.range(20, 2_000))
+        .cache(rng.range(0, 8_000), rng.range(0, 60_000));
+
+    if rng.chance(cost_ratio) {
+        // Roughly Sonnet-priced so recorded and calculated costs are in the same range
+        let cost = (message.input_tokens as f64 * 3.0
+            + message.output_tokens as f64 * 15.0
+            + message.cache_creation_tokens as f64 * 3.75
+            + message.cache_read_tokens as f64 * 0.3)
+            / 1_000_000.0;
+        message.cost(cost)
+    } else {
+        message
+    }
+}
+
+/// Writes `projects × sessions × messages` synthetic transcripts into `dir`
+pub fn generate(dir: &FixtureDir, options: &GenerateOptions) -> io::Result<GenerateSummary> {
+    let mut rng = FixtureRng::new(options.seed);
+    let mut summary = GenerateSummary::default();
+    let span = (options.end - options.start).num_seconds().max(1) as u64;
+
+    for project in 0..options.projects {
+        let project_name = format!("-synthetic-project-{:03}", project);
+
+        for session in 0..options.sessions {
+            let session_id = format!("{:08x}-0000-4000-8000-{:012x}", project, session);
+            let mut writer = dir.session(&project_name, &session_id)?;
+            let mut timestamp = options.start + chrono::Duration::seconds(rng.range(0, span) as i64);
+
+            for message in 0..options.messages {
+                timestamp += chrono::Duration::seconds(rng.range(5, 120) as i64);
+                writer.user(timestamp, "Synthetic prompt")?;
+
+                if rng.chance(options.malformed_ratio) {
+                    writer.raw_line("{\"type\":\"assistant\",\"message\":{\"usage\":")?;
+                } else {
+                    let model = rng.weighted(&options.models).to_string();
+                    let id = format!("p{}s{}m{}", project, session, message);
+                    let reply_at = timestamp + chrono::Duration::seconds(rng.range(1, 30) as i64);
+                    writer.assistant(&random_message(&mut rng, reply_at, &model, &id, options.cost_ratio))?;
+                }
+                summary.lines += 2;
+            }
+            summary.files += 1;
+        }
+    }
+
+    Ok(summary)
+}
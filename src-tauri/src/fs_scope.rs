@@ -0,0 +1,149 @@
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_fs::FsExt;
+use tracing::warn;
+
+use crate::core;
+
+/// Normalizes a path lexically, resolving `.` and `..` without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Rejects custom data directories so broad that allowing them would expose unrelated files:
+/// relative paths, a file system root, the home directory and any directory containing it
+pub fn validate_data_directory(dir: &str, home: Option<&Path>) -> Result<(), String> {
+    check_directory("Data directory", dir, home)
+}
+
+/// Rejects export directories under the same rules as data directories
+pub fn validate_export_directory(dir: &str, home: Option<&Path>) -> Result<(), String> {
+    check_directory("Export directory", dir, home)
+}
+
+/// Rejects sync folders under the same rules as data directories
+pub fn validate_sync_folder(dir: &str, home: Option<&Path>) -> Result<(), String> {
+    check_directory("Sync folder", dir, home)
+}
+
+/// Rejects team directories under the same rules as data directories
+pub fn validate_team_directory(dir: &str, home: Option<&Path>) -> Result<(), String> {
+    check_directory("Team directory", dir, home)
+}
+
+fn check_directory(label: &str, dir: &str, home: Option<&Path>) -> Result<(), String> {
+    let path = Path::new(dir.trim());
+    if path.as_os_str().is_empty() {
+        return Err(format!("{} must not be empty", label));
+    }
+    if !path.is_absolute() {
+        return Err(format!("{} must be an absolute path: {}", label, dir));
+    }
+
+    // Resolve symlinks where possible so a link to `/` is caught too
+    let path = path.canonicalize().unwrap_or_else(|_| normalize(path));
+    if path.parent().is_none() {
+        return Err(format!("{} is a file system root: {}", label, dir));
+    }
+    if let Some(home) = home {
+        let home = home.canonicalize().unwrap_or_else(|_| normalize(home));
+        if home.starts_with(&path) {
+            return Err(format!("{} would expose the whole home directory: {}", label, dir));
+        }
+    }
+    Ok(())
+}
+
+/// Validates every custom data directory against the current user's home directory
+pub fn check_data_directories(dirs: &[String]) -> Result<(), String> {
+    let home = dirs::home_dir();
+    dirs.iter()
+        .try_for_each(|dir| validate_data_directory(dir, home.as_deref()))
+}
+
+/// Validates every configured export directory against the current user's home directory
+pub fn check_export_directories(dirs: &[String]) -> Result<(), String> {
+    let home = dirs::home_dir();
+    dirs.iter()
+        .try_for_each(|dir| validate_export_directory(dir, home.as_deref()))
+}
+
+/// Directories headless exports may write into: the user's Downloads and Documents
+/// directories plus the valid configured ones
+pub fn export_directories(configured: &[String]) -> Vec<PathBuf> {
+    let home = dirs::home_dir();
+    let configured = configured
+        .iter()
+        .filter(|dir| validate_export_directory(dir, home.as_deref()).is_ok())
+        .map(|dir| PathBuf::from(dir.trim()));
+    dirs::download_dir()
+        .into_iter()
+        .chain(dirs::document_dir())
+        .chain(configured)
+        .collect()
+}
+
+/// Resolves an explicit export path, which must be an absolute file path whose directory
+/// exists inside one of `allowed`. Symlinks in the directory are resolved before checking.
+pub fn check_export_path(path: &str, allowed: &[PathBuf]) -> Result<PathBuf, String> {
+    let path = Path::new(path.trim());
+    if !path.is_absolute() {
+        return Err(format!("Export path must be absolute: {}", path.display()));
+    }
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(format!("Export path has no file name: {}", path.display()));
+    };
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| format!("Export directory is not accessible: {}: {}", dir.display(), e))?;
+    let inside = allowed
+        .iter()
+        .filter_map(|allowed| allowed.canonicalize().ok())
+        .any(|allowed| dir.starts_with(allowed));
+    if !inside {
+        return Err(format!("Export path is outside the allowed export directories: {}", path.display()));
+    }
+
+    let path = dir.join(name);
+    if path.is_dir() {
+        return Err(format!("Export path is a directory: {}", path.display()));
+    }
+    Ok(path)
+}
+
+/// With privacy mode on, forbids the Claude data directories and valid custom data
+/// directories in the fs plugin scope, so no capability can let the webview read transcripts.
+/// Forbidden directories stay forbidden until the app restarts.
+pub fn protect_data_directories<R: Runtime>(app: &AppHandle<R>, privacy_mode: bool, custom_dirs: &[String]) {
+    if !privacy_mode {
+        return;
+    }
+    let home = dirs::home_dir();
+    let custom = custom_dirs
+        .iter()
+        .filter(|dir| validate_data_directory(dir, home.as_deref()).is_ok())
+        .map(|dir| PathBuf::from(dir.trim()));
+    let scope = app.fs_scope();
+    for dir in core::claude_path_candidates().into_iter().chain(custom) {
+        if let Err(e) = scope.forbid_directory(&dir, true) {
+            warn!(dir = %dir.display(), "Failed to forbid data directory: {}", e);
+        }
+    }
+}
+
+/// Adds a file the user chose as an export destination to the fs plugin scope
+pub fn allow_export(app: &AppHandle, path: &Path) {
+    if let Err(e) = app.fs_scope().allow_file(path) {
+        warn!(path = %path.display(), "Failed to allow export destination: {}", e);
+    }
+}
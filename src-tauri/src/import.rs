@@ -0,0 +1,551 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+use crate::archive::{self, archive_key};
+use crate::reports::LOCAL_MACHINE;
+use crate::usage::UsageEntry;
+
+const FILE_SUFFIX: &str = ".ndjson";
+
+/// Message ids of records built from ccusage reports start with this
+const CCUSAGE_PREFIX: &str = "ccusage:";
+
+/// Longest machine label accepted
+pub const MAX_MACHINE_LEN: usize = 64;
+
+static IMPORTS: OnceLock<Imports> = OnceLock::new();
+
+/// Kind of file an import was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportFormat {
+    /// `ccusage <report> --json` output
+    Ccusage,
+    /// A JSON or NDJSON export of the entries report
+    ClaudeDeck,
+}
+
+/// Outcome of one import
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub machine: String,
+    pub format: ImportFormat,
+    pub records: usize,
+    pub added: usize,
+    pub duplicates: usize,
+}
+
+/// Usage stored for one machine
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedMachine {
+    pub machine: String,
+    pub entries: usize,
+    pub first: Option<DateTime<Utc>>,
+    pub last: Option<DateTime<Utc>>,
+}
+
+/// Checks a machine label, which also names the file its usage is stored in
+pub fn validate_machine(machine: &str) -> Result<String, String> {
+    validate_label("Machine", machine)
+}
+
+/// Trims a machine or user label and checks that it is safe as a file name
+pub fn validate_label(kind: &str, label: &str) -> Result<String, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(format!("{} label is required", kind));
+    }
+    if label.len() > MAX_MACHINE_LEN
+        || label.starts_with('.')
+        || !label.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    {
+        return Err(format!(
+            "Invalid {} label: {} (use up to {} letters, digits, '.', '_' or '-')",
+            kind.to_lowercase(),
+            label,
+            MAX_MACHINE_LEN
+        ));
+    }
+    if label.eq_ignore_ascii_case(LOCAL_MACHINE) {
+        return Err(format!("{} label {} is reserved for this machine", kind, LOCAL_MACHINE));
+    }
+    Ok(label.to_string())
+}
+
+/// A row of ClaudeDeck's entries export
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedEntry {
+    timestamp: DateTime<Utc>,
+    #[serde(default)]
+    project_path: String,
+    #[serde(default)]
+    session_id: String,
+    model: Option<String>,
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_tokens: u64,
+    #[serde(default)]
+    cache_read_tokens: u64,
+    total_cost: Option<f64>,
+    message_id: Option<String>,
+    request_id: Option<String>,
+    version: Option<String>,
+}
+
+impl ExportedEntry {
+    fn into_entry(self, machine: &str) -> UsageEntry {
+        UsageEntry {
+            timestamp: self.timestamp,
+            model: self.model,
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_creation_tokens: self.cache_creation_tokens,
+            cache_read_tokens: self.cache_read_tokens,
+            cost_usd: self.total_cost,
+            version: self.version,
+            message_id: self.message_id,
+            request_id: self.request_id,
+            session_id: self.session_id,
+            project: self.project_path,
+            machine: Some(machine.to_string()),
+            user: None,
+        }
+    }
+}
+
+/// The reports `ccusage daily|monthly|session|blocks --json` print
+#[derive(Deserialize)]
+struct CcusageReport {
+    daily: Option<Vec<CcusageRow>>,
+    monthly: Option<Vec<CcusageRow>>,
+    sessions: Option<Vec<CcusageRow>>,
+    blocks: Option<Vec<CcusageBlock>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CcusageRow {
+    date: Option<String>,
+    month: Option<String>,
+    session_id: Option<String>,
+    project_path: Option<String>,
+    last_activity: Option<String>,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    total_cost: Option<f64>,
+    models_used: Vec<String>,
+    model_breakdowns: Vec<CcusageModel>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CcusageModel {
+    model_name: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    cost: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CcusageBlock {
+    id: Option<String>,
+    start_time: DateTime<Utc>,
+    #[serde(default)]
+    is_gap: bool,
+    #[serde(default)]
+    token_counts: CcusageTokenCounts,
+    #[serde(rename = "costUSD")]
+    cost_usd: Option<f64>,
+    #[serde(default)]
+    models: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CcusageTokenCounts {
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_input_tokens: u64,
+    cache_read_input_tokens: u64,
+}
+
+/// Where a ccusage row sits in time and in which session
+struct CcusageSlot<'a> {
+    machine: &'a str,
+    report: &'static str,
+    key: String,
+    timestamp: DateTime<Utc>,
+    project: String,
+    session: String,
+}
+
+impl CcusageSlot<'_> {
+    /// One record per model in the row's breakdown, or one for the whole row without one.
+    /// Ids are stable across imports of the same report, so re-importing replaces rows.
+    fn entries(&self, row: CcusageRow) -> Vec<UsageEntry> {
+        let models = if row.model_breakdowns.is_empty() {
+            let model = match row.models_used.as_slice() {
+                [model] => model.clone(),
+                _ => String::new(),
+            };
+            vec![CcusageModel {
+                model_name: model,
+                input_tokens: row.input_tokens,
+                output_tokens: row.output_tokens,
+                cache_creation_tokens: row.cache_creation_tokens,
+                cache_read_tokens: row.cache_read_tokens,
+                cost: row.total_cost,
+            }]
+        } else {
+            row.model_breakdowns
+        };
+        models.into_iter().map(|model| self.entry(model)).collect()
+    }
+
+    fn entry(&self, model: CcusageModel) -> UsageEntry {
+        let message_id = format!(
+            "{}{}:{}:{}:{}",
+            CCUSAGE_PREFIX, self.machine, self.report, self.key, model.model_name
+        );
+        UsageEntry {
+            timestamp: self.timestamp,
+            model: Some(model.model_name).filter(|name| !name.is_empty()),
+            input_tokens: model.input_tokens,
+            output_tokens: model.output_tokens,
+            cache_creation_tokens: model.cache_creation_tokens,
+            cache_read_tokens: model.cache_read_tokens,
+            cost_usd: model.cost,
+            version: None,
+            message_id: Some(message_id),
+            request_id: None,
+            session_id: self.session.clone(),
+            project: self.project.clone(),
+            machine: Some(self.machine.to_string()),
+            user: None,
+        }
+    }
+}
+
+/// ccusage reports local dates; noon UTC keeps them on the same day in most time zones
+fn noon(date: &str) -> Result<DateTime<Utc>, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{}-01", date), "%Y-%m-%d"))
+        .map(|date| date.and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default()).and_utc())
+        .map_err(|_| format!("Invalid date in ccusage report: {}", date))
+}
+
+fn parse_ccusage(report: CcusageReport, machine: &str) -> Result<Vec<UsageEntry>, String> {
+    let slot = |report: &'static str, key: &str, timestamp, project: &str, session: &str| CcusageSlot {
+        machine,
+        report,
+        key: key.to_string(),
+        timestamp,
+        project: project.to_string(),
+        session: session.to_string(),
+    };
+
+    let mut entries = Vec::new();
+    for row in report.daily.unwrap_or_default() {
+        let date = row.date.clone().ok_or("ccusage daily row without a date")?;
+        let slot = slot("daily", &date, noon(&date)?, "ccusage", &format!("daily-{}", date));
+        entries.extend(slot.entries(row));
+    }
+    for row in report.monthly.unwrap_or_default() {
+        let month = row.month.clone().ok_or("ccusage monthly row without a month")?;
+        let slot = slot("monthly", &month, noon(&month)?, "ccusage", &format!("monthly-{}", month));
+        entries.extend(slot.entries(row));
+    }
+    for row in report.sessions.unwrap_or_default() {
+        let session = row.session_id.clone().ok_or("ccusage session row without a sessionId")?;
+        let project = row.project_path.clone().unwrap_or_else(|| "ccusage".to_string());
+        let last_activity = row.last_activity.as_deref().ok_or("ccusage session row without lastActivity")?;
+        let timestamp = DateTime::parse_from_rfc3339(last_activity)
+            .map(|time| time.with_timezone(&Utc))
+            .or_else(|_| noon(last_activity))?;
+        let slot = slot("session", &format!("{}/{}", project, session), timestamp, &project, &session);
+        entries.extend(slot.entries(row));
+    }
+    for block in report.blocks.unwrap_or_default().into_iter().filter(|block| !block.is_gap) {
+        let key = block.id.unwrap_or_else(|| block.start_time.to_rfc3339());
+        let row = CcusageRow {
+            input_tokens: block.token_counts.input_tokens,
+            output_tokens: block.token_counts.output_tokens,
+            cache_creation_tokens: block.token_counts.cache_creation_input_tokens,
+            cache_read_tokens: block.token_counts.cache_read_input_tokens,
+            total_cost: block.cost_usd,
+            models_used: block.models,
+            ..CcusageRow::default()
+        };
+        let slot = slot("blocks", &key, block.start_time, "ccusage", &format!("block-{}", key));
+        entries.extend(slot.entries(row));
+    }
+    Ok(entries)
+}
+
+fn parse_exported_rows(rows: Vec<Value>, machine: &str) -> Result<Vec<UsageEntry>, String> {
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            serde_json::from_value::<ExportedEntry>(row)
+                .map(|row| row.into_entry(machine))
+                .map_err(|e| format!("Row {} is not a usage entry ({}); export the entries report", index + 1, e))
+        })
+        .collect()
+}
+
+/// Reads ccusage `--json` output or a ClaudeDeck JSON or NDJSON entries export, tagging
+/// every record with `machine`
+pub fn parse_import(content: &str, machine: &str) -> Result<(ImportFormat, Vec<UsageEntry>), String> {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('[') {
+        let rows: Vec<Value> =
+            serde_json::from_str(content).map_err(|e| format!("Failed to parse JSON export: {}", e))?;
+        return Ok((ImportFormat::ClaudeDeck, parse_exported_rows(rows, machine)?));
+    }
+    if !trimmed.starts_with('{') {
+        return Err(
+            "Unrecognized import file: expected ccusage --json output or a ClaudeDeck JSON or NDJSON entries export"
+                .to_string(),
+        );
+    }
+
+    match serde_json::from_str::<Value>(content) {
+        Ok(Value::Object(object))
+            if ["daily", "monthly", "sessions", "blocks"].iter().any(|key| object.contains_key(*key)) =>
+        {
+            let report: CcusageReport = serde_json::from_value(Value::Object(object))
+                .map_err(|e| format!("Failed to parse ccusage report: {}", e))?;
+            Ok((ImportFormat::Ccusage, parse_ccusage(report, machine)?))
+        }
+        // A single NDJSON row
+        Ok(row) => Ok((ImportFormat::ClaudeDeck, parse_exported_rows(vec![row], machine)?)),
+        Err(_) => {
+            let rows = trimmed
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(index, line)| {
+                    serde_json::from_str(line).map_err(|e| format!("Failed to parse NDJSON line {}: {}", index + 1, e))
+                })
+                .collect::<Result<Vec<Value>, String>>()?;
+            Ok((ImportFormat::ClaudeDeck, parse_exported_rows(rows, machine)?))
+        }
+    }
+}
+
+fn is_ccusage(entry: &UsageEntry) -> bool {
+    entry.message_id.as_deref().is_some_and(|id| id.starts_with(CCUSAGE_PREFIX))
+}
+
+/// Usage imported from other machines, stored as one NDJSON file of usage records per
+/// machine label. Imported records are merged into every load behind local ones, so a
+/// record seen both locally and in an import is counted once.
+pub struct Imports {
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl Imports {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, machine: &str) -> PathBuf {
+        self.dir.join(format!("{}{}", machine, FILE_SUFFIX))
+    }
+
+    /// Reads and stores an import file
+    pub fn import_file(&self, path: &Path, machine: &str) -> Result<ImportSummary, String> {
+        let machine = validate_machine(machine)?;
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let (format, entries) = parse_import(&content, &machine)?;
+        self.import(&machine, format, entries)
+            .map_err(|e| format!("Failed to store imported usage: {}", e))
+    }
+
+    /// Adds records to a machine's store, skipping ones already stored. A ccusage report
+    /// replaces the records of earlier ccusage imports for the machine, since each report
+    /// covers all the usage ccusage found.
+    pub fn import(&self, machine: &str, format: ImportFormat, entries: Vec<UsageEntry>) -> io::Result<ImportSummary> {
+        let _guard = self.lock.lock().unwrap();
+        let path = self.path(machine);
+        let mut stored = read_entries(&path)?;
+
+        let mut replaced = HashSet::new();
+        if format == ImportFormat::Ccusage {
+            stored.retain(|entry| {
+                if is_ccusage(entry) {
+                    replaced.insert(archive_key(entry));
+                    return false;
+                }
+                true
+            });
+        }
+        let mut known: HashSet<String> = stored.iter().map(archive_key).collect();
+
+        let records = entries.len();
+        let (mut added, mut duplicates) = (0, 0);
+        for entry in entries {
+            let key = archive_key(&entry);
+            if !known.insert(key.clone()) {
+                duplicates += 1;
+                continue;
+            }
+            // A refreshed ccusage row is not new usage
+            if replaced.contains(&key) {
+                duplicates += 1;
+            } else {
+                added += 1;
+            }
+            stored.push(entry);
+        }
+        stored.sort_by_key(|entry| entry.timestamp);
+
+        fs::create_dir_all(&self.dir)?;
+        let mut temp = tempfile::Builder::new()
+            .prefix(".import-")
+            .suffix(".tmp")
+            .tempfile_in(&self.dir)?;
+        {
+            let mut out = BufWriter::new(temp.as_file_mut());
+            for entry in &stored {
+                serde_json::to_writer(&mut out, entry)?;
+                out.write_all(b"\n")?;
+            }
+            out.flush()?;
+        }
+        temp.as_file().sync_all()?;
+        temp.persist(&path).map_err(|e| e.error)?;
+
+        Ok(ImportSummary {
+            machine: machine.to_string(),
+            format,
+            records,
+            added,
+            duplicates,
+        })
+    }
+
+    /// Every imported record, oldest first
+    pub fn entries(&self) -> Vec<UsageEntry> {
+        let mut entries = Vec::new();
+        for (_, path) in self.files() {
+            match read_entries(&path) {
+                Ok(stored) => {
+                    archive::merge(&mut entries, stored);
+                }
+                Err(e) => warn!(path = %path.display(), "Failed to read imported usage: {}", e),
+            }
+        }
+        entries
+    }
+
+    /// Machines with imported usage, by label
+    pub fn machines(&self) -> Vec<ImportedMachine> {
+        self.files()
+            .into_iter()
+            .map(|(machine, path)| {
+                let entries = read_entries(&path).unwrap_or_default();
+                ImportedMachine {
+                    machine,
+                    entries: entries.len(),
+                    first: entries.first().map(|entry| entry.timestamp),
+                    last: entries.last().map(|entry| entry.timestamp),
+                }
+            })
+            .collect()
+    }
+
+    /// Deletes a machine's imported usage. Returns `false` when there was none.
+    pub fn remove(&self, machine: &str) -> Result<bool, String> {
+        let machine = validate_machine(machine)?;
+        let _guard = self.lock.lock().unwrap();
+        match fs::remove_file(self.path(&machine)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(format!("Failed to remove imported usage for {}: {}", machine, e)),
+        }
+    }
+
+    fn files(&self) -> Vec<(String, PathBuf)> {
+        let mut files: Vec<(String, PathBuf)> = fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_str()?.strip_suffix(FILE_SUFFIX)?.to_string();
+                validate_machine(&name).ok().map(|machine| (machine, entry.path()))
+            })
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+/// Reads a machine's stored records, skipping lines that fail to parse
+fn read_entries(path: &Path) -> io::Result<Vec<UsageEntry>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(entry) = serde_json::from_str::<UsageEntry>(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Where the desktop app keeps imports, for use without a running app
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("com.claudedeck.claudedeck").join("imports"))
+}
+
+/// Opens the import store in the app data directory
+pub fn init(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = IMPORTS.set(Imports::new(dir.join("imports")));
+        }
+        Err(e) => warn!("Usage imports disabled, no app data directory: {}", e),
+    }
+}
+
+/// The import store, once the app has opened it
+pub fn store() -> Result<&'static Imports, String> {
+    IMPORTS.get().ok_or_else(|| "Usage imports are not available".to_string())
+}
+
+/// Imported records, or none before the store is opened
+pub fn imported_entries() -> Vec<UsageEntry> {
+    IMPORTS.get().map(Imports::entries).unwrap_or_default()
+}
@@ -0,0 +1,386 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{error, info, warn};
+
+use crate::commands;
+use crate::settings::AppSettings;
+
+const SOCKET_NAME: &str = "claudedeck.sock";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const APP_ERROR: i64 = -32000;
+
+/// Every method served on the socket: each command the webview can invoke
+pub const METHODS: &[&str] = &[
+    "load_usage_entries",
+    "get_daily_report",
+    "get_monthly_report",
+    "get_session_report",
+    "get_active_block",
+    "get_model_report",
+    "get_cache_report",
+    "get_machine_report",
+    "import_usage",
+    "get_imports",
+    "remove_import",
+    "get_sync_status",
+    "sync_now",
+    "get_team_report",
+    "export_team_snapshot",
+    "cancel_load",
+    "export_report",
+    "get_settings",
+    "save_settings",
+    "update_tray_title",
+    "set_tray_tooltip",
+    "show_popup_window",
+    "hide_popup_window",
+    "get_popup_position",
+    "show_main_window",
+    "get_logs",
+    "get_watcher_health",
+];
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    jsonrpc: Option<String>,
+    /// `None` for a notification, which gets no response; `Some(Null)` for an explicit null id
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Keeps an explicit `null` apart from a missing member
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ReportParams {
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct LoadParams {
+    since_date: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct CancelParams {
+    load_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SaveSettingsParams {
+    settings: AppSettings,
+}
+
+#[derive(Deserialize)]
+struct TitleParams {
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct TooltipParams {
+    tooltip: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportReportParams {
+    report: String,
+    format: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    mode: Option<String>,
+    path: Option<String>,
+    overwrite: Option<String>,
+    team: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SaveParams {
+    path: Option<String>,
+    overwrite: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ImportParams {
+    machine: String,
+    path: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MachineParams {
+    machine: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct LogParams {
+    level: Option<String>,
+    target: Option<String>,
+    search: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Path of the control socket inside the app's runtime directory
+pub fn socket_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .runtime_dir()
+        .map(|dir| dir.join("claudedeck"))
+        .or_else(|_| app.path().app_cache_dir())
+        .map_err(|e| format!("Failed to resolve runtime directory: {}", e))?;
+    Ok(dir.join(SOCKET_NAME))
+}
+
+/// Serves newline-delimited JSON-RPC 2.0 on a user-only Unix socket. Methods mirror the
+/// Tauri commands and take the same camelCase parameters.
+pub fn init(app: &AppHandle) {
+    // Scripting access is optional, so a socket problem must not abort startup
+    if let Err(e) = start(app) {
+        warn!("IPC socket not started: {}", e);
+    }
+}
+
+fn start(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let path = socket_path(app)?;
+
+    if let Some(dir) = path.parent() {
+        fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        // The mode only applies to directories the builder creates
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+
+    remove_stale_socket(&path)?;
+
+    let listener = std::os::unix::net::UnixListener::bind(&path)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    listener.set_nonblocking(true)?;
+    info!("Listening on {:?}", path);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match UnixListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to start IPC socket: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(handle_connection(app, stream));
+                }
+                Err(e) => warn!("Failed to accept connection: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Removes a socket left behind by a previous run, which would make bind fail. Fails
+/// without touching it when another instance still accepts connections on it.
+pub fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    match std::os::unix::net::UnixStream::connect(path) {
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("another instance is listening on {:?}", path),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path),
+        Err(e) => Err(e),
+    }
+}
+
+async fn handle_connection(app: AppHandle, stream: UnixStream) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(response) = handle_line(&app, &line).await else {
+            continue;
+        };
+        let mut response = response.to_string();
+        response.push('\n');
+        if writer.write_all(response.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Handles one request line, returning the response, or `None` for a notification
+async fn handle_line(app: &AppHandle, line: &str) -> Option<Value> {
+    let request: RpcRequest = match serde_json::from_str::<Value>(line) {
+        Ok(value) => match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => return Some(error_response(Value::Null, INVALID_REQUEST, e.to_string())),
+        },
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, e.to_string())),
+    };
+
+    if request.jsonrpc.as_deref() != Some("2.0") {
+        let message = "jsonrpc must be \"2.0\"".to_string();
+        return request.id.map(|id| error_response(id, INVALID_REQUEST, message));
+    }
+
+    let response = dispatch(app, &request.method, request.params).await;
+    let id = request.id?;
+    Some(match response {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error_response(id, e.code, e.message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn params<T: DeserializeOwned + Default>(params: Value) -> Result<T, RpcError> {
+    if params.is_null() {
+        return Ok(T::default());
+    }
+    required_params(params)
+}
+
+fn required_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError {
+        code: INVALID_PARAMS,
+        message: e.to_string(),
+    })
+}
+
+fn result<T: Serialize>(value: Result<T, String>) -> Result<Value, RpcError> {
+    value
+        .and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+        .map_err(|message| RpcError { code: APP_ERROR, message })
+}
+
+async fn dispatch(app: &AppHandle, method: &str, raw: Value) -> Result<Value, RpcError> {
+    let app = app.clone();
+    match method {
+        "load_usage_entries" => {
+            let p: LoadParams = params(raw)?;
+            result(commands::load_usage_entries(p.since_date, app).await)
+        }
+        "get_daily_report" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_daily_report(p.since, p.until, p.mode, app).await)
+        }
+        "get_monthly_report" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_monthly_report(p.since, p.until, p.mode, app).await)
+        }
+        "get_session_report" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_session_report(p.since, p.until, p.mode, app).await)
+        }
+        "get_active_block" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_active_block(p.mode, app).await)
+        }
+        "get_model_report" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_model_report(p.since, p.until, p.mode, app).await)
+        }
+        "get_cache_report" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_cache_report(p.since, p.until, p.mode, app).await)
+        }
+        "get_machine_report" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_machine_report(p.since, p.until, p.mode, app).await)
+        }
+        "import_usage" => {
+            let p: ImportParams = required_params(raw)?;
+            result(commands::import_usage(p.machine, p.path, app).await)
+        }
+        "get_imports" => result(commands::get_imports().await),
+        "remove_import" => {
+            let p: MachineParams = required_params(raw)?;
+            result(commands::remove_import(p.machine).await)
+        }
+        "get_sync_status" => result(commands::get_sync_status().await),
+        "sync_now" => result(commands::sync_now(app).await),
+        "get_team_report" => {
+            let p: ReportParams = params(raw)?;
+            result(commands::get_team_report(p.since, p.until, p.mode, app).await)
+        }
+        "export_team_snapshot" => {
+            let p: SaveParams = params(raw)?;
+            result(commands::export_team_snapshot(p.path, p.overwrite, app).await)
+        }
+        "cancel_load" => {
+            let p: CancelParams = params(raw)?;
+            result(commands::cancel_load(p.load_id, app).await)
+        }
+        "export_report" => {
+            let p: ExportReportParams = required_params(raw)?;
+            let export = commands::export_report(
+                p.report, p.format, p.since, p.until, p.mode, p.path, p.overwrite, p.team, app,
+            );
+            result(export.await)
+        }
+        "get_settings" => result(commands::get_settings(app).await),
+        "get_watcher_health" => result(commands::get_watcher_health().await),
+        "save_settings" => {
+            let p: SaveSettingsParams = required_params(raw)?;
+            result(commands::save_settings(p.settings, app).await)
+        }
+        "update_tray_title" => {
+            let p: TitleParams = required_params(raw)?;
+            result(commands::update_tray_title(p.title, app).await)
+        }
+        "set_tray_tooltip" => {
+            let p: TooltipParams = required_params(raw)?;
+            result(commands::set_tray_tooltip(p.tooltip, app).await)
+        }
+        "show_popup_window" => result(commands::show_popup_window(app).await),
+        "hide_popup_window" => result(commands::hide_popup_window(app).await),
+        "get_popup_position" => result(commands::get_popup_position(app).await),
+        "show_main_window" => result(commands::show_main_window(app).await),
+        "get_logs" => {
+            let p: LogParams = params(raw)?;
+            result(commands::get_logs(p.level, p.target, p.search, p.limit).await)
+        }
+        _ => Err(RpcError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Unknown method: {}", method),
+        }),
+    }
+}
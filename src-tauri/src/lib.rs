@@ -1,58 +1,131 @@
-
-use tauri::Manager;
+use tracing::{debug, info};
 
 pub mod commands;
 pub mod core;
 pub mod settings;
+pub mod fs_scope;
 pub mod tray;
 pub mod file_monitor;
+pub mod usage;
+pub mod scanner;
+pub mod archive;
+pub mod import;
+pub mod sync;
+pub mod team;
+pub mod pricing;
+pub mod reports;
+pub mod export;
+pub mod scheduler;
+pub mod metrics;
+pub mod live;
+pub mod logging;
+pub mod fixtures;
+pub mod cli;
+#[cfg(unix)]
+pub mod ipc;
+pub mod api;
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
+/// Builds the Tauri application with every plugin, subsystem and command registered.
+/// The desktop binary, mobile entry point and tests all start from here.
+pub fn app_builder() -> tauri::Builder<tauri::Wry> {
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_store::Builder::default().build())
-        .setup(|app| {
-            println!("[lib.rs] Setting up Tauri application...");
-            
-            // Get the main window and enable devtools
-            if let Some(window) = app.get_webview_window("main") {
-                println!("[lib.rs] Found main window, attempting to open devtools...");
-                
-                // Try to open devtools automatically in debug builds
-                #[cfg(debug_assertions)]
-                {
-                    window.open_devtools();
-                    println!("[lib.rs] DevTools opened");
-                }
-            } else {
-                println!("[lib.rs] Main window not found");
-            }
-            
-            // Log the asset protocol URL
-            println!("[lib.rs] Asset protocol URL: tauri://localhost");
-            
-            // Initialize system tray
-            tray::init(&app.handle())?;
-            
-            // Initialize settings
-            settings::init(&app.handle())?;
-            
-            // Initialize file monitoring
-            file_monitor::init(app.handle().clone())?;
-            
-            println!("[lib.rs] Setup complete");
-            Ok(())
-        })
+        .manage(core::ActiveLoads::default())
+        .setup(|app| setup(app.handle()))
         .invoke_handler(tauri::generate_handler![
             commands::load_usage_entries,
-            commands::export_data,
+            commands::get_daily_report,
+            commands::get_monthly_report,
+            commands::get_session_report,
+            commands::get_active_block,
+            commands::get_model_report,
+            commands::get_cache_report,
+            commands::get_machine_report,
+            commands::import_usage,
+            commands::get_imports,
+            commands::remove_import,
+            commands::get_sync_status,
+            commands::sync_now,
+            commands::get_team_report,
+            commands::export_team_snapshot,
+            commands::cancel_load,
+            commands::export_report,
             commands::get_settings,
             commands::save_settings,
             commands::update_tray_title,
+            commands::set_tray_tooltip,
+            commands::show_popup_window,
+            commands::hide_popup_window,
+            commands::get_popup_position,
+            commands::show_main_window,
+            commands::get_logs,
+            commands::get_watcher_health,
         ])
+}
+
+fn setup(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize logging first so the rest of setup is captured
+    let app_settings = settings::load_settings(app);
+    if let Err(e) = logging::init(app, &app_settings.logging) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    debug!("Setting up Tauri application...");
+
+    // Open devtools automatically in debug builds
+    #[cfg(debug_assertions)]
+    {
+        use tauri::Manager;
+        match app.get_webview_window("main") {
+            Some(window) => window.open_devtools(),
+            None => tracing::warn!("Main window not found"),
+        }
+    }
+
+    // Initialize system tray
+    tray::init(app)?;
+
+    // Initialize settings
+    settings::init(app)?;
+
+    // Keep transcripts out of the webview's reach in privacy mode
+    fs_scope::protect_data_directories(app, app_settings.privacy_mode, &app_settings.custom_data_directories);
+
+    // Initialize live usage tailing before the watcher starts feeding it
+    live::init(app);
+
+    // Archive usage records before Claude Code prunes old transcripts
+    archive::init(app, &app_settings.archive);
+
+    // Open usage imported from other machines
+    import::init(app);
+
+    // Share usage with other machines through a synced folder
+    sync::init(app, &app_settings.sync);
+
+    // Run scheduled exports while the app is open
+    scheduler::init(app, &app_settings.scheduled_exports);
+
+    // Initialize file monitoring
+    file_monitor::init(app.clone())?;
+
+    // Start the local HTTP API if enabled in settings
+    api::init(app)?;
+
+    // Expose the JSON-RPC control socket for local scripts
+    #[cfg(unix)]
+    ipc::init(app);
+
+    info!("Setup complete");
+    Ok(())
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    app_builder()
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
-}
\ No newline at end of file
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::core;
+use crate::pricing::CostMode;
+use crate::reports::{self, BlockUsage, BLOCK_HOURS};
+use crate::settings::load_settings;
+use crate::usage::{self, UsageEntry};
+
+/// Events pushed to live subscribers as transcripts grow
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LiveEvent {
+    Entries { entries: Vec<UsageEntry> },
+    ActiveBlock { block: Option<BlockUsage> },
+}
+
+#[derive(Default)]
+struct TailState {
+    /// False until startup offsets are recorded; changes seen before then wait in `pending`
+    ready: bool,
+    pending: HashSet<PathBuf>,
+    offsets: HashMap<PathBuf, u64>,
+    /// Dedup keys of the entries in `recent`, with their timestamps so they can be pruned
+    seen: HashMap<String, DateTime<Utc>>,
+    recent: Vec<UsageEntry>,
+}
+
+impl TailState {
+    /// Drops entries and keys that have left the active block window
+    fn prune(&mut self) -> DateTime<Utc> {
+        let cutoff = Utc::now() - Duration::hours(BLOCK_HOURS);
+        self.recent.retain(|e| e.timestamp >= cutoff);
+        self.seen.retain(|_, timestamp| *timestamp >= cutoff);
+        cutoff
+    }
+}
+
+/// Tails JSONL files reported by the watcher and broadcasts newly appended usage
+pub struct LiveFeed {
+    sender: broadcast::Sender<LiveEvent>,
+    state: Mutex<TailState>,
+}
+
+pub fn init(app: &AppHandle) {
+    let (sender, _) = broadcast::channel(256);
+    app.manage(LiveFeed {
+        sender,
+        state: Mutex::new(TailState::default()),
+    });
+
+    // Record current file sizes so only data appended after startup is streamed
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut offsets = HashMap::new();
+        for claude_path in core::discover_claude_paths() {
+            if let Ok(files) = core::find_jsonl_files(&claude_path.join("projects")) {
+                for file in files {
+                    if let Ok(metadata) = file.metadata() {
+                        offsets.insert(file, metadata.len());
+                    }
+                }
+            }
+        }
+
+        // Only the active block window is kept; older lines are never streamed anyway
+        let entries = core::cached_entries();
+        let cutoff = Utc::now() - Duration::hours(BLOCK_HOURS);
+        let recent: Vec<UsageEntry> = entries.iter().filter(|e| e.timestamp >= cutoff).cloned().collect();
+
+        let feed = app.state::<LiveFeed>();
+        let pending = {
+            let mut state = feed.state.lock().unwrap();
+            state.offsets = offsets;
+            state.seen = recent.iter().filter_map(|e| Some((e.dedup_key()?, e.timestamp))).collect();
+            state.recent = recent;
+            state.ready = true;
+            std::mem::take(&mut state.pending)
+        };
+
+        // Changes reported while seeding are read from the recorded offsets
+        for path in pending {
+            file_changed(&app, &path);
+        }
+    });
+}
+
+pub fn subscribe(app: &AppHandle) -> broadcast::Receiver<LiveEvent> {
+    app.state::<LiveFeed>().sender.subscribe()
+}
+
+/// Summarises the current block from the entries seen in the last few hours
+pub fn active_block(app: &AppHandle) -> Option<BlockUsage> {
+    let mode = CostMode::parse(&load_settings(app).cost_mode);
+    let feed = app.state::<LiveFeed>();
+    let state = feed.state.lock().unwrap();
+    reports::active_block(&state.recent, mode, Utc::now())
+}
+
+/// Reads whatever was appended to `path` since the last call and broadcasts it
+pub fn file_changed(app: &AppHandle, path: &Path) {
+    let feed = app.state::<LiveFeed>();
+    let new_entries = loop {
+        let offset = {
+            let mut state = feed.state.lock().unwrap();
+            if !state.ready {
+                state.pending.insert(path.to_path_buf());
+                return;
+            }
+            state.offsets.get(path).copied().unwrap_or(0)
+        };
+
+        // The file is read without holding the lock
+        let (content, next_offset) = match read_appended(path, offset) {
+            Ok(read) => read,
+            Err(e) => {
+                warn!("Failed to read {:?}: {}", path, e);
+                return;
+            }
+        };
+        let parsed = usage::parse_content(&content, path);
+
+        let mut state = feed.state.lock().unwrap();
+        // Another event already consumed this range, so read again from where it stopped
+        if state.offsets.get(path).copied().unwrap_or(0) != offset {
+            continue;
+        }
+        state.offsets.insert(path.to_path_buf(), next_offset);
+
+        let cutoff = state.prune();
+        let mut new_entries = Vec::new();
+        for entry in parsed.entries {
+            // Resumed sessions copy earlier history; anything before the window is not live
+            if entry.timestamp < cutoff {
+                continue;
+            }
+            if let Some(key) = entry.dedup_key() {
+                if state.seen.insert(key, entry.timestamp).is_some() {
+                    continue;
+                }
+            }
+            new_entries.push(entry);
+        }
+
+        state.recent.extend(new_entries.iter().cloned());
+        break new_entries;
+    };
+
+    if new_entries.is_empty() {
+        return;
+    }
+
+    // Sending only fails when nobody is subscribed
+    let _ = feed.sender.send(LiveEvent::Entries { entries: new_entries });
+    let _ = feed.sender.send(LiveEvent::ActiveBlock { block: active_block(app) });
+}
+
+/// Forgets the read position of a deleted file
+pub fn file_removed(app: &AppHandle, path: &Path) {
+    let feed = app.state::<LiveFeed>();
+    let mut state = feed.state.lock().unwrap();
+    state.offsets.remove(path);
+    state.pending.remove(path);
+}
+
+/// Returns complete lines written after `offset` and the offset to resume from
+fn read_appended(path: &Path, offset: u64) -> std::io::Result<(String, u64)> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    // A shorter file was truncated or replaced, so start over
+    let offset = if len < offset { 0 } else { offset };
+
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+
+    // Leave a partially written last line for the next event
+    let complete = buf.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    buf.truncate(complete);
+
+    Ok((String::from_utf8_lossy(&buf).into_owned(), offset + complete as u64))
+}
@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+use crate::settings::LoggingSettings;
+
+/// Number of recent log lines kept in memory for `get_logs`
+const BUFFER_CAPACITY: usize = 2000;
+
+/// Rotated log files kept in the app log directory
+const MAX_LOG_FILES: usize = 7;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+static BUFFER: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// Installs the global subscriber: a daily-rotated file in the app log directory, an
+/// in-memory buffer for the debug console and, in debug builds, stdout
+pub fn init(app: &AppHandle, settings: &LoggingSettings) -> Result<(), Box<dyn std::error::Error>> {
+    let log_dir = app.path().app_log_dir()?;
+    std::fs::create_dir_all(&log_dir)?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("claudedeck")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&log_dir)?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+
+    let filter = build_filter(settings).unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let stdout = cfg!(debug_assertions).then(fmt::layer);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(file_writer).with_ansi(false))
+        .with(BufferLayer)
+        .with(stdout)
+        .try_init()?;
+
+    let _ = FILTER.set(handle);
+    let _ = FILE_GUARD.set(guard);
+
+    tracing::info!(log_dir = %log_dir.display(), "Logging initialized");
+    Ok(())
+}
+
+/// Rejects log level settings that do not form a valid filter, before they are saved
+pub fn check_settings(settings: &LoggingSettings) -> Result<(), String> {
+    build_filter(settings).map(|_| ())
+}
+
+/// Applies new level settings to the running subscriber
+pub fn apply_settings(settings: &LoggingSettings) -> Result<(), String> {
+    let filter = build_filter(settings)?;
+    if let Some(handle) = FILTER.get() {
+        handle
+            .reload(filter)
+            .map_err(|e| format!("Failed to update log levels: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Builds an `EnvFilter` from the global level and per-module overrides. Bare module
+/// names such as `file_monitor` are resolved relative to this crate.
+fn build_filter(settings: &LoggingSettings) -> Result<EnvFilter, String> {
+    let mut directives = vec![settings.level.clone()];
+    for (module, level) in &settings.modules {
+        let target = if module.contains("::") {
+            module.clone()
+        } else {
+            format!("{}::{}", env!("CARGO_CRATE_NAME"), module)
+        };
+        directives.push(format!("{}={}", target, level));
+    }
+
+    EnvFilter::try_new(directives.join(","))
+        .map_err(|e| format!("Invalid log level configuration: {}", e))
+}
+
+/// Returns buffered log lines, oldest first, filtered by minimum level, target prefix
+/// and message text
+pub fn recent_logs(
+    level: Option<&str>,
+    target: Option<&str>,
+    search: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = level
+        .map(|level| level.parse::<Level>().map_err(|_| format!("Invalid log level: {}", level)))
+        .transpose()?;
+    let search = search.map(str::to_lowercase);
+
+    let buffer = BUFFER.lock().unwrap();
+    let mut logs: Vec<LogEntry> = buffer
+        .iter()
+        .rev()
+        .filter(|entry| {
+            // Lower verbosity compares as greater: ERROR < WARN < ... < TRACE
+            min_level.is_none_or(|min| entry.level.parse::<Level>().is_ok_and(|l| l <= min))
+        })
+        .filter(|entry| target.is_none_or(|target| entry.target.contains(target)))
+        .filter(|entry| {
+            search
+                .as_deref()
+                .is_none_or(|search| entry.message.to_lowercase().contains(search))
+        })
+        .take(limit.unwrap_or(BUFFER_CAPACITY))
+        .cloned()
+        .collect();
+
+    logs.reverse();
+    Ok(logs)
+}
+
+struct BufferLayer;
+
+impl<S: Subscriber> Layer<S> for BufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let entry = LogEntry {
+            timestamp: Utc::now(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.finish(),
+        };
+
+        let mut buffer = BUFFER.lock().unwrap();
+        if buffer.len() == BUFFER_CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(entry);
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else {
+            format!("{} {}", self.message, self.fields.join(" "))
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={}", field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
@@ -1,43 +1,32 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    #[cfg(windows)]
+    if tauri_app_lib::cli::is_command(&args) {
+        attach_parent_console();
+    }
+    if let Some(code) = tauri_app_lib::cli::run(&args) {
+        std::process::exit(code);
+    }
 
-mod commands;
-mod core;
-mod settings;
-mod tray;
-mod file_monitor;
+    tauri_app_lib::run();
+}
 
-fn main() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_store::Builder::default().build())
-        .setup(|app| {
-            // Initialize system tray
-            tray::init(&app.handle())?;
-            
-            // Initialize settings
-            settings::init(&app.handle())?;
-            
-            // Initialize file monitoring
-            file_monitor::init(app.handle().clone())?;
-            
-            Ok(())
-        })
-        .invoke_handler(tauri::generate_handler![
-            commands::load_usage_entries,
-            commands::export_data,
-            commands::get_settings,
-            commands::save_settings,
-            commands::update_tray_title,
-            commands::set_tray_tooltip,
-            commands::show_popup_window,
-            commands::hide_popup_window,
-            commands::get_popup_position,
-            commands::show_main_window,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
\ No newline at end of file
+/// Release builds use the GUI subsystem and start without a console, so subcommands write
+/// to the console of the shell they were run from. Launched from Explorer there is none to
+/// attach to and output is dropped.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::core::LoadStats;
+use crate::pricing::{calculate_cost, CostMode};
+use crate::reports;
+use crate::usage::UsageEntry;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Default)]
+struct ModelTotals {
+    input: u64,
+    output: u64,
+    cache_creation: u64,
+    cache_read: u64,
+    requests: u64,
+    cost: f64,
+}
+
+/// Renders usage and loader health in the Prometheus text exposition format
+pub fn render(
+    entries: &[UsageEntry],
+    mode: CostMode,
+    stats: Option<&LoadStats>,
+    now: DateTime<Utc>,
+) -> String {
+    let mut models: BTreeMap<&str, ModelTotals> = BTreeMap::new();
+    let mut projects: BTreeMap<&str, f64> = BTreeMap::new();
+
+    for entry in entries {
+        let cost = calculate_cost(entry, mode);
+        let totals = models.entry(entry.model.as_deref().unwrap_or("unknown")).or_default();
+        totals.input += entry.input_tokens;
+        totals.output += entry.output_tokens;
+        totals.cache_creation += entry.cache_creation_tokens;
+        totals.cache_read += entry.cache_read_tokens;
+        totals.requests += 1;
+        totals.cost += cost;
+        *projects.entry(entry.project.as_str()).or_default() += cost;
+    }
+
+    let mut out = String::new();
+
+    header(&mut out, "claudedeck_tokens_total", "counter", "Tokens used by model and token type");
+    for (model, totals) in &models {
+        for (kind, value) in [
+            ("input", totals.input),
+            ("output", totals.output),
+            ("cache_creation", totals.cache_creation),
+            ("cache_read", totals.cache_read),
+        ] {
+            let _ = writeln!(
+                out,
+                "claudedeck_tokens_total{{model=\"{}\",type=\"{}\"}} {}",
+                escape(model),
+                kind,
+                value
+            );
+        }
+    }
+
+    header(&mut out, "claudedeck_requests_total", "counter", "Assistant responses with usage by model");
+    for (model, totals) in &models {
+        let _ = writeln!(out, "claudedeck_requests_total{{model=\"{}\"}} {}", escape(model), totals.requests);
+    }
+
+    header(&mut out, "claudedeck_cost_usd_total", "counter", "Cost in USD by model");
+    for (model, totals) in &models {
+        let _ = writeln!(out, "claudedeck_cost_usd_total{{model=\"{}\"}} {}", escape(model), totals.cost);
+    }
+
+    header(&mut out, "claudedeck_project_cost_usd_total", "counter", "Cost in USD by project");
+    for (project, cost) in &projects {
+        let _ = writeln!(out, "claudedeck_project_cost_usd_total{{project=\"{}\"}} {}", escape(project), cost);
+    }
+
+    let block = reports::active_block(entries, mode, now);
+    header(&mut out, "claudedeck_active_block", "gauge", "Whether a 5-hour block is currently active");
+    let _ = writeln!(out, "claudedeck_active_block {}", u8::from(block.is_some()));
+    header(&mut out, "claudedeck_active_block_tokens", "gauge", "Tokens used in the active block");
+    let _ = writeln!(
+        out,
+        "claudedeck_active_block_tokens {}",
+        block.as_ref().map_or(0, |b| b.totals.total_tokens)
+    );
+    header(&mut out, "claudedeck_active_block_cost_usd", "gauge", "Cost in USD of the active block");
+    let _ = writeln!(
+        out,
+        "claudedeck_active_block_cost_usd {}",
+        block.as_ref().map_or(0.0, |b| b.totals.total_cost)
+    );
+    header(&mut out, "claudedeck_active_block_remaining_seconds", "gauge", "Seconds until the active block ends");
+    let _ = writeln!(
+        out,
+        "claudedeck_active_block_remaining_seconds {}",
+        block.as_ref().map_or(0, |b| (b.end_time - now).num_seconds().max(0))
+    );
+
+    if let Some(stats) = stats {
+        header(&mut out, "claudedeck_loader_files_scanned", "gauge", "JSONL files read by the last scan");
+        let _ = writeln!(out, "claudedeck_loader_files_scanned {}", stats.files_scanned);
+        header(&mut out, "claudedeck_loader_parse_errors", "gauge", "Malformed lines seen by the last scan");
+        let _ = writeln!(out, "claudedeck_loader_parse_errors {}", stats.parse_errors);
+        header(&mut out, "claudedeck_loader_last_scan_duration_seconds", "gauge", "Duration of the last scan");
+        let _ = writeln!(
+            out,
+            "claudedeck_loader_last_scan_duration_seconds {}",
+            stats.duration.as_secs_f64()
+        );
+        if let Some(finished_at) = stats.finished_at {
+            header(&mut out, "claudedeck_loader_last_scan_timestamp_seconds", "gauge", "Unix time the last scan finished");
+            let _ = writeln!(out, "claudedeck_loader_last_scan_timestamp_seconds {}", finished_at.timestamp());
+        }
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Escapes a label value per the exposition format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::usage::UsageEntry;
+
+/// How costs are derived, mirroring the `CostMode` setting in the frontend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostMode {
+    #[default]
+    Auto,
+    Calculate,
+    Display,
+}
+
+impl CostMode {
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "calculate" => CostMode::Calculate,
+            "display" => CostMode::Display,
+            _ => CostMode::Auto,
+        }
+    }
+}
+
+/// Per-token prices in USD
+#[derive(Debug, Clone, Copy)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_creation: f64,
+    pub cache_read: f64,
+}
+
+const SONNET_PRICING: ModelPricing = ModelPricing {
+    input: 0.000003,
+    output: 0.000015,
+    cache_creation: 0.00000375,
+    cache_read: 0.0000003,
+};
+
+const OPUS_PRICING: ModelPricing = ModelPricing {
+    input: 0.000015,
+    output: 0.000075,
+    cache_creation: 0.00001875,
+    cache_read: 0.0000015,
+};
+
+/// Hard-coded pricing for known Claude models, kept in sync with `data-processor.ts`
+pub fn pricing_for(model: &str) -> Option<ModelPricing> {
+    match model {
+        "claude-sonnet-4-20250514" | "claude-3.5-sonnet-20241022" => Some(SONNET_PRICING),
+        "claude-opus-4-20250514" | "claude-3-opus-20240229" => Some(OPUS_PRICING),
+        _ => None,
+    }
+}
+
+/// Calculates the cost of a single usage entry according to the cost mode
+pub fn calculate_cost(entry: &UsageEntry, mode: CostMode) -> f64 {
+    match mode {
+        CostMode::Display => return entry.cost_usd.unwrap_or(0.0),
+        CostMode::Auto => {
+            if let Some(cost) = entry.cost_usd {
+                return cost;
+            }
+        }
+        CostMode::Calculate => {}
+    }
+
+    let Some(pricing) = entry.model.as_deref().and_then(pricing_for) else {
+        return 0.0;
+    };
+
+    entry.input_tokens as f64 * pricing.input
+        + entry.output_tokens as f64 * pricing.output
+        + entry.cache_creation_tokens as f64 * pricing.cache_creation
+        + entry.cache_read_tokens as f64 * pricing.cache_read
+}
+
+/// List-price cost of a single entry had every prompt token been billed as plain input.
+/// Models without known pricing cost nothing.
+pub fn uncached_cost(entry: &UsageEntry) -> f64 {
+    let Some(pricing) = entry.model.as_deref().and_then(pricing_for) else {
+        return 0.0;
+    };
+
+    (entry.input_tokens + entry.cache_creation_tokens + entry.cache_read_tokens) as f64 * pricing.input
+        + entry.output_tokens as f64 * pricing.output
+}
+
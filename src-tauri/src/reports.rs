@@ -0,0 +1,724 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::pricing::{self, calculate_cost, CostMode};
+use crate::usage::UsageEntry;
+
+/// Length of a Claude usage block
+pub const BLOCK_HOURS: i64 = 5;
+
+/// Name reported for entries that do not record a model
+pub const UNKNOWN_MODEL: &str = "unknown";
+
+/// Name reported for entries recorded on this machine rather than imported
+pub const LOCAL_MACHINE: &str = "local";
+
+/// Sessions that wrote at least this many cache tokens are checked for poor reuse
+pub const POOR_REUSE_MIN_WRITE_TOKENS: u64 = 10_000;
+
+/// Sessions that read back fewer cache tokens per token written than this reuse it poorly
+pub const POOR_REUSE_AMORTIZATION: f64 = 1.0;
+
+/// Token and cost totals shared by every report row
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub total_cost: f64,
+}
+
+impl TokenTotals {
+    pub fn add(&mut self, entry: &UsageEntry, mode: CostMode) {
+        self.input_tokens += entry.input_tokens;
+        self.output_tokens += entry.output_tokens;
+        self.cache_creation_tokens += entry.cache_creation_tokens;
+        self.cache_read_tokens += entry.cache_read_tokens;
+        self.total_tokens += entry.total_tokens();
+        self.total_cost += calculate_cost(entry, mode);
+    }
+
+    /// Fraction of prompt tokens served from the cache rather than sent or written to it
+    pub fn cache_hit_ratio(&self) -> f64 {
+        let prompt = self.input_tokens + self.cache_creation_tokens + self.cache_read_tokens;
+        ratio(self.cache_read_tokens as f64, prompt as f64)
+    }
+}
+
+/// `part / whole`, or 0 when `whole` is 0
+pub fn ratio(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 {
+        part / whole
+    } else {
+        0.0
+    }
+}
+
+/// One model's share of a report row, in the shape of ccusage's `modelBreakdowns`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelBreakdown {
+    pub model_name: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    pub date: String,
+    pub models: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub model_breakdowns: Vec<ModelBreakdown>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyUsage {
+    pub month: String,
+    pub models: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub model_breakdowns: Vec<ModelBreakdown>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsage {
+    pub session_id: String,
+    pub project_path: String,
+    pub models: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub last_activity: DateTime<Utc>,
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockUsage {
+    pub block_id: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub models: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub is_active: bool,
+    pub model_breakdowns: Vec<ModelBreakdown>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub requests: u64,
+    /// Fraction of the report's cost spent on this model
+    pub cost_share: f64,
+    pub cache_hit_ratio: f64,
+    pub average_tokens_per_request: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectUsage {
+    pub project_path: String,
+    pub models: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub sessions: usize,
+    pub last_activity: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MachineUsage {
+    pub machine: String,
+    pub models: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub sessions: usize,
+    pub last_activity: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserUsage {
+    pub user: String,
+    pub machines: Vec<String>,
+    pub models: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    pub sessions: usize,
+    pub projects: usize,
+    pub last_activity: DateTime<Utc>,
+}
+
+/// What a leaderboard ranks users by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LeaderboardMetric {
+    Cost,
+    Tokens,
+    Sessions,
+    Projects,
+}
+
+impl LeaderboardMetric {
+    pub const ALL: [LeaderboardMetric; 4] = [
+        LeaderboardMetric::Cost,
+        LeaderboardMetric::Tokens,
+        LeaderboardMetric::Sessions,
+        LeaderboardMetric::Projects,
+    ];
+
+    fn value(self, user: &UserUsage) -> f64 {
+        match self {
+            LeaderboardMetric::Cost => user.totals.total_cost,
+            LeaderboardMetric::Tokens => user.totals.total_tokens as f64,
+            LeaderboardMetric::Sessions => user.sessions as f64,
+            LeaderboardMetric::Projects => user.projects as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardRow {
+    /// Users with equal values share a rank, and the next rank skips accordingly
+    pub rank: usize,
+    pub user: String,
+    pub value: f64,
+}
+
+/// Inclusive date range used to narrow entries before aggregation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DateRange {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Builds a range from optional `YYYY-MM-DD` or `YYYYMMDD` strings
+    pub fn parse(since: Option<&str>, until: Option<&str>) -> Result<Self, String> {
+        Ok(Self {
+            since: since.map(parse_date).transpose()?,
+            until: until.map(parse_date).transpose()?,
+        })
+    }
+
+    pub fn contains(&self, entry: &UsageEntry) -> bool {
+        let date = entry.timestamp.date_naive();
+        self.since.is_none_or(|since| date >= since) && self.until.is_none_or(|until| date <= until)
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y%m%d"))
+        .map_err(|_| format!("Invalid date: {}", value))
+}
+
+fn push_model(models: &mut Vec<String>, entry: &UsageEntry) {
+    if let Some(model) = &entry.model {
+        if !models.contains(model) {
+            models.push(model.clone());
+        }
+    }
+}
+
+/// Adds an entry to the breakdown row of its model
+fn add_breakdown(breakdowns: &mut Vec<ModelBreakdown>, entry: &UsageEntry, mode: CostMode) {
+    let model = entry.model.as_deref().unwrap_or(UNKNOWN_MODEL);
+    let index = match breakdowns.iter().position(|breakdown| breakdown.model_name == model) {
+        Some(index) => index,
+        None => {
+            breakdowns.push(ModelBreakdown {
+                model_name: model.to_string(),
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                cost: 0.0,
+            });
+            breakdowns.len() - 1
+        }
+    };
+    let breakdown = &mut breakdowns[index];
+    breakdown.input_tokens += entry.input_tokens;
+    breakdown.output_tokens += entry.output_tokens;
+    breakdown.cache_creation_tokens += entry.cache_creation_tokens;
+    breakdown.cache_read_tokens += entry.cache_read_tokens;
+    breakdown.cost += calculate_cost(entry, mode);
+}
+
+/// Orders breakdowns most expensive first
+fn sort_breakdowns(breakdowns: &mut [ModelBreakdown]) {
+    breakdowns.sort_by(|a, b| b.cost.total_cmp(&a.cost).then_with(|| a.model_name.cmp(&b.model_name)));
+}
+
+/// Aggregates entries per UTC day, newest first
+pub fn daily_report(entries: &[UsageEntry], mode: CostMode) -> Vec<DailyUsage> {
+    let mut days: BTreeMap<String, DailyUsage> = BTreeMap::new();
+
+    for entry in entries {
+        let date = entry.timestamp.format("%Y-%m-%d").to_string();
+        let day = days.entry(date.clone()).or_insert_with(|| DailyUsage {
+            date,
+            models: Vec::new(),
+            totals: TokenTotals::default(),
+            model_breakdowns: Vec::new(),
+        });
+        push_model(&mut day.models, entry);
+        day.totals.add(entry, mode);
+        add_breakdown(&mut day.model_breakdowns, entry, mode);
+    }
+
+    let mut days: Vec<DailyUsage> = days.into_values().rev().collect();
+    days.iter_mut().for_each(|day| sort_breakdowns(&mut day.model_breakdowns));
+    days
+}
+
+/// Aggregates entries per UTC month, newest first
+pub fn monthly_report(entries: &[UsageEntry], mode: CostMode) -> Vec<MonthlyUsage> {
+    let mut months: BTreeMap<String, MonthlyUsage> = BTreeMap::new();
+
+    for entry in entries {
+        let month = entry.timestamp.format("%Y-%m").to_string();
+        let row = months.entry(month.clone()).or_insert_with(|| MonthlyUsage {
+            month,
+            models: Vec::new(),
+            totals: TokenTotals::default(),
+            model_breakdowns: Vec::new(),
+        });
+        push_model(&mut row.models, entry);
+        row.totals.add(entry, mode);
+        add_breakdown(&mut row.model_breakdowns, entry, mode);
+    }
+
+    let mut months: Vec<MonthlyUsage> = months.into_values().rev().collect();
+    months.iter_mut().for_each(|month| sort_breakdowns(&mut month.model_breakdowns));
+    months
+}
+
+/// Aggregates entries per transcript session, most recently active first
+pub fn session_report(entries: &[UsageEntry], mode: CostMode) -> Vec<SessionUsage> {
+    let mut sessions: HashMap<(String, String), SessionUsage> = HashMap::new();
+
+    for entry in entries {
+        let key = (entry.project.clone(), entry.session_id.clone());
+        let session = sessions.entry(key).or_insert_with(|| SessionUsage {
+            session_id: entry.session_id.clone(),
+            project_path: entry.project.clone(),
+            models: Vec::new(),
+            totals: TokenTotals::default(),
+            last_activity: entry.timestamp,
+            versions: Vec::new(),
+        });
+        push_model(&mut session.models, entry);
+        if let Some(version) = &entry.version {
+            if !session.versions.contains(version) {
+                session.versions.push(version.clone());
+            }
+        }
+        session.last_activity = session.last_activity.max(entry.timestamp);
+        session.totals.add(entry, mode);
+    }
+
+    let mut sessions: Vec<SessionUsage> = sessions.into_values().collect();
+    sessions.sort_by_key(|session| Reverse(session.last_activity));
+    sessions
+}
+
+/// Aggregates entries into fixed 5-hour blocks, newest first
+pub fn block_report(entries: &[UsageEntry], mode: CostMode, now: DateTime<Utc>) -> Vec<BlockUsage> {
+    let block_secs = BLOCK_HOURS * 60 * 60;
+    let mut blocks: BTreeMap<i64, BlockUsage> = BTreeMap::new();
+
+    for entry in entries {
+        let start_secs = entry.timestamp.timestamp().div_euclid(block_secs) * block_secs;
+        let block = blocks.entry(start_secs).or_insert_with(|| {
+            let start_time = DateTime::from_timestamp(start_secs, 0).unwrap_or_default();
+            let end_time = start_time + Duration::hours(BLOCK_HOURS);
+            BlockUsage {
+                block_id: start_time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                start_time,
+                end_time,
+                models: Vec::new(),
+                totals: TokenTotals::default(),
+                is_active: now >= start_time && now < end_time,
+                model_breakdowns: Vec::new(),
+            }
+        });
+        push_model(&mut block.models, entry);
+        block.totals.add(entry, mode);
+        add_breakdown(&mut block.model_breakdowns, entry, mode);
+    }
+
+    let mut blocks: Vec<BlockUsage> = blocks.into_values().rev().collect();
+    blocks.iter_mut().for_each(|block| sort_breakdowns(&mut block.model_breakdowns));
+    blocks
+}
+
+/// Returns the block containing `now`, if any usage was recorded in it
+pub fn active_block(entries: &[UsageEntry], mode: CostMode, now: DateTime<Utc>) -> Option<BlockUsage> {
+    block_report(entries, mode, now).into_iter().find(|block| block.is_active)
+}
+
+/// Aggregates entries per model, most expensive first, with each model's token mix, cost
+/// share, cache hit ratio and average tokens per request
+pub fn model_report(entries: &[UsageEntry], mode: CostMode) -> Vec<ModelUsage> {
+    let mut models: HashMap<&str, ModelUsage> = HashMap::new();
+
+    for entry in entries {
+        let model = entry.model.as_deref().unwrap_or(UNKNOWN_MODEL);
+        let row = models.entry(model).or_insert_with(|| ModelUsage {
+            model: model.to_string(),
+            totals: TokenTotals::default(),
+            requests: 0,
+            cost_share: 0.0,
+            cache_hit_ratio: 0.0,
+            average_tokens_per_request: 0.0,
+        });
+        row.totals.add(entry, mode);
+        row.requests += 1;
+    }
+
+    let total_cost: f64 = models.values().map(|row| row.totals.total_cost).sum();
+    let mut models: Vec<ModelUsage> = models
+        .into_values()
+        .map(|mut row| {
+            row.cost_share = ratio(row.totals.total_cost, total_cost);
+            row.cache_hit_ratio = row.totals.cache_hit_ratio();
+            row.average_tokens_per_request = ratio(row.totals.total_tokens as f64, row.requests as f64);
+            row
+        })
+        .collect();
+    models.sort_by(|a, b| {
+        b.totals
+            .total_cost
+            .total_cmp(&a.totals.total_cost)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+    models
+}
+
+/// Aggregates entries per project directory, most expensive first
+pub fn project_report(entries: &[UsageEntry], mode: CostMode) -> Vec<ProjectUsage> {
+    let mut projects: HashMap<&str, (ProjectUsage, HashSet<&str>)> = HashMap::new();
+
+    for entry in entries {
+        let (project, sessions) = projects.entry(&entry.project).or_insert_with(|| {
+            let project = ProjectUsage {
+                project_path: entry.project.clone(),
+                models: Vec::new(),
+                totals: TokenTotals::default(),
+                sessions: 0,
+                last_activity: entry.timestamp,
+            };
+            (project, HashSet::new())
+        });
+        push_model(&mut project.models, entry);
+        project.totals.add(entry, mode);
+        project.last_activity = project.last_activity.max(entry.timestamp);
+        sessions.insert(&entry.session_id);
+    }
+
+    let mut projects: Vec<ProjectUsage> = projects
+        .into_values()
+        .map(|(mut project, sessions)| {
+            project.sessions = sessions.len();
+            project
+        })
+        .collect();
+    projects.sort_by(|a, b| {
+        b.totals
+            .total_cost
+            .total_cmp(&a.totals.total_cost)
+            .then_with(|| a.project_path.cmp(&b.project_path))
+    });
+    projects
+}
+
+/// A session is unique by project and session id
+type SessionKey<'a> = (&'a str, &'a str);
+
+/// Aggregates entries per source machine, most expensive first
+pub fn machine_report(entries: &[UsageEntry], mode: CostMode) -> Vec<MachineUsage> {
+    let mut machines: HashMap<&str, (MachineUsage, HashSet<SessionKey>)> = HashMap::new();
+
+    for entry in entries {
+        let name = entry.machine.as_deref().unwrap_or(LOCAL_MACHINE);
+        let (machine, sessions) = machines.entry(name).or_insert_with(|| {
+            let machine = MachineUsage {
+                machine: name.to_string(),
+                models: Vec::new(),
+                totals: TokenTotals::default(),
+                sessions: 0,
+                last_activity: entry.timestamp,
+            };
+            (machine, HashSet::new())
+        });
+        push_model(&mut machine.models, entry);
+        machine.totals.add(entry, mode);
+        machine.last_activity = machine.last_activity.max(entry.timestamp);
+        sessions.insert((&entry.project, &entry.session_id));
+    }
+
+    let mut machines: Vec<MachineUsage> = machines
+        .into_values()
+        .map(|(mut machine, sessions)| {
+            machine.sessions = sessions.len();
+            machine
+        })
+        .collect();
+    machines.sort_by(|a, b| {
+        b.totals
+            .total_cost
+            .total_cmp(&a.totals.total_cost)
+            .then_with(|| a.machine.cmp(&b.machine))
+    });
+    machines
+}
+
+/// Aggregates entries per user, most expensive first. Entries without a user, i.e. personal
+/// data, count as this machine's user.
+pub fn user_report(entries: &[UsageEntry], mode: CostMode) -> Vec<UserUsage> {
+    type UserSets<'a> = (HashSet<SessionKey<'a>>, HashSet<&'a str>);
+    let mut users: HashMap<&str, (UserUsage, UserSets)> = HashMap::new();
+
+    for entry in entries {
+        let name = entry.user.as_deref().unwrap_or(LOCAL_MACHINE);
+        let (user, (sessions, projects)) = users.entry(name).or_insert_with(|| {
+            let user = UserUsage {
+                user: name.to_string(),
+                machines: Vec::new(),
+                models: Vec::new(),
+                totals: TokenTotals::default(),
+                sessions: 0,
+                projects: 0,
+                last_activity: entry.timestamp,
+            };
+            (user, (HashSet::new(), HashSet::new()))
+        });
+        let machine = entry.machine.as_deref().unwrap_or(LOCAL_MACHINE);
+        if !user.machines.iter().any(|known| known == machine) {
+            user.machines.push(machine.to_string());
+        }
+        push_model(&mut user.models, entry);
+        user.totals.add(entry, mode);
+        user.last_activity = user.last_activity.max(entry.timestamp);
+        sessions.insert((&entry.project, &entry.session_id));
+        projects.insert(&entry.project);
+    }
+
+    let mut users: Vec<UserUsage> = users
+        .into_values()
+        .map(|(mut user, (sessions, projects))| {
+            user.machines.sort();
+            user.sessions = sessions.len();
+            user.projects = projects.len();
+            user
+        })
+        .collect();
+    users.sort_by(|a, b| {
+        b.totals
+            .total_cost
+            .total_cmp(&a.totals.total_cost)
+            .then_with(|| a.user.cmp(&b.user))
+    });
+    users
+}
+
+/// Ranks users by `metric`, highest first, keeping at most `limit` rows
+pub fn leaderboard(users: &[UserUsage], metric: LeaderboardMetric, limit: usize) -> Vec<LeaderboardRow> {
+    let mut ranked: Vec<(&str, f64)> = users.iter().map(|user| (user.user.as_str(), metric.value(user))).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut rows: Vec<LeaderboardRow> = Vec::new();
+    for (index, (user, value)) in ranked.into_iter().take(limit).enumerate() {
+        let rank = match rows.last() {
+            Some(previous) if previous.value == value => previous.rank,
+            _ => index + 1,
+        };
+        rows.push(LeaderboardRow {
+            rank,
+            user: user.to_string(),
+            value,
+        });
+    }
+    rows
+}
+
+/// Prompt cache use of a group of entries, alongside its usual totals
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEfficiency {
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+    /// Cache read tokens over input, cache creation and cache read tokens
+    pub cache_read_ratio: f64,
+    /// Cache read tokens per cache creation token: how often each cache write was read back
+    pub write_amortization: f64,
+    /// Estimated cost at list prices had every prompt token been billed as plain input
+    pub uncached_cost: f64,
+    /// Cost of the same tokens at list prices, whatever the cost mode
+    pub calculated_cost: f64,
+    /// `uncachedCost - calculatedCost`; negative when cache writes did not pay off
+    pub estimated_savings: f64,
+}
+
+impl CacheEfficiency {
+    fn add(&mut self, entry: &UsageEntry, mode: CostMode) {
+        self.totals.add(entry, mode);
+        self.uncached_cost += pricing::uncached_cost(entry);
+        self.calculated_cost += calculate_cost(entry, CostMode::Calculate);
+    }
+
+    /// Derives the ratios once every entry has been added
+    fn finish(&mut self) {
+        self.cache_read_ratio = self.totals.cache_hit_ratio();
+        self.write_amortization = ratio(
+            self.totals.cache_read_tokens as f64,
+            self.totals.cache_creation_tokens as f64,
+        );
+        self.estimated_savings = self.uncached_cost - self.calculated_cost;
+    }
+
+    fn is_poor_reuse(&self) -> bool {
+        self.totals.cache_creation_tokens >= POOR_REUSE_MIN_WRITE_TOKENS
+            && self.write_amortization < POOR_REUSE_AMORTIZATION
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyCacheEfficiency {
+    pub date: String,
+    #[serde(flatten)]
+    pub cache: CacheEfficiency,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCacheEfficiency {
+    pub project_path: String,
+    #[serde(flatten)]
+    pub cache: CacheEfficiency,
+    pub sessions: usize,
+    pub poor_reuse_sessions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCacheEfficiency {
+    pub session_id: String,
+    pub project_path: String,
+    #[serde(flatten)]
+    pub cache: CacheEfficiency,
+    pub last_activity: DateTime<Utc>,
+}
+
+/// Cache efficiency overall, per UTC day (newest first) and per project (most expensive
+/// first), with the sessions that reused their cache writes poorly (most written first)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheReport {
+    pub totals: CacheEfficiency,
+    pub days: Vec<DailyCacheEfficiency>,
+    pub projects: Vec<ProjectCacheEfficiency>,
+    pub poor_reuse_sessions: Vec<SessionCacheEfficiency>,
+}
+
+/// Aggregates prompt cache use. Savings are estimated at list prices, whatever the cost mode.
+pub fn cache_report(entries: &[UsageEntry], mode: CostMode) -> CacheReport {
+    let mut totals = CacheEfficiency::default();
+    let mut days: BTreeMap<String, CacheEfficiency> = BTreeMap::new();
+    let mut projects: HashMap<&str, CacheEfficiency> = HashMap::new();
+    let mut sessions: HashMap<SessionKey, SessionCacheEfficiency> = HashMap::new();
+
+    for entry in entries {
+        totals.add(entry, mode);
+        days.entry(entry.timestamp.format("%Y-%m-%d").to_string())
+            .or_default()
+            .add(entry, mode);
+        projects.entry(&entry.project).or_default().add(entry, mode);
+        let session = sessions
+            .entry((&entry.project, &entry.session_id))
+            .or_insert_with(|| SessionCacheEfficiency {
+                session_id: entry.session_id.clone(),
+                project_path: entry.project.clone(),
+                cache: CacheEfficiency::default(),
+                last_activity: entry.timestamp,
+            });
+        session.cache.add(entry, mode);
+        session.last_activity = session.last_activity.max(entry.timestamp);
+    }
+
+    totals.finish();
+    let days = days
+        .into_iter()
+        .rev()
+        .map(|(date, mut cache)| {
+            cache.finish();
+            DailyCacheEfficiency { date, cache }
+        })
+        .collect();
+
+    let mut project_sessions: HashMap<&str, (usize, usize)> = HashMap::new();
+    let mut poor_reuse_sessions = Vec::new();
+    for ((project, _), mut session) in sessions {
+        session.cache.finish();
+        let (count, poor) = project_sessions.entry(project).or_default();
+        *count += 1;
+        if session.cache.is_poor_reuse() {
+            *poor += 1;
+            poor_reuse_sessions.push(session);
+        }
+    }
+    poor_reuse_sessions.sort_by(|a: &SessionCacheEfficiency, b| {
+        b.cache
+            .totals
+            .cache_creation_tokens
+            .cmp(&a.cache.totals.cache_creation_tokens)
+            .then_with(|| b.last_activity.cmp(&a.last_activity))
+    });
+
+    let mut projects: Vec<ProjectCacheEfficiency> = projects
+        .into_iter()
+        .map(|(project, mut cache)| {
+            cache.finish();
+            let (sessions, poor_reuse_sessions) = project_sessions[project];
+            ProjectCacheEfficiency {
+                project_path: project.to_string(),
+                cache,
+                sessions,
+                poor_reuse_sessions,
+            }
+        })
+        .collect();
+    projects.sort_by(|a, b| {
+        b.cache
+            .totals
+            .total_cost
+            .total_cmp(&a.cache.totals.total_cost)
+            .then_with(|| a.project_path.cmp(&b.project_path))
+    });
+
+    CacheReport {
+        totals,
+        days,
+        projects,
+        poor_reuse_sessions,
+    }
+}
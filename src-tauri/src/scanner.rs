@@ -0,0 +1,436 @@
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::usage::{self, UsageEntry};
+
+/// Upper bound on worker threads, and so on files open for reading at once
+const MAX_THREADS: usize = 8;
+
+/// Directory nesting beyond which the walk stops, guarding against symlink fan-out
+const MAX_DEPTH: usize = 32;
+
+/// Minimum gap between progress callbacks
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Error returned by a scan whose token was cancelled
+pub const CANCELLED: &str = "Load cancelled";
+
+static POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+
+fn pool() -> &'static rayon::ThreadPool {
+    POOL.get_or_init(|| {
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(MAX_THREADS);
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("scanner-{}", i))
+            .build()
+            .expect("failed to start scanner threads")
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanPhase {
+    Discovering,
+    Parsing,
+    Done,
+}
+
+/// Snapshot of scan progress reported to the UI
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanProgress {
+    pub phase: ScanPhase,
+    pub files_discovered: usize,
+    pub files_parsed: usize,
+    pub bytes_discovered: u64,
+    pub bytes_parsed: u64,
+    /// Share of discovered bytes parsed so far; 0 while discovering
+    pub percent: f64,
+}
+
+/// Shared flag that asks a running scan to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Usage entries from a scan, deduplicated and sorted oldest first
+#[derive(Debug, Default)]
+pub struct ScanResult {
+    pub entries: Vec<UsageEntry>,
+    pub files_scanned: usize,
+    pub parse_errors: usize,
+    pub bytes_parsed: u64,
+}
+
+type ProgressFn = Box<dyn Fn(&ScanProgress) + Send + Sync>;
+
+/// Parallel transcript scanner. Directories are walked as work-stealing tasks and files
+/// are parsed line by line on a bounded pool.
+#[derive(Default)]
+pub struct Scanner {
+    on_progress: Option<ProgressFn>,
+    cancel: CancelToken,
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_progress(mut self, callback: impl Fn(&ScanProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Stops the scan early, returning `CANCELLED`, once `token` is cancelled
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Finds every `.jsonl` file below `roots`, ordered by root and then path
+    pub fn discover(&self, roots: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+        let tracker = self.tracker();
+        let files = self.discover_with(roots, &tracker)?;
+        Ok(files.into_iter().map(|file| file.path).collect())
+    }
+
+    /// Loads usage from the `projects` directory of each Claude config dir. When a message
+    /// appears in several files the first file in discovery order wins.
+    pub fn scan(&self, claude_paths: &[PathBuf]) -> Result<ScanResult, String> {
+        let tracker = self.tracker();
+        let roots: Vec<PathBuf> = claude_paths.iter().map(|path| path.join("projects")).collect();
+        let files = self.discover_with(&roots, &tracker)?;
+
+        tracker.report(ScanPhase::Parsing, true);
+        let parsed: Vec<_> = pool().install(|| {
+            files
+                .par_iter()
+                .map(|file| {
+                    if tracker.cancel.is_cancelled() {
+                        return None;
+                    }
+                    let parsed = parse_file(&file.path, &tracker);
+                    tracker.files_parsed.fetch_add(1, Ordering::Relaxed);
+                    tracker.report(ScanPhase::Parsing, false);
+                    parsed
+                })
+                .collect()
+        });
+        tracker.check_cancelled()?;
+
+        let mut result = ScanResult::default();
+        let mut seen = HashSet::new();
+        for content in parsed.into_iter().flatten() {
+            result.files_scanned += 1;
+            result.parse_errors += content.malformed_lines;
+
+            for entry in content.entries {
+                // Resumed sessions repeat earlier messages; count each message once
+                if let Some(key) = entry.dedup_key() {
+                    if !seen.insert(key) {
+                        continue;
+                    }
+                }
+                result.entries.push(entry);
+            }
+        }
+        result.entries.sort_by_key(|entry| entry.timestamp);
+        result.bytes_parsed = tracker.bytes_parsed.load(Ordering::Relaxed);
+
+        tracker.report(ScanPhase::Done, true);
+        Ok(result)
+    }
+
+    /// Reads transcripts below each config dir's `projects` directory line by line, keeping
+    /// only the fields the frontend reads from each line
+    pub fn read_transcripts(&self, claude_paths: &[PathBuf]) -> Result<Vec<String>, String> {
+        let tracker = self.tracker();
+        let roots: Vec<PathBuf> = claude_paths.iter().map(|path| path.join("projects")).collect();
+        let files = self.discover_with(&roots, &tracker)?;
+
+        tracker.report(ScanPhase::Parsing, true);
+        let contents = pool().install(|| {
+            files
+                .par_iter()
+                .filter_map(|file| {
+                    if tracker.cancel.is_cancelled() {
+                        return None;
+                    }
+                    let content = read_transcript(&file.path, &tracker);
+                    tracker.files_parsed.fetch_add(1, Ordering::Relaxed);
+                    tracker.report(ScanPhase::Parsing, false);
+                    content
+                })
+                .collect()
+        });
+        tracker.check_cancelled()?;
+
+        tracker.report(ScanPhase::Done, true);
+        Ok(contents)
+    }
+
+    fn tracker(&self) -> Tracker<'_> {
+        Tracker::new(self.on_progress.as_deref(), self.cancel.clone())
+    }
+
+    fn discover_with(&self, roots: &[PathBuf], tracker: &Tracker) -> Result<Vec<DiscoveredFile>, String> {
+        let found = Mutex::new(HashMap::new());
+
+        pool().scope(|scope| {
+            for (root_index, root) in roots.iter().enumerate() {
+                let found = &found;
+                scope.spawn(move |scope| {
+                    walk(scope, root_index, root.clone(), false, Vec::new(), found, tracker)
+                });
+            }
+        });
+        tracker.check_cancelled()?;
+
+        let mut files: Vec<DiscoveredFile> = found.into_inner().unwrap().into_values().collect();
+        files.sort_by(|a, b| (a.root_index, &a.path).cmp(&(b.root_index, &b.path)));
+        tracker.report(ScanPhase::Discovering, true);
+        debug!("Discovered {} transcript files", files.len());
+        Ok(files)
+    }
+}
+
+struct DiscoveredFile {
+    via_link: bool,
+    root_index: usize,
+    path: PathBuf,
+}
+
+impl DiscoveredFile {
+    /// Real paths beat symlinked aliases, then earlier roots, then path order
+    fn rank(&self) -> (bool, usize, &Path) {
+        (self.via_link, self.root_index, &self.path)
+    }
+}
+
+/// Lists one directory, spawning a task per subdirectory. `ancestors` holds the canonical
+/// paths above `dir` so a symlink pointing back up the tree is not followed forever.
+fn walk<'s>(
+    scope: &rayon::Scope<'s>,
+    root_index: usize,
+    dir: PathBuf,
+    via_link: bool,
+    mut ancestors: Vec<PathBuf>,
+    found: &'s Mutex<HashMap<PathBuf, DiscoveredFile>>,
+    tracker: &'s Tracker,
+) {
+    if tracker.cancel.is_cancelled() {
+        return;
+    }
+    let Ok(canonical) = fs::canonicalize(&dir) else {
+        return;
+    };
+    if ancestors.contains(&canonical) {
+        warn!("Skipping symlink cycle at {:?}", dir);
+        return;
+    }
+    if ancestors.len() >= MAX_DEPTH {
+        warn!("Skipping {:?}: nested deeper than {} levels", dir, MAX_DEPTH);
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    ancestors.push(canonical.clone());
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Follows symlinks; dangling links are skipped
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+
+        let is_link = entry.file_type().is_ok_and(|t| t.is_symlink());
+
+        if metadata.is_dir() {
+            let ancestors = ancestors.clone();
+            let via_link = via_link || is_link;
+            scope.spawn(move |scope| {
+                walk(scope, root_index, path, via_link, ancestors, found, tracker)
+            });
+        } else if metadata.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+            let key = if is_link {
+                fs::canonicalize(&path).unwrap_or_else(|_| path.clone())
+            } else {
+                canonical.join(entry.file_name())
+            };
+
+            let file = DiscoveredFile {
+                via_link: via_link || is_link,
+                root_index,
+                path,
+            };
+
+            // A file reachable through several links is parsed once, under its best path
+            let mut found = found.lock().unwrap();
+            match found.get_mut(&key) {
+                Some(existing) => {
+                    if file.rank() < existing.rank() {
+                        *existing = file;
+                    }
+                }
+                None => {
+                    found.insert(key, file);
+                    drop(found);
+                    tracker.files_discovered.fetch_add(1, Ordering::Relaxed);
+                    tracker.bytes_discovered.fetch_add(metadata.len(), Ordering::Relaxed);
+                    tracker.report(ScanPhase::Discovering, false);
+                }
+            }
+        }
+    }
+}
+
+fn parse_file(path: &Path, tracker: &Tracker) -> Option<usage::ParsedContent> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("Failed to open {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    let parsed = usage::parse_reader(BufReader::new(file), path, |bytes| {
+        tracker.bytes_parsed.fetch_add(bytes as u64, Ordering::Relaxed);
+        !tracker.cancel.is_cancelled()
+    });
+    match parsed {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            warn!("Failed to read {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn read_transcript(path: &Path, tracker: &Tracker) -> Option<String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("Failed to open {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    let mut reader = BufReader::new(file);
+    let mut content = String::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(read) => {
+                tracker.bytes_parsed.fetch_add(read as u64, Ordering::Relaxed);
+            }
+            Err(e) => {
+                warn!("Failed to read {:?}: {}", path, e);
+                return None;
+            }
+        }
+        if tracker.cancel.is_cancelled() {
+            return None;
+        }
+        if let Some(slim) = usage::slim_transcript_line(&line) {
+            content.push_str(&slim);
+            content.push('\n');
+        }
+    }
+    Some(content)
+}
+
+/// Shared counters plus a throttle so callbacks fire at most every `PROGRESS_INTERVAL`
+struct Tracker<'a> {
+    callback: Option<&'a (dyn Fn(&ScanProgress) + Send + Sync)>,
+    cancel: CancelToken,
+    last_report: Mutex<Option<Instant>>,
+    files_discovered: AtomicUsize,
+    files_parsed: AtomicUsize,
+    bytes_discovered: AtomicU64,
+    bytes_parsed: AtomicU64,
+}
+
+impl<'a> Tracker<'a> {
+    fn new(callback: Option<&'a (dyn Fn(&ScanProgress) + Send + Sync)>, cancel: CancelToken) -> Self {
+        Self {
+            callback,
+            cancel,
+            last_report: Mutex::new(None),
+            files_discovered: AtomicUsize::new(0),
+            files_parsed: AtomicUsize::new(0),
+            bytes_discovered: AtomicU64::new(0),
+            bytes_parsed: AtomicU64::new(0),
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        if self.cancel.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        Ok(())
+    }
+
+    fn report(&self, phase: ScanPhase, force: bool) {
+        let Some(callback) = self.callback else {
+            return;
+        };
+
+        {
+            let mut last = self.last_report.lock().unwrap();
+            if !force && last.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL) {
+                return;
+            }
+            *last = Some(Instant::now());
+        }
+
+        let bytes_discovered = self.bytes_discovered.load(Ordering::Relaxed);
+        let bytes_parsed = self.bytes_parsed.load(Ordering::Relaxed);
+        let percent = match phase {
+            ScanPhase::Discovering => 0.0,
+            ScanPhase::Done => 100.0,
+            // Files can grow while being read, so cap at 100
+            ScanPhase::Parsing if bytes_discovered > 0 => {
+                (bytes_parsed as f64 / bytes_discovered as f64 * 100.0).min(100.0)
+            }
+            ScanPhase::Parsing => 0.0,
+        };
+
+        callback(&ScanProgress {
+            phase,
+            files_discovered: self.files_discovered.load(Ordering::Relaxed),
+            files_parsed: self.files_parsed.load(Ordering::Relaxed),
+            bytes_discovered,
+            bytes_parsed,
+            percent,
+        });
+    }
+}
@@ -0,0 +1,457 @@
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, info, warn};
+
+use crate::core;
+use crate::export::{self, ExportFormat, OverwritePolicy, ReportKind};
+use crate::pricing::CostMode;
+use crate::reports::DateRange;
+use crate::settings::{load_settings, ScheduledExport};
+use crate::usage::UsageEntry;
+
+/// How often the scheduler checks for due jobs. Wall-clock time is compared on every tick,
+/// so runs missed while the machine slept are noticed on the first tick after waking.
+const TICK: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Most missed runs of one job caught up at once; older missed runs are skipped
+pub const MAX_CATCH_UP: usize = 12;
+
+const STATE_FILE: &str = "scheduled-exports.json";
+
+static JOBS: Mutex<Vec<ScheduledExport>> = Mutex::new(Vec::new());
+static STATE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// A parsed five-field cron expression: minute, hour, day of month, month, day of week.
+/// Fields accept `*`, numbers, ranges, lists and steps; months and weekdays also accept
+/// three-letter names. As in cron, when both day fields are restricted a day matching
+/// either one fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+const MONTH_NAMES: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+fn parse_value(value: &str, names: &[&str], offset: u32) -> Option<u32> {
+    value.parse().ok().or_else(|| {
+        let lower = value.to_ascii_lowercase();
+        names.iter().position(|name| *name == lower).map(|index| index as u32 + offset)
+    })
+}
+
+/// Parses one cron field into a bit mask of the allowed values
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let invalid = || format!("Invalid schedule field: {}", field);
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|&step| step > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start = parse_value(start, names, min).ok_or_else(invalid)?;
+            (start, parse_value(end, names, min).ok_or_else(invalid)?)
+        } else {
+            let start = parse_value(range, names, min).ok_or_else(invalid)?;
+            // `5/15` means every 15 from 5
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("Schedule needs five fields: {}", expression));
+        };
+
+        // Both 0 and 7 mean Sunday
+        let mut weekdays = parse_field(weekday, 0, 7, &WEEKDAY_NAMES)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])?,
+            days: parse_field(day, 1, 31, &[])?,
+            months: parse_field(month, 1, 12, &MONTH_NAMES)?,
+            weekdays,
+            // As in Vixie cron, any field starting with `*` (`*/2` too) leaves the day unrestricted
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.any_day || self.any_weekday {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+
+    /// First time after `after` the schedule fires, in the time zone of `after`.
+    /// Local times skipped by a DST change never fire.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(5 * 366);
+        let mut time = start;
+
+        while time < limit {
+            let date = time.date();
+            if self.months & (1 << date.month()) == 0 {
+                time = date.with_day(1)?.checked_add_months(Months::new(1))?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_date(date) {
+                time = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if self.hours & (1 << time.hour()) == 0 {
+                time = date.and_hms_opt(time.hour(), 0, 0)? + Duration::hours(1);
+            } else if self.minutes & (1 << time.minute()) == 0 {
+                time += Duration::minutes(1);
+            } else {
+                match after.timezone().from_local_datetime(&time).earliest() {
+                    Some(fire) if fire > *after => return Some(fire),
+                    _ => time += Duration::minutes(1),
+                }
+            }
+        }
+        None
+    }
+
+    /// Times the schedule fired after `last_run` up to and including `now`, oldest first,
+    /// keeping only the most recent `limit`
+    pub fn missed_runs<Tz: TimeZone>(&self, last_run: &DateTime<Tz>, now: &DateTime<Tz>, limit: usize) -> Vec<DateTime<Tz>> {
+        let mut runs = VecDeque::with_capacity(limit);
+        let mut cursor = last_run.clone();
+        while let Some(run) = self.next_after(&cursor).filter(|run| run <= now) {
+            if runs.len() == limit {
+                runs.pop_front();
+            }
+            runs.push_back(run.clone());
+            cursor = run;
+        }
+        runs.into()
+    }
+}
+
+/// Dates a scheduled export covers, relative to the local date of the run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportPeriod {
+    PreviousDay,
+    /// The previous Monday to Sunday week
+    PreviousWeek,
+    PreviousMonth,
+    MonthToDate,
+    All,
+}
+
+impl ExportPeriod {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "previousDay" => Ok(ExportPeriod::PreviousDay),
+            "previousWeek" => Ok(ExportPeriod::PreviousWeek),
+            "previousMonth" => Ok(ExportPeriod::PreviousMonth),
+            "monthToDate" => Ok(ExportPeriod::MonthToDate),
+            "all" => Ok(ExportPeriod::All),
+            _ => Err(format!("Unknown export period: {}", value)),
+        }
+    }
+
+    pub fn range(self, run_date: NaiveDate) -> DateRange {
+        let day = |date: Option<NaiveDate>| DateRange { since: date, until: date };
+        let first_of_month = run_date.with_day(1);
+        match self {
+            ExportPeriod::PreviousDay => day(run_date.pred_opt()),
+            ExportPeriod::PreviousWeek => {
+                let this_monday = run_date - Duration::days(run_date.weekday().num_days_from_monday() as i64);
+                DateRange {
+                    since: Some(this_monday - Duration::days(7)),
+                    until: this_monday.pred_opt(),
+                }
+            }
+            ExportPeriod::PreviousMonth => DateRange {
+                since: first_of_month.and_then(|first| first.checked_sub_months(Months::new(1))),
+                until: first_of_month.and_then(|first| first.pred_opt()),
+            },
+            ExportPeriod::MonthToDate => DateRange {
+                since: first_of_month,
+                until: Some(run_date),
+            },
+            ExportPeriod::All => DateRange::default(),
+        }
+    }
+
+    /// Short label for file names, e.g. `2025-06` for a previous month
+    fn label(self, range: &DateRange) -> String {
+        match (self, range.since, range.until) {
+            (ExportPeriod::PreviousMonth | ExportPeriod::MonthToDate, Some(since), _) => since.format("%Y-%m").to_string(),
+            (ExportPeriod::PreviousDay, Some(since), _) => since.to_string(),
+            (_, Some(since), Some(until)) => format!("{}-to-{}", since, until),
+            _ => "all".to_string(),
+        }
+    }
+}
+
+/// Expands a file name template. Tokens: `{report}`, `{period}`, `{since}`, `{until}`, and
+/// `{date}`, `{time}`, `{year}`, `{month}`, `{day}` of the run. The format's extension is
+/// appended unless the template already ends with it.
+pub fn render_file_name<Tz: TimeZone>(
+    template: &str,
+    report: ReportKind,
+    format: ExportFormat,
+    period: ExportPeriod,
+    run: &DateTime<Tz>,
+) -> Result<String, String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let range = period.range(run.date_naive());
+    let mut name = String::new();
+    let mut rest = template.trim();
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed token in file name: {}", template))?;
+        let value = match &rest[start + 1..start + end] {
+            "report" => report.name().to_string(),
+            "period" => period.label(&range),
+            "since" => range.since.map_or_else(|| "all".to_string(), |since| since.to_string()),
+            "until" => range.until.map_or_else(|| "all".to_string(), |until| until.to_string()),
+            "date" => run.format("%Y-%m-%d").to_string(),
+            "time" => run.format("%H%M").to_string(),
+            "year" => run.format("%Y").to_string(),
+            "month" => run.format("%m").to_string(),
+            "day" => run.format("%d").to_string(),
+            token => return Err(format!("Unknown file name token: {{{}}}", token)),
+        };
+        name.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    name.push_str(rest);
+
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid export file name: {}", name));
+    }
+    let extension = format!(".{}", format.extension());
+    if !name.ends_with(&extension) {
+        name.push_str(&extension);
+    }
+    Ok(name)
+}
+
+/// Checks a job the way the scheduler will run it
+pub fn validate_job(job: &ScheduledExport) -> Result<(), String> {
+    let report = ReportKind::parse(&job.report)?;
+    let format = ExportFormat::parse(&job.format)?;
+    let period = ExportPeriod::parse(&job.period)?;
+    Schedule::parse(&job.schedule)?;
+    if !Path::new(job.destination.trim()).is_absolute() {
+        return Err(format!("Export destination must be an absolute path: {}", job.destination));
+    }
+    render_file_name(&job.file_name, report, format, period, &Utc::now())?;
+    Ok(())
+}
+
+/// Validates every job, naming the first invalid one
+pub fn check_jobs(jobs: &[ScheduledExport]) -> Result<(), String> {
+    jobs.iter()
+        .try_for_each(|job| validate_job(job).map_err(|e| format!("Scheduled export {}: {}", job.id, e)))
+}
+
+/// Entries inside `range`; `entries` must be sorted oldest first
+fn entries_in<'a>(entries: &'a [UsageEntry], range: &DateRange) -> &'a [UsageEntry] {
+    let start = range
+        .since
+        .map_or(0, |since| entries.partition_point(|entry| entry.timestamp.date_naive() < since));
+    let end = range.until.map_or(entries.len(), |until| {
+        entries.partition_point(|entry| entry.timestamp.date_naive() <= until)
+    });
+    &entries[start..end.max(start)]
+}
+
+/// Writes one run of a job into its destination directory and returns the file written
+pub fn run_job<Tz: TimeZone>(
+    job: &ScheduledExport,
+    run: &DateTime<Tz>,
+    entries: &[UsageEntry],
+    mode: CostMode,
+) -> Result<PathBuf, String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let report = ReportKind::parse(&job.report)?;
+    let format = ExportFormat::parse(&job.format)?;
+    let period = ExportPeriod::parse(&job.period)?;
+    let range = period.range(run.date_naive());
+    let dir = Path::new(job.destination.trim());
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create export directory: {}", e))?;
+    let path = dir.join(render_file_name(&job.file_name, report, format, period, run)?);
+
+    // A rerun for the same period replaces the earlier file
+    export::export_to_file(
+        &path,
+        OverwritePolicy::Replace,
+        report,
+        format,
+        &range,
+        entries_in(entries, &range),
+        mode,
+        Utc::now(),
+    )
+    .map_err(|e| format!("Failed to write export: {}", e))
+}
+
+/// Payload of the `scheduled-export` event sent after every run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportRun {
+    id: String,
+    scheduled_for: DateTime<Utc>,
+    path: Option<String>,
+    error: Option<String>,
+}
+
+/// Last run of every job, keyed by job id
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+    last_runs: HashMap<String, DateTime<Utc>>,
+}
+
+fn load_state(path: &Path) -> State {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(path: &Path, state: &State) {
+    let tmp = path.with_extension("json.tmp");
+    let result = serde_json::to_vec_pretty(state)
+        .map_err(std::io::Error::other)
+        .and_then(|bytes| fs::write(&tmp, bytes))
+        .and_then(|()| fs::rename(&tmp, path));
+    if let Err(e) = result {
+        warn!(path = %path.display(), "Failed to save scheduled export state: {}", e);
+    }
+}
+
+/// Runs every job that came due since its last run
+fn run_due(app: &AppHandle, state_path: &Path) {
+    let jobs: Vec<ScheduledExport> = JOBS.lock().unwrap().iter().filter(|job| job.enabled).cloned().collect();
+    let mut state = load_state(state_path);
+    let now = Local::now();
+    let mut changed = false;
+    let mut loaded: Option<(Arc<Vec<UsageEntry>>, CostMode)> = None;
+
+    for job in &jobs {
+        // A new job starts counting from now rather than running for the past
+        let Some(last_run) = state.last_runs.get(&job.id) else {
+            state.last_runs.insert(job.id.clone(), now.with_timezone(&Utc));
+            changed = true;
+            continue;
+        };
+        let schedule = match Schedule::parse(&job.schedule) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                warn!(job = %job.id, "Skipping scheduled export: {}", e);
+                continue;
+            }
+        };
+        let runs = schedule.missed_runs(&last_run.with_timezone(&Local), &now, MAX_CATCH_UP);
+        let Some(latest) = runs.last() else {
+            continue;
+        };
+
+        let (entries, mode) = loaded.get_or_insert_with(|| {
+            (core::cached_entries(), CostMode::parse(&load_settings(app).cost_mode))
+        });
+        for run in &runs {
+            let result = run_job(job, run, entries, *mode);
+            match &result {
+                Ok(path) => info!(job = %job.id, path = %path.display(), "Scheduled export written"),
+                Err(e) => warn!(job = %job.id, scheduled_for = %run, "Scheduled export failed: {}", e),
+            }
+            let payload = ExportRun {
+                id: job.id.clone(),
+                scheduled_for: run.with_timezone(&Utc),
+                path: result.as_ref().ok().map(|path| path.to_string_lossy().to_string()),
+                error: result.err(),
+            };
+            if let Err(e) = app.emit("scheduled-export", payload) {
+                debug!("Failed to emit scheduled-export: {}", e);
+            }
+        }
+        // Failed runs are not retried; the next attempt is the next scheduled time
+        state.last_runs.insert(job.id.clone(), latest.with_timezone(&Utc));
+        changed = true;
+    }
+
+    let before = state.last_runs.len();
+    state.last_runs.retain(|id, _| jobs.iter().any(|job| &job.id == id));
+    if changed || state.last_runs.len() != before {
+        save_state(state_path, &state);
+    }
+}
+
+/// Starts the scheduler thread; it runs for the lifetime of the app
+pub fn init(app: &AppHandle, jobs: &[ScheduledExport]) {
+    let state_path = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(STATE_FILE),
+        Err(e) => {
+            warn!("Scheduled exports disabled, no app data directory: {}", e);
+            return;
+        }
+    };
+    if let Some(dir) = state_path.parent() {
+        if let Err(e) = fs::create_dir_all(dir) {
+            warn!("Failed to create app data directory: {}", e);
+        }
+    }
+    if STATE_PATH.set(state_path.clone()).is_err() {
+        return;
+    }
+    apply_settings(jobs);
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        run_due(&app, &state_path);
+        std::thread::sleep(TICK);
+    });
+}
+
+/// Replaces the scheduled jobs; the next tick picks them up
+pub fn apply_settings(jobs: &[ScheduledExport]) {
+    *JOBS.lock().unwrap() = jobs.to_vec();
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri_plugin_store::{Store, StoreExt};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,6 +107,147 @@ pub struct SystemTraySettings {
     pub notifications: SystemTrayNotifications,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalApiSettings {
+    pub enabled: bool,
+    pub bind_address: String, // Must be a loopback address
+    pub port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>, // Generated when the API is first enabled
+}
+
+// Keeps the bearer token out of logs
+impl std::fmt::Debug for LocalApiSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalApiSettings")
+            .field("enabled", &self.enabled)
+            .field("bind_address", &self.bind_address)
+            .field("port", &self.port)
+            .field("token", &self.token.as_ref().map(|_| "[redacted]"))
+            .finish()
+    }
+}
+
+impl Default for LocalApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 47821,
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    pub level: String, // "error" | "warn" | "info" | "debug" | "trace"
+    pub modules: BTreeMap<String, String>, // Per-module overrides, e.g. { "file_monitor": "debug" }
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileMonitorSettings {
+    pub debounce_ms: u64, // Changes to a file within this window are merged into one event
+    pub force_polling: bool, // Poll instead of native events, e.g. for network home directories
+    pub poll_interval_secs: u64,
+}
+
+impl Default for FileMonitorSettings {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 500,
+            force_polling: false,
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArchiveSettings {
+    pub enabled: bool, // Keep usage records after Claude Code deletes old transcripts
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportSettings {
+    pub allowed_directories: Vec<String>, // Where exports with an explicit path may write, besides Downloads and Documents
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScheduledExport {
+    pub id: String,
+    pub enabled: bool,
+    pub report: String, // "daily" | "monthly" | "session" | "blocks" | "models" | "projects" | "machines" | "entries"
+    pub format: String, // Any export format, e.g. "csv" or "xlsx"
+    pub period: String, // "previousDay" | "previousWeek" | "previousMonth" | "monthToDate" | "all"
+    pub schedule: String, // Cron expression in local time, e.g. "0 9 1 * *"
+    pub destination: String, // Absolute directory
+    pub file_name: String, // Template with {report}, {period}, {since}, {until}, {date}, {year}, ... tokens
+}
+
+impl Default for ScheduledExport {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            enabled: true,
+            report: "monthly".to_string(),
+            format: "csv".to_string(),
+            period: "previousMonth".to_string(),
+            schedule: "0 9 1 * *".to_string(),
+            destination: String::new(),
+            file_name: "claude-{report}-{period}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncSettings {
+    pub enabled: bool,
+    pub folder: String, // Shared folder, e.g. in Dropbox or a Syncthing share
+    pub machine_name: String, // Label other machines show for this one; empty uses the host name
+    pub interval_secs: u64, // How often to write this machine's snapshot and read the others
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folder: String::new(),
+            machine_name: String::new(),
+            interval_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TeamSettings {
+    pub directory: String, // Folder of member snapshots to roll up; empty turns team reports off
+    pub anonymize: bool, // Replace user and machine labels with user-1, user-2, ...
+    pub user_name: String, // Label this machine's usage carries in the team snapshots it writes
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettings {
@@ -122,6 +264,24 @@ pub struct AppSettings {
     pub default_export_format: String,
     #[serde(default)]
     pub compact_mode: bool,
+    #[serde(default)]
+    pub privacy_mode: bool, // Send only usage metadata to the webview, never transcript text
+    #[serde(default)]
+    pub local_api: LocalApiSettings,
+    #[serde(default)]
+    pub logging: LoggingSettings,
+    #[serde(default)]
+    pub file_monitor: FileMonitorSettings,
+    #[serde(default)]
+    pub archive: ArchiveSettings,
+    #[serde(default)]
+    pub export: ExportSettings,
+    #[serde(default)]
+    pub scheduled_exports: Vec<ScheduledExport>,
+    #[serde(default)]
+    pub sync: SyncSettings,
+    #[serde(default)]
+    pub team: TeamSettings,
 }
 
 impl Default for AppSettings {
@@ -142,6 +302,15 @@ impl Default for AppSettings {
             launch_at_startup: false,
             default_export_format: "csv".to_string(),
             compact_mode: false,
+            privacy_mode: false,
+            local_api: LocalApiSettings::default(),
+            logging: LoggingSettings::default(),
+            file_monitor: FileMonitorSettings::default(),
+            archive: ArchiveSettings::default(),
+            export: ExportSettings::default(),
+            scheduled_exports: Vec::new(),
+            sync: SyncSettings::default(),
+            team: TeamSettings::default(),
         }
     }
 }
@@ -178,4 +347,11 @@ pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
 pub fn get_settings_store(app: &AppHandle) -> Result<Arc<Store<tauri::Wry>>, String> {
     app.store("settings.json")
         .map_err(|e| format!("Failed to get settings store: {}", e))
-}
\ No newline at end of file
+}
+
+/// Reads the current settings, falling back to defaults if the store is unavailable
+pub fn load_settings(app: &AppHandle) -> AppSettings {
+    get_settings_store(app)
+        .map(|store| AppSettings::from_store(&store))
+        .unwrap_or_default()
+}
@@ -0,0 +1,634 @@
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, info, warn};
+
+use crate::archive::{self, archive_key};
+use crate::core;
+use crate::fs_scope;
+use crate::import::validate_machine;
+use crate::settings::SyncSettings;
+use crate::usage::UsageEntry;
+
+/// Snapshot files are named `<machine id>` followed by this
+pub const SNAPSHOT_SUFFIX: &str = ".claudedeck.ndjson.gz";
+pub(crate) const SNAPSHOT_FORMAT: &str = "claudedeck-usage-snapshot";
+pub(crate) const SNAPSHOT_VERSION: u32 = 1;
+const IDENTITY_FILE: &str = "identity.json";
+const PEERS_DIR: &str = "peers";
+
+/// Shortest interval between syncs
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// How often the sync thread checks whether a sync is due
+const TICK: Duration = Duration::from_secs(5);
+
+static STATE_DIR: OnceLock<PathBuf> = OnceLock::new();
+static ACTIVE: Mutex<Option<ActiveSync>> = Mutex::new(None);
+static STATUS: Mutex<Option<SyncStatus>> = Mutex::new(None);
+
+/// First line of a snapshot file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotHeader {
+    pub format: String,
+    pub version: u32,
+    pub machine_id: String,
+    pub machine: String,
+    /// Person the usage belongs to, set in snapshots written for a team roll-up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Random id of the process that wrote the snapshot
+    pub instance: String,
+    /// Increases with every snapshot a machine writes. Snapshots are ordered by this rather
+    /// than by `written_at`, since the machines' clocks may disagree.
+    pub sequence: u64,
+    pub written_at: DateTime<Utc>,
+    pub entries: usize,
+}
+
+/// A machine's usage records as read from its snapshot file
+#[derive(Debug)]
+pub struct Snapshot {
+    pub header: SnapshotHeader,
+    pub entries: Vec<UsageEntry>,
+}
+
+/// What was last read from another machine's snapshot
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerStatus {
+    pub machine_id: String,
+    pub machine: String,
+    pub sequence: Option<u64>,
+    pub written_at: Option<DateTime<Utc>>,
+    pub entries: usize,
+    /// Why the snapshot in the folder could not be read. The last complete copy, if any,
+    /// is still used.
+    pub error: Option<String>,
+}
+
+/// Outcome of the most recent sync, returned by `get_sync_status` and `sync_now` and sent
+/// with `sync-updated` events
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub machine_id: String,
+    pub machine: String,
+    pub folder: String,
+    pub last_sync: DateTime<Utc>,
+    pub published_entries: usize,
+    pub error: Option<String>,
+    pub peers: Vec<PeerStatus>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Identity {
+    machine_id: String,
+}
+
+pub(crate) fn random_id() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    hasher.write_u128(nanos);
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+pub fn snapshot_path(folder: &Path, machine_id: &str) -> PathBuf {
+    folder.join(format!("{}{}", machine_id, SNAPSHOT_SUFFIX))
+}
+
+/// Snapshot files in `folder` by machine id. Names that are not a plain machine id, such as
+/// the conflicted copies sync tools create, are skipped.
+fn snapshot_files(folder: &Path) -> Vec<(String, PathBuf)> {
+    let mut files: Vec<(String, PathBuf)> = fs::read_dir(folder)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.strip_suffix(SNAPSHOT_SUFFIX)?.to_string();
+            let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            valid.then(|| (id, entry.path()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse_header(line: Option<io::Result<String>>) -> Result<SnapshotHeader, String> {
+    let line = line
+        .ok_or("Snapshot is empty")?
+        .map_err(|e| format!("Snapshot is incomplete: {}", e))?;
+    let header: SnapshotHeader =
+        serde_json::from_str(&line).map_err(|e| format!("Snapshot header is damaged: {}", e))?;
+    if header.format != SNAPSHOT_FORMAT {
+        return Err(format!("Not a usage snapshot: {}", header.format));
+    }
+    if header.version > SNAPSHOT_VERSION {
+        return Err(format!(
+            "Snapshot version {} is newer than this version of ClaudeDeck reads",
+            header.version
+        ));
+    }
+    Ok(header)
+}
+
+fn open_lines(path: &Path) -> Result<io::Lines<BufReader<GzDecoder<File>>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open snapshot: {}", e))?;
+    Ok(BufReader::new(GzDecoder::new(file)).lines())
+}
+
+/// Reads the header of a snapshot without its records
+pub fn read_header(path: &Path) -> Result<SnapshotHeader, String> {
+    parse_header(open_lines(path)?.next())
+}
+
+/// Reads a whole snapshot. A file that is truncated, fails its gzip checksum or holds fewer
+/// records than its header declares, e.g. because a sync tool is still copying it, is an
+/// error rather than a smaller snapshot.
+pub fn read_snapshot(path: &Path) -> Result<Snapshot, String> {
+    let mut lines = open_lines(path)?;
+    let header = parse_header(lines.next())?;
+    let mut entries = Vec::new();
+    for line in lines {
+        let line = line.map_err(|e| format!("Snapshot is incomplete: {}", e))?;
+        let entry = serde_json::from_str::<UsageEntry>(&line)
+            .map_err(|e| format!("Snapshot has a damaged record: {}", e))?;
+        entries.push(entry);
+    }
+    if entries.len() != header.entries {
+        return Err(format!(
+            "Snapshot is incomplete: {} of {} records",
+            entries.len(),
+            header.entries
+        ));
+    }
+    Ok(Snapshot { header, entries })
+}
+
+/// Writes a snapshot into `folder` through a temporary file that is renamed into place, so
+/// readers on this machine never see a partial file
+pub fn write_snapshot(folder: &Path, header: &SnapshotHeader, entries: &[UsageEntry]) -> io::Result<PathBuf> {
+    let path = snapshot_path(folder, &header.machine_id);
+    let mut temp = tempfile::Builder::new()
+        .prefix(&format!(".{}.", header.machine_id))
+        .suffix(".tmp")
+        .tempfile_in(folder)?;
+    encode_snapshot(BufWriter::new(temp.as_file_mut()), header, entries)?;
+    temp.as_file().sync_all()?;
+    temp.persist(&path).map_err(|e| e.error)?;
+    Ok(path)
+}
+
+/// Writes a snapshot, gzip compressed, to `out`
+pub fn encode_snapshot<W: Write>(out: W, header: &SnapshotHeader, entries: &[UsageEntry]) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(out, Compression::default());
+    serde_json::to_writer(&mut encoder, header)?;
+    encoder.write_all(b"\n")?;
+    for entry in entries {
+        serde_json::to_writer(&mut encoder, entry)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()?.flush()
+}
+
+/// Changes whenever the records or the label a snapshot would hold change
+fn fingerprint(machine: &str, entries: &[UsageEntry]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    machine.hash(&mut hasher);
+    for entry in entries {
+        archive_key(entry).hash(&mut hasher);
+        entry.total_tokens().hash(&mut hasher);
+        entry.cost_usd.map(f64::to_bits).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Label shown for a peer: its machine name, or its id when the name is unusable
+fn peer_label(header: &SnapshotHeader) -> String {
+    validate_machine(&header.machine).unwrap_or_else(|_| header.machine_id.clone())
+}
+
+struct FolderState {
+    machine_id: String,
+    /// Sequence of the last snapshot this process wrote or adopted, 0 before the first
+    sequence: u64,
+    fingerprint: Option<u64>,
+}
+
+/// A shared folder that every machine writes its own snapshot into and reads the others'
+/// from. Each machine only ever writes the file named by its machine id, so writers never
+/// contend for a file. Complete peer snapshots are copied into `state_dir`, so loads never
+/// wait on a slow network folder and a snapshot caught mid-sync is replaced by the last
+/// complete one.
+pub struct SyncFolder {
+    folder: PathBuf,
+    state_dir: PathBuf,
+    instance: String,
+    state: Mutex<FolderState>,
+}
+
+impl SyncFolder {
+    /// Opens `folder`, reading this machine's id from `state_dir` or creating one
+    pub fn open(folder: impl Into<PathBuf>, state_dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let state_dir = state_dir.into();
+        let machine_id = match fs::read(state_dir.join(IDENTITY_FILE)) {
+            Ok(bytes) => serde_json::from_slice::<Identity>(&bytes)?.machine_id,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let machine_id = random_id();
+                save_identity(&state_dir, &machine_id)?;
+                machine_id
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            folder: folder.into(),
+            state_dir,
+            instance: random_id(),
+            state: Mutex::new(FolderState {
+                machine_id,
+                sequence: 0,
+                fingerprint: None,
+            }),
+        })
+    }
+
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    pub fn machine_id(&self) -> String {
+        self.state.lock().unwrap().machine_id.clone()
+    }
+
+    /// Writes this machine's snapshot when its records changed since the last one. Returns
+    /// whether a snapshot was written.
+    ///
+    /// If another process has written a newer snapshot under this machine's id since this
+    /// one last wrote, two installations share the id, e.g. because the app data directory
+    /// was copied to a new machine. This one then moves to a new id instead of overwriting.
+    pub fn publish(&self, machine: &str, entries: &[UsageEntry], now: DateTime<Utc>) -> Result<bool, String> {
+        if !self.folder.is_dir() {
+            return Err(format!("Sync folder is not accessible: {}", self.folder.display()));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let mut current = read_header(&snapshot_path(&self.folder, &state.machine_id)).ok();
+        if let Some(header) = &current {
+            if header.instance == self.instance {
+                // Ours; nothing to adopt
+            } else if state.sequence == 0 {
+                // Written by this machine before the app restarted
+                state.sequence = header.sequence;
+            } else if header.sequence >= state.sequence {
+                let previous = std::mem::replace(&mut state.machine_id, random_id());
+                save_identity(&self.state_dir, &state.machine_id)
+                    .map_err(|e| format!("Failed to save machine id: {}", e))?;
+                warn!(
+                    previous = %previous,
+                    machine_id = %state.machine_id,
+                    "Another installation writes snapshots under this machine id; switching to a new id"
+                );
+                state.sequence = 0;
+                state.fingerprint = None;
+                current = None;
+            }
+        }
+
+        let fingerprint = fingerprint(machine, entries);
+        let unchanged = current
+            .as_ref()
+            .is_some_and(|header| header.instance == self.instance && header.sequence == state.sequence);
+        if unchanged && state.fingerprint == Some(fingerprint) {
+            return Ok(false);
+        }
+
+        self.remove_temp_files(&state.machine_id);
+        let header = SnapshotHeader {
+            format: SNAPSHOT_FORMAT.to_string(),
+            version: SNAPSHOT_VERSION,
+            machine_id: state.machine_id.clone(),
+            machine: machine.to_string(),
+            user: None,
+            instance: self.instance.clone(),
+            sequence: state.sequence + 1,
+            written_at: now,
+            entries: entries.len(),
+        };
+        write_snapshot(&self.folder, &header, entries).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+        state.sequence = header.sequence;
+        state.fingerprint = Some(fingerprint);
+        Ok(true)
+    }
+
+    /// Removes temporary files a crashed write under this machine's id left behind
+    fn remove_temp_files(&self, machine_id: &str) {
+        let prefix = format!(".{}.", machine_id);
+        for entry in fs::read_dir(&self.folder).into_iter().flatten().flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(&prefix) && name.ends_with(".tmp") {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    /// Copies complete peer snapshots from the folder into the local state. A snapshot that
+    /// is partial or damaged, e.g. still being synced, leaves the last complete copy in
+    /// place. Peers whose snapshot has left the folder are forgotten.
+    pub fn pull(&self) -> Vec<PeerStatus> {
+        let machine_id = self.machine_id();
+        let cache = self.state_dir.join(PEERS_DIR);
+        if let Err(e) = fs::create_dir_all(&cache) {
+            warn!("Failed to create peer snapshot directory: {}", e);
+        }
+
+        let mut present = HashSet::new();
+        let mut peers = Vec::new();
+        for (id, path) in snapshot_files(&self.folder) {
+            if id == machine_id {
+                continue;
+            }
+            present.insert(id.clone());
+            let cached = read_header(&snapshot_path(&cache, &id)).ok();
+
+            let fresh = read_snapshot(&path).and_then(|snapshot| {
+                if snapshot.header.machine_id != id {
+                    return Err(format!("Snapshot belongs to machine {}", snapshot.header.machine_id));
+                }
+                Ok(snapshot)
+            });
+            match fresh {
+                Ok(snapshot) => {
+                    // A restarted peer that lost its snapshot starts a new sequence
+                    let newer = cached.as_ref().is_none_or(|cached| {
+                        snapshot.header.sequence > cached.sequence || snapshot.header.instance != cached.instance
+                    });
+                    let mut error = None;
+                    if newer {
+                        if let Err(e) = write_snapshot(&cache, &snapshot.header, &snapshot.entries) {
+                            warn!(machine_id = %id, "Failed to store peer snapshot: {}", e);
+                            error = Some(format!("Failed to store snapshot: {}", e));
+                        }
+                    }
+                    let used = if newer { Some(&snapshot.header) } else { cached.as_ref() };
+                    peers.push(peer_status(&id, used, error));
+                }
+                Err(e) => {
+                    debug!(machine_id = %id, "Keeping the last complete snapshot: {}", e);
+                    peers.push(peer_status(&id, cached.as_ref(), Some(e)));
+                }
+            }
+        }
+
+        for (id, path) in snapshot_files(&cache) {
+            if !present.contains(&id) {
+                info!(machine_id = %id, "Peer snapshot left the sync folder");
+                let _ = fs::remove_file(path);
+            }
+        }
+        peers
+    }
+
+    /// Records from the stored peer snapshots, each tagged with its machine's label
+    pub fn peer_entries(&self) -> Vec<UsageEntry> {
+        let mut entries = Vec::new();
+        for (_, path) in snapshot_files(&self.state_dir.join(PEERS_DIR)) {
+            match read_snapshot(&path) {
+                Ok(snapshot) => {
+                    let machine = peer_label(&snapshot.header);
+                    let peer = snapshot.entries.into_iter().map(|mut entry| {
+                        entry.machine = Some(machine.clone());
+                        entry
+                    });
+                    archive::merge(&mut entries, peer.collect());
+                }
+                Err(e) => warn!(path = %path.display(), "Failed to read stored peer snapshot: {}", e),
+            }
+        }
+        entries
+    }
+}
+
+fn peer_status(machine_id: &str, header: Option<&SnapshotHeader>, error: Option<String>) -> PeerStatus {
+    PeerStatus {
+        machine_id: machine_id.to_string(),
+        machine: header.map_or_else(|| machine_id.to_string(), peer_label),
+        sequence: header.map(|header| header.sequence),
+        written_at: header.map(|header| header.written_at),
+        entries: header.map_or(0, |header| header.entries),
+        error,
+    }
+}
+
+fn save_identity(state_dir: &Path, machine_id: &str) -> io::Result<()> {
+    fs::create_dir_all(state_dir)?;
+    let path = state_dir.join(IDENTITY_FILE);
+    let temp = path.with_extension("json.tmp");
+    fs::write(
+        &temp,
+        serde_json::to_vec(&Identity {
+            machine_id: machine_id.to_string(),
+        })?,
+    )?;
+    fs::rename(temp, path)
+}
+
+/// The host name, made into a valid machine label
+pub fn host_name() -> Option<String> {
+    let name = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())?;
+    let name: String = name
+        .trim()
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    validate_machine(&name).ok()
+}
+
+/// Label this machine's snapshots carry: the configured machine name, else the host name
+pub fn machine_name(settings: &SyncSettings) -> Option<String> {
+    validate_machine(&settings.machine_name).ok().or_else(host_name)
+}
+
+/// Rejects sync settings that cannot work, before they are saved
+pub fn check_settings(settings: &SyncSettings) -> Result<(), String> {
+    if !settings.enabled {
+        return Ok(());
+    }
+    fs_scope::validate_sync_folder(&settings.folder, dirs::home_dir().as_deref())?;
+    if !Path::new(settings.folder.trim()).is_dir() {
+        return Err(format!("Sync folder does not exist: {}", settings.folder));
+    }
+    if !settings.machine_name.trim().is_empty() {
+        validate_machine(&settings.machine_name)?;
+    }
+    if settings.interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!("Sync interval must be at least {} seconds", MIN_INTERVAL_SECS));
+    }
+    Ok(())
+}
+
+struct ActiveSync {
+    settings: SyncSettings,
+    folder: Arc<SyncFolder>,
+    next_due: Instant,
+}
+
+fn active_folder() -> Option<Arc<SyncFolder>> {
+    ACTIVE.lock().unwrap().as_ref().map(|active| active.folder.clone())
+}
+
+/// Records from other machines' snapshots, or none when sync is off
+pub fn peer_entries() -> Vec<UsageEntry> {
+    active_folder().map(|folder| folder.peer_entries()).unwrap_or_default()
+}
+
+/// The outcome of the most recent sync
+pub fn status() -> Option<SyncStatus> {
+    STATUS.lock().unwrap().clone()
+}
+
+/// Writes this machine's snapshot and reads the others' now. Emits `sync-updated` when a
+/// peer's snapshot changed.
+pub fn sync_now(app: &AppHandle) -> Result<SyncStatus, String> {
+    let (folder, settings) = {
+        let mut active = ACTIVE.lock().unwrap();
+        let active = active.as_mut().ok_or("Folder sync is not enabled")?;
+        active.next_due = Instant::now() + Duration::from_secs(active.settings.interval_secs);
+        (active.folder.clone(), active.settings.clone())
+    };
+
+    let machine_id = folder.machine_id();
+    let machine = machine_name(&settings).unwrap_or_else(|| machine_id.clone());
+
+    // Only this machine's own records: imported and peer usage is published by its source
+    let local: Vec<UsageEntry> = core::load_entries()
+        .into_iter()
+        .filter(|entry| entry.machine.is_none())
+        .collect();
+    let error = match folder.publish(&machine, &local, Utc::now()) {
+        Ok(true) => {
+            debug!(entries = local.len(), "Wrote usage snapshot");
+            None
+        }
+        Ok(false) => None,
+        Err(e) => {
+            warn!("Folder sync failed: {}", e);
+            Some(e)
+        }
+    };
+    let peers = folder.pull();
+
+    let status = SyncStatus {
+        machine_id: folder.machine_id(),
+        machine,
+        folder: folder.folder().to_string_lossy().to_string(),
+        last_sync: Utc::now(),
+        published_entries: local.len(),
+        error,
+        peers,
+    };
+    let sequences = |status: &SyncStatus| -> HashMap<String, Option<u64>> {
+        status.peers.iter().map(|peer| (peer.machine_id.clone(), peer.sequence)).collect()
+    };
+    let changed = STATUS
+        .lock()
+        .unwrap()
+        .replace(status.clone())
+        .is_none_or(|previous| sequences(&previous) != sequences(&status));
+    if changed {
+        crate::core::invalidate_entries();
+        if let Err(e) = app.emit("sync-updated", status.clone()) {
+            debug!("Failed to emit sync-updated: {}", e);
+        }
+    }
+    Ok(status)
+}
+
+/// Starts the sync thread; it runs for the lifetime of the app and syncs while enabled
+pub fn init(app: &AppHandle, settings: &SyncSettings) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = STATE_DIR.set(dir.join("sync"));
+        }
+        Err(e) => {
+            warn!("Folder sync disabled, no app data directory: {}", e);
+            return;
+        }
+    }
+    apply_settings(settings);
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TICK);
+        let due = ACTIVE
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|active| Instant::now() >= active.next_due);
+        if due {
+            let _ = sync_now(&app);
+        }
+    });
+}
+
+/// Starts, stops or reconfigures folder sync; a changed configuration syncs on the next tick
+pub fn apply_settings(settings: &SyncSettings) {
+    let Some(state_dir) = STATE_DIR.get() else {
+        return;
+    };
+    let mut active = ACTIVE.lock().unwrap();
+    if !settings.enabled || check_settings(settings).is_err() {
+        if active.take().is_some() {
+            info!("Folder sync stopped");
+            *STATUS.lock().unwrap() = None;
+        }
+        return;
+    }
+
+    let folder = PathBuf::from(settings.folder.trim());
+    let reuse = active
+        .as_ref()
+        .filter(|active| active.folder.folder() == folder)
+        .map(|active| active.folder.clone());
+    let folder = match reuse {
+        Some(folder) => folder,
+        None => match SyncFolder::open(folder, state_dir) {
+            Ok(folder) => {
+                info!(folder = %folder.folder().display(), machine_id = %folder.machine_id(), "Folder sync started");
+                Arc::new(folder)
+            }
+            Err(e) => {
+                warn!("Failed to start folder sync: {}", e);
+                return;
+            }
+        },
+    };
+    *active = Some(ActiveSync {
+        settings: settings.clone(),
+        folder,
+        next_due: Instant::now(),
+    });
+}
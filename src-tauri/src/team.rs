@@ -0,0 +1,253 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::path::Path;
+use tracing::warn;
+
+use crate::archive;
+use crate::fs_scope;
+use crate::import::{validate_label, validate_machine};
+use crate::pricing::CostMode;
+use crate::reports::{self, LeaderboardMetric, LeaderboardRow, ModelUsage, ProjectUsage, TokenTotals, UserUsage};
+use crate::settings::TeamSettings;
+use crate::sync::{self, read_snapshot, SnapshotHeader, SNAPSHOT_FORMAT, SNAPSHOT_SUFFIX, SNAPSHOT_VERSION};
+use crate::usage::UsageEntry;
+
+/// Most users listed on each leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// A member snapshot read from the team directory
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSnapshot {
+    pub user: String,
+    pub machine: String,
+    pub entries: usize,
+    pub written_at: DateTime<Utc>,
+}
+
+/// A file in the team directory that could not be used
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedSnapshot {
+    pub file: String,
+    pub error: String,
+}
+
+/// Every member's usage, each record tagged with its user and machine
+#[derive(Debug, Default)]
+pub struct TeamData {
+    pub entries: Vec<UsageEntry>,
+    pub snapshots: Vec<TeamSnapshot>,
+    pub skipped: Vec<SkippedSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaderboard {
+    pub metric: LeaderboardMetric,
+    pub rows: Vec<LeaderboardRow>,
+}
+
+/// Team usage per user, project and model, with leaderboards, returned by `get_team_report`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamReport {
+    pub totals: TokenTotals,
+    pub users: Vec<UserUsage>,
+    pub projects: Vec<ProjectUsage>,
+    pub models: Vec<ModelUsage>,
+    pub leaderboards: Vec<Leaderboard>,
+    pub snapshots: Vec<TeamSnapshot>,
+    pub skipped: Vec<SkippedSnapshot>,
+}
+
+/// Checks a user label, which must be safe in a file name like machine labels
+pub fn validate_user(user: &str) -> Result<String, String> {
+    validate_label("User", user)
+}
+
+/// Rejects team settings that cannot work, before they are saved
+pub fn check_settings(settings: &TeamSettings) -> Result<(), String> {
+    if !settings.directory.trim().is_empty() {
+        fs_scope::validate_team_directory(&settings.directory, dirs::home_dir().as_deref())?;
+    }
+    if !settings.user_name.trim().is_empty() {
+        validate_user(&settings.user_name)?;
+    }
+    Ok(())
+}
+
+/// Suggested file name for a member snapshot
+pub fn snapshot_file_name(user: &str, machine: &str) -> String {
+    format!("{}.{}{}", user, machine, SNAPSHOT_SUFFIX)
+}
+
+/// Writes `entries` as a member snapshot for `user` on `machine`. The records keep usage
+/// fields only, in the same format as folder sync snapshots.
+pub fn write_snapshot<W: Write>(
+    out: W,
+    user: &str,
+    machine: &str,
+    entries: &[UsageEntry],
+    now: DateTime<Utc>,
+) -> io::Result<()> {
+    let header = SnapshotHeader {
+        format: SNAPSHOT_FORMAT.to_string(),
+        version: SNAPSHOT_VERSION,
+        machine_id: sync::random_id(),
+        machine: machine.to_string(),
+        user: Some(user.to_string()),
+        instance: sync::random_id(),
+        sequence: 1,
+        written_at: now,
+        entries: entries.len(),
+    };
+    sync::encode_snapshot(out, &header, entries)
+}
+
+/// Reads every member snapshot in `dir`. Snapshots that are partial, damaged or carry no
+/// user label are skipped and listed. A user's records from several snapshots are counted
+/// once. With `anonymize` set, users become `user-1`, `user-2`, ... in order of their first
+/// activity, machines across the team `machine-1`, `machine-2`, ... and projects
+/// `project-1`, `project-2`, ..., since project directory names carry home directory paths.
+/// Skipped snapshots keep a numbered name and a generic error.
+pub fn load_team(dir: &Path, anonymize: bool) -> Result<TeamData, String> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read team directory {}: {}", dir.display(), e))?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && name.ends_with(SNAPSHOT_SUFFIX)
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+
+    let mut data = TeamData::default();
+    let mut users: BTreeMap<String, Vec<UsageEntry>> = BTreeMap::new();
+    for path in files {
+        let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let snapshot = read_snapshot(&path).and_then(|snapshot| {
+            let user = snapshot
+                .header
+                .user
+                .as_deref()
+                .ok_or("Snapshot has no user label; it was written by folder sync rather than for a team")?;
+            Ok((validate_user(user)?, snapshot))
+        });
+        let (user, snapshot) = match snapshot {
+            Ok(snapshot) => snapshot,
+            Err(error) => {
+                warn!(file = %file, "Skipping team snapshot: {}", error);
+                data.skipped.push(SkippedSnapshot { file, error });
+                continue;
+            }
+        };
+
+        let machine = validate_machine(&snapshot.header.machine).unwrap_or(snapshot.header.machine_id);
+        data.snapshots.push(TeamSnapshot {
+            user: user.clone(),
+            machine: machine.clone(),
+            entries: snapshot.entries.len(),
+            written_at: snapshot.header.written_at,
+        });
+        let tagged = snapshot
+            .entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.machine = Some(machine.clone());
+                entry.user = Some(user.clone());
+                entry
+            })
+            .collect();
+        archive::merge(users.entry(user).or_default(), tagged);
+    }
+
+    data.entries = users.into_values().flatten().collect();
+    data.entries.sort_by_key(|entry| entry.timestamp);
+    if anonymize {
+        anonymize_labels(&mut data);
+    }
+    Ok(data)
+}
+
+/// Numbers labels in order of first appearance, so aliases stay put as usage grows
+fn aliases<K: Eq + Hash>(labels: impl Iterator<Item = K>, prefix: &str) -> HashMap<K, String> {
+    let mut aliases = HashMap::new();
+    for label in labels {
+        let next = aliases.len() + 1;
+        aliases.entry(label).or_insert_with(|| format!("{}-{}", prefix, next));
+    }
+    aliases
+}
+
+fn anonymize_labels(data: &mut TeamData) {
+    // Entries are oldest first; snapshots without usage follow
+    let user_labels = data
+        .entries
+        .iter()
+        .filter_map(|entry| entry.user.as_deref())
+        .chain(data.snapshots.iter().map(|snapshot| snapshot.user.as_str()))
+        .map(str::to_string);
+    let users = aliases(user_labels, "user");
+
+    // Numbered across the team, so two members' `laptop`s stay apart
+    let machine_labels = data
+        .entries
+        .iter()
+        .filter_map(|entry| Some((entry.user.clone()?, entry.machine.clone()?)))
+        .chain(data.snapshots.iter().map(|snapshot| (snapshot.user.clone(), snapshot.machine.clone())));
+    let machines = aliases(machine_labels, "machine");
+
+    let rename = |user: &mut String, machine: &mut String| {
+        *machine = machines[&(user.clone(), machine.clone())].clone();
+        *user = users[user.as_str()].clone();
+    };
+    // A project shared by several members keeps one alias
+    let projects = aliases(data.entries.iter().map(|entry| entry.project.clone()), "project");
+    for entry in &mut data.entries {
+        if let (Some(user), Some(machine)) = (&mut entry.user, &mut entry.machine) {
+            rename(user, machine);
+        }
+        entry.project = projects[entry.project.as_str()].clone();
+    }
+    for snapshot in &mut data.snapshots {
+        rename(&mut snapshot.user, &mut snapshot.machine);
+    }
+    // Errors can quote the labels being hidden, such as an invalid user label
+    for (index, skipped) in data.skipped.iter_mut().enumerate() {
+        skipped.file = format!("snapshot-{}", index + 1);
+        skipped.error = "Snapshot could not be read".to_string();
+    }
+}
+
+/// Aggregates team usage with the same report engine as personal usage
+pub fn team_report(data: TeamData, mode: CostMode) -> TeamReport {
+    let mut totals = TokenTotals::default();
+    for entry in &data.entries {
+        totals.add(entry, mode);
+    }
+    let users = reports::user_report(&data.entries, mode);
+    let leaderboards = LeaderboardMetric::ALL
+        .into_iter()
+        .map(|metric| Leaderboard {
+            metric,
+            rows: reports::leaderboard(&users, metric, LEADERBOARD_SIZE),
+        })
+        .collect();
+
+    TeamReport {
+        totals,
+        projects: reports::project_report(&data.entries, mode),
+        models: reports::model_report(&data.entries, mode),
+        users,
+        leaderboards,
+        snapshots: data.snapshots,
+        skipped: data.skipped,
+    }
+}
@@ -3,6 +3,7 @@ use tauri::{
     tray::{TrayIconBuilder, TrayIconEvent, TrayIconId},
     menu::{MenuBuilder, MenuItemBuilder},
 };
+use tracing::error;
 use crate::settings::{AppSettings, get_settings_store};
 
 fn create_popup_window(app: &AppHandle) -> Result<(), String> {
@@ -90,7 +91,7 @@ pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 let app = tray.app_handle();
                 
                 // Read user settings to determine click action
-                let click_action = if let Ok(store) = get_settings_store(&app) {
+                let click_action = if let Ok(store) = get_settings_store(app) {
                     let settings = AppSettings::from_store(&store);
                     
                     // Get click_action from system_tray settings
@@ -108,13 +109,13 @@ pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 // Handle different click actions
                 match click_action.as_str() {
                     "show_popup" => {
-                        if let Err(e) = create_popup_window(&app) {
-                            eprintln!("Failed to create popup window: {}", e);
+                        if let Err(e) = create_popup_window(app) {
+                            error!("Failed to create popup window: {}", e);
                         }
                     }
                     "toggle_window" => {
-                        if let Err(e) = toggle_main_window(&app) {
-                            eprintln!("Failed to toggle window: {}", e);
+                        if let Err(e) = toggle_main_window(app) {
+                            error!("Failed to toggle window: {}", e);
                         }
                     }
                     "open_app" => {
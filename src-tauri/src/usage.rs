@@ -0,0 +1,240 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+/// A single usage record extracted from a Claude Code JSONL transcript line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageEntry {
+    pub timestamp: DateTime<Utc>,
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: Option<f64>,
+    pub version: Option<String>,
+    pub message_id: Option<String>,
+    pub request_id: Option<String>,
+    pub session_id: String,
+    pub project: String,
+    /// Label of the machine the record was imported from; `None` for this machine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
+    /// Label of the person the record belongs to in a team roll-up; `None` elsewhere
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+impl UsageEntry {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+
+    /// Key used to drop the duplicated lines Claude Code writes when resuming sessions
+    pub fn dedup_key(&self) -> Option<String> {
+        match (&self.message_id, &self.request_id) {
+            (Some(message_id), Some(request_id)) => Some(format!("{}:{}", message_id, request_id)),
+            (Some(message_id), None) => Some(message_id.clone()),
+            _ => None,
+        }
+    }
+
+    /// Renders the entry as a usage-only transcript line that `parse_line` reads back unchanged
+    pub fn to_transcript_line(&self) -> String {
+        let mut line = json!({
+            "timestamp": self.timestamp.to_rfc3339(),
+            "sessionId": self.session_id,
+            "version": self.version,
+            "requestId": self.request_id,
+            "message": {
+                "id": self.message_id,
+                "model": self.model,
+                "usage": {
+                    "input_tokens": self.input_tokens,
+                    "output_tokens": self.output_tokens,
+                    "cache_creation_input_tokens": self.cache_creation_tokens,
+                    "cache_read_input_tokens": self.cache_read_tokens,
+                },
+            },
+        });
+        // The frontend treats a present `costUSD` as authoritative, so leave it out when unknown
+        if let Some(cost_usd) = self.cost_usd {
+            line["costUSD"] = json!(cost_usd);
+        }
+        if let Some(machine) = &self.machine {
+            line["machine"] = json!(machine);
+        }
+        line.to_string()
+    }
+}
+
+/// The fields of a transcript line that carry usage. Serde skips everything else, including
+/// message content, without copying it.
+#[derive(Deserialize)]
+struct RawLine {
+    timestamp: Option<String>,
+    message: Option<RawMessage>,
+    #[serde(rename = "costUSD")]
+    cost_usd: Option<f64>,
+    version: Option<String>,
+    #[serde(rename = "requestId")]
+    request_id: Option<String>,
+    machine: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawMessage {
+    id: Option<String>,
+    model: Option<String>,
+    usage: Option<RawUsage>,
+}
+
+#[derive(Deserialize)]
+struct RawUsage {
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+}
+
+/// Fields of a transcript line the frontend reads; everything else, message text included,
+/// is dropped before transcripts are sent to it
+const TRANSCRIPT_FIELDS: [&str; 6] = ["timestamp", "sessionId", "version", "requestId", "costUSD", "machine"];
+const TRANSCRIPT_MESSAGE_FIELDS: [&str; 3] = ["id", "model", "usage"];
+
+/// Reduces a raw transcript line to the fields the frontend reads. Returns `None` for blank
+/// lines and lines that are not JSON objects, which the frontend would skip anyway.
+pub fn slim_transcript_line(line: &[u8]) -> Option<String> {
+    let serde_json::Value::Object(mut raw) = serde_json::from_slice(line).ok()? else {
+        return None;
+    };
+
+    let mut slim = serde_json::Map::new();
+    for field in TRANSCRIPT_FIELDS {
+        if let Some(value) = raw.remove(field) {
+            slim.insert(field.to_string(), value);
+        }
+    }
+    if let Some(serde_json::Value::Object(mut message)) = raw.remove("message") {
+        let message: serde_json::Map<_, _> = TRANSCRIPT_MESSAGE_FIELDS
+            .into_iter()
+            .filter_map(|field| message.remove(field).map(|value| (field.to_string(), value)))
+            .collect();
+        slim.insert("message".to_string(), serde_json::Value::Object(message));
+    }
+    Some(serde_json::Value::Object(slim).to_string())
+}
+
+/// Derives the (project, session) pair from a `projects/<project>/<session>.jsonl` path
+pub fn session_from_path(path: &Path) -> (String, String) {
+    let session = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let project = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (project, session)
+}
+
+/// Parses one JSONL line, returning `Ok(None)` for valid lines that carry no usage information
+pub fn parse_line(line: &str, project: &str, session: &str) -> Result<Option<UsageEntry>, serde_json::Error> {
+    let raw: RawLine = serde_json::from_str(line)?;
+    let Some(timestamp) = raw
+        .timestamp
+        .as_deref()
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+    else {
+        return Ok(None);
+    };
+
+    let message = raw.message;
+    let usage = message.as_ref().and_then(|m| m.usage.as_ref());
+    if usage.is_none() && raw.cost_usd.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(UsageEntry {
+        timestamp: timestamp.with_timezone(&Utc),
+        model: message.as_ref().and_then(|m| m.model.clone()),
+        input_tokens: usage.and_then(|u| u.input_tokens).unwrap_or(0),
+        output_tokens: usage.and_then(|u| u.output_tokens).unwrap_or(0),
+        cache_creation_tokens: usage.and_then(|u| u.cache_creation_input_tokens).unwrap_or(0),
+        cache_read_tokens: usage.and_then(|u| u.cache_read_input_tokens).unwrap_or(0),
+        cost_usd: raw.cost_usd,
+        version: raw.version,
+        message_id: message.as_ref().and_then(|m| m.id.clone()),
+        request_id: raw.request_id,
+        session_id: session.to_string(),
+        project: project.to_string(),
+        machine: raw.machine,
+        user: None,
+    }))
+}
+
+/// Usage entries parsed from one transcript along with the number of lines that failed to parse
+#[derive(Debug, Default)]
+pub struct ParsedContent {
+    pub entries: Vec<UsageEntry>,
+    pub malformed_lines: usize,
+}
+
+impl ParsedContent {
+    fn push_line(&mut self, line: &str, project: &str, session: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        match parse_line(line, project, session) {
+            Ok(Some(entry)) => self.entries.push(entry),
+            Ok(None) => {}
+            Err(_) => self.malformed_lines += 1,
+        }
+    }
+}
+
+/// Parses the contents of a JSONL file, skipping malformed and non-usage lines
+pub fn parse_content(content: &str, path: &Path) -> ParsedContent {
+    let (project, session) = session_from_path(path);
+    let mut parsed = ParsedContent::default();
+
+    for line in content.lines() {
+        parsed.push_line(line, &project, &session);
+    }
+
+    parsed
+}
+
+/// Parses a JSONL transcript one line at a time so large files are never held in memory.
+/// `on_line` receives the byte length of each line read and returns `false` to stop early.
+pub fn parse_reader(
+    mut reader: impl BufRead,
+    path: &Path,
+    mut on_line: impl FnMut(usize) -> bool,
+) -> io::Result<ParsedContent> {
+    let (project, session) = session_from_path(path);
+    let mut parsed = ParsedContent::default();
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            break;
+        }
+        if !on_line(read) {
+            break;
+        }
+
+        match std::str::from_utf8(&line) {
+            Ok(text) => parsed.push_line(text, &project, &session),
+            Err(_) => parsed.malformed_lines += 1,
+        }
+    }
+
+    Ok(parsed)
+}
@@ -0,0 +1,52 @@
+use tauri_app_lib::api::{ensure_token, is_loopback_host, tokens_match};
+use tauri_app_lib::settings::LocalApiSettings;
+
+#[test]
+fn enabling_the_api_generates_a_token_once() {
+    let mut settings = LocalApiSettings::default();
+    ensure_token(&mut settings, None).unwrap();
+    assert!(settings.token.is_none());
+
+    settings.enabled = true;
+    ensure_token(&mut settings, None).unwrap();
+    let token = settings.token.clone().unwrap();
+    assert_eq!(token.len(), 64);
+    assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+
+    // Settings saved from the webview carry no token and keep the stored one
+    let mut saved = LocalApiSettings { enabled: true, ..LocalApiSettings::default() };
+    ensure_token(&mut saved, Some(token.clone())).unwrap();
+    assert_eq!(saved.token.as_deref(), Some(token.as_str()));
+
+    let mut other = LocalApiSettings { enabled: true, token: Some(String::new()), ..LocalApiSettings::default() };
+    ensure_token(&mut other, None).unwrap();
+    assert_ne!(other.token.as_deref(), Some(token.as_str()));
+
+    assert!(!format!("{:?}", saved).contains(&token));
+}
+
+#[test]
+fn tokens_are_compared_whole() {
+    assert!(tokens_match("abc123", "abc123"));
+    assert!(!tokens_match("abc123", "abc124"));
+    assert!(!tokens_match("abc123", "abc12"));
+    assert!(!tokens_match("abc123", ""));
+}
+
+#[test]
+fn only_loopback_host_headers_are_accepted() {
+    for host in ["127.0.0.1:47821", "127.0.0.1", "localhost:47821", "LOCALHOST", "[::1]:47821", "[::1]"] {
+        assert!(is_loopback_host(host), "{}", host);
+    }
+    for host in ["evil.example:47821", "127.0.0.1.evil.example", "192.168.1.5:47821", "", "[::2]:47821"] {
+        assert!(!is_loopback_host(host), "{}", host);
+    }
+}
+
+#[test]
+fn partial_stored_settings_keep_their_defaults() {
+    let settings: LocalApiSettings = serde_json::from_value(serde_json::json!({ "enabled": true })).unwrap();
+    assert!(settings.enabled);
+    assert_eq!((settings.bind_address.as_str(), settings.port), ("127.0.0.1", 47821));
+    assert!(settings.token.is_none());
+}
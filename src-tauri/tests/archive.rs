@@ -0,0 +1,144 @@
+mod common;
+
+use common::{at, Fixture, SONNET};
+use flate2::read::MultiGzDecoder;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use tauri_app_lib::archive::{self, Archive};
+use tauri_app_lib::core;
+use tauri_app_lib::fixtures::{FixtureDir, FixtureMessage};
+use tauri_app_lib::usage;
+
+fn segments(archive: &Archive) -> Vec<std::path::PathBuf> {
+    let mut segments: Vec<_> = fs::read_dir(archive.dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().ends_with(".ndjson.gz"))
+        .collect();
+    segments.sort();
+    segments
+}
+
+#[test]
+fn archived_usage_survives_deleted_transcripts() {
+    let fixture = Fixture::new();
+    let archive = Archive::new(fixture.path().join("archive"));
+    let live = core::load_entries_from(&fixture.config_dirs);
+
+    assert_eq!(archive.archive_all(&fixture.config_dirs), live.len());
+
+    // Claude Code prunes old transcripts; the archive keeps their usage
+    fs::remove_dir_all(&fixture.config_dirs[0]).unwrap();
+    let mut remaining = core::load_entries_from(&fixture.config_dirs);
+    assert!(remaining.len() < live.len());
+
+    let added = archive::merge(&mut remaining, archive.entries());
+    assert_eq!(added, live.len() - core::load_entries_from(&fixture.config_dirs).len());
+    assert_eq!(remaining.len(), live.len());
+
+    let keys = |entries: &[usage::UsageEntry]| entries.iter().map(archive::archive_key).collect::<Vec<_>>();
+    assert_eq!(keys(&remaining), keys(&live));
+}
+
+#[test]
+fn merge_skips_records_still_in_transcripts() {
+    let fixture = Fixture::new();
+    let archive = Archive::new(fixture.path().join("archive"));
+    archive.archive_all(&fixture.config_dirs);
+
+    let mut live = core::load_entries_from(&fixture.config_dirs);
+    let count = live.len();
+    assert_eq!(archive::merge(&mut live, archive.entries()), 0);
+    assert_eq!(live.len(), count);
+}
+
+#[test]
+fn only_appended_lines_are_archived() {
+    let fixture = Fixture::new();
+    let archive = Archive::new(fixture.path().join("archive"));
+    archive.archive_all(&fixture.config_dirs);
+
+    // Unchanged files are skipped, also after reopening the archive
+    assert_eq!(archive.archive_all(&fixture.config_dirs), 0);
+    let reopened = Archive::new(archive.dir());
+    assert_eq!(reopened.archive_all(&fixture.config_dirs), 0);
+
+    let dir = FixtureDir::new(&fixture.config_dirs[1]);
+    let mut session = dir.session("-home-dev-beta", "session-beta-1").unwrap();
+    session
+        .assistant(&FixtureMessage::new(at(2025, 7, 3, 9, 0), SONNET, "b4").tokens(10, 5))
+        .unwrap();
+
+    // A line still being written is left for the next pass
+    let mut file = OpenOptions::new().append(true).open(session.path()).unwrap();
+    file.write_all(b"{\"timestamp\":\"2025-07-03T09:05:00Z\",\"message\":{\"id\":\"msg_b5\"").unwrap();
+    assert_eq!(reopened.archive_file(session.path()).unwrap(), 1);
+
+    file.write_all(b",\"usage\":{\"input_tokens\":7,\"output_tokens\":3}}}\n").unwrap();
+    assert_eq!(reopened.archive_file(session.path()).unwrap(), 1);
+
+    let ids: Vec<_> = reopened
+        .entries()
+        .into_iter()
+        .filter_map(|entry| entry.message_id)
+        .collect();
+    assert!(ids.ends_with(&["msg_b4".to_string(), "msg_b5".to_string()]), "{:?}", ids);
+}
+
+#[test]
+fn archive_holds_usage_only() {
+    let fixture = Fixture::new();
+    let archive = Archive::new(fixture.path().join("archive"));
+    archive.archive_all(&fixture.config_dirs);
+
+    let segments = segments(&archive);
+    assert!(!segments.is_empty());
+    for segment in segments {
+        let mut text = String::new();
+        MultiGzDecoder::new(fs::File::open(&segment).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert!(!text.contains("Refactor the parser"));
+        assert!(!text.contains("\"content\""));
+    }
+}
+
+#[test]
+fn truncated_segment_keeps_earlier_records_and_is_repaired() {
+    let fixture = Fixture::new();
+    let archive = Archive::new(fixture.path().join("archive"));
+    let archived = archive.archive_all(&fixture.config_dirs);
+
+    // Simulate a crash halfway through appending another member
+    let segment = segments(&archive).pop().unwrap();
+    let mut partial = Vec::new();
+    let mut encoder = flate2::write::GzEncoder::new(&mut partial, flate2::Compression::default());
+    encoder.write_all(b"{\"timestamp\":\"2025-07-31T00:00:00Z\"}\n").unwrap();
+    encoder.finish().unwrap();
+    let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+    file.write_all(&partial[..partial.len() / 2]).unwrap();
+
+    let reopened = Archive::new(archive.dir());
+    assert_eq!(reopened.entries().len(), archived);
+
+    // New records appended after the repair stay readable
+    let dir = FixtureDir::new(&fixture.config_dirs[1]);
+    let mut session = dir.session("-home-dev-beta", "session-beta-1").unwrap();
+    session
+        .assistant(&FixtureMessage::new(at(2025, 7, 4, 9, 0), SONNET, "b6").tokens(10, 5))
+        .unwrap();
+    assert_eq!(reopened.archive_file(session.path()).unwrap(), 1);
+    assert_eq!(Archive::new(archive.dir()).entries().len(), archived + 1);
+}
+
+#[test]
+fn transcript_lines_round_trip() {
+    let fixture = Fixture::new();
+    for entry in core::load_entries_from(&fixture.config_dirs) {
+        let line = entry.to_transcript_line();
+        let parsed = usage::parse_line(&line, &entry.project, &entry.session_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), serde_json::to_value(&entry).unwrap());
+    }
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+use common::{at, Fixture, HAIKU, OPUS, SONNET};
+use std::io::Cursor;
+use tauri_app_lib::core::load_entries_from;
+use tauri_app_lib::export::{self, ExportFormat, ReportKind};
+use tauri_app_lib::pricing::{self, CostMode};
+use tauri_app_lib::reports::{self, DateRange};
+use tauri_app_lib::usage::UsageEntry;
+
+fn entry(timestamp: DateTime<Utc>, model: &str, project: &str, session: &str, tokens: [u64; 3]) -> UsageEntry {
+    let [input_tokens, cache_creation_tokens, cache_read_tokens] = tokens;
+    UsageEntry {
+        timestamp,
+        model: Some(model.to_string()),
+        input_tokens,
+        output_tokens: 100,
+        cache_creation_tokens,
+        cache_read_tokens,
+        cost_usd: None,
+        version: None,
+        message_id: None,
+        request_id: None,
+        session_id: session.to_string(),
+        project: project.to_string(),
+        machine: None,
+        user: None,
+    }
+}
+
+#[test]
+fn cache_reads_and_writes_are_priced_against_plain_input() {
+    // One session writes a cache and reads it back ten times; another writes and never reads
+    let entries = vec![
+        entry(at(2025, 6, 1, 9, 0), SONNET, "/work/app", "reused", [1_000, 20_000, 0]),
+        entry(at(2025, 6, 1, 9, 5), SONNET, "/work/app", "reused", [500, 0, 200_000]),
+        entry(at(2025, 6, 2, 9, 0), SONNET, "/work/app", "wasted", [1_000, 50_000, 0]),
+        entry(at(2025, 6, 2, 9, 5), SONNET, "/work/app", "wasted", [1_000, 0, 5_000]),
+        entry(at(2025, 6, 2, 10, 0), SONNET, "/work/tool", "small", [1_000, 2_000, 0]),
+    ];
+    let report = reports::cache_report(&entries, CostMode::Calculate);
+
+    let totals = &report.totals;
+    assert_eq!((totals.totals.cache_creation_tokens, totals.totals.cache_read_tokens), (72_000, 205_000));
+    assert!((totals.write_amortization - 205_000.0 / 72_000.0).abs() < 1e-12);
+    assert!((totals.cache_read_ratio - 205_000.0 / 281_500.0).abs() < 1e-12);
+    // Writes cost 25% more than input and reads 90% less
+    let savings = 205_000.0 * 0.0000027 - 72_000.0 * 0.00000075;
+    assert!((totals.estimated_savings - savings).abs() < 1e-9);
+    assert!((totals.uncached_cost - totals.totals.total_cost - savings).abs() < 1e-9);
+    let uncached: f64 = entries
+        .iter()
+        .map(|entry| {
+            let prompt = entry.input_tokens + entry.cache_creation_tokens + entry.cache_read_tokens;
+            prompt as f64 * 0.000003 + entry.output_tokens as f64 * 0.000015
+        })
+        .sum();
+    assert!((totals.uncached_cost - uncached).abs() < 1e-9);
+
+    let days: Vec<&str> = report.days.iter().map(|day| day.date.as_str()).collect();
+    assert_eq!(days, ["2025-06-02", "2025-06-01"]);
+    assert!(report.days[0].cache.estimated_savings < 0.0);
+    assert!((report.days[1].cache.write_amortization - 10.0).abs() < 1e-12);
+
+    // Only sessions that wrote enough cache to matter are flagged
+    let poor: Vec<&str> = report.poor_reuse_sessions.iter().map(|session| session.session_id.as_str()).collect();
+    assert_eq!(poor, ["wasted"]);
+    let app = report.projects.iter().find(|project| project.project_path == "/work/app").unwrap();
+    assert_eq!((app.sessions, app.poor_reuse_sessions), (2, 1));
+    let tool = report.projects.iter().find(|project| project.project_path == "/work/tool").unwrap();
+    assert_eq!((tool.sessions, tool.poor_reuse_sessions, tool.cache.write_amortization), (1, 0, 0.0));
+}
+
+#[test]
+fn savings_ignore_recorded_costs_in_display_mode() {
+    let mut entries = vec![
+        entry(at(2025, 6, 1, 9, 0), SONNET, "/work/app", "a", [1_000, 20_000, 0]),
+        entry(at(2025, 6, 1, 9, 5), SONNET, "/work/app", "a", [500, 0, 200_000]),
+    ];
+    for entry in &mut entries {
+        entry.cost_usd = Some(5.0);
+    }
+    let display = reports::cache_report(&entries, CostMode::Display).totals;
+    let calculate = reports::cache_report(&entries, CostMode::Calculate).totals;
+
+    assert!((display.totals.total_cost - 10.0).abs() < 1e-9);
+    assert!((display.calculated_cost - calculate.totals.total_cost).abs() < 1e-9);
+    assert!((display.uncached_cost - calculate.uncached_cost).abs() < 1e-9);
+    let savings = 200_000.0 * 0.0000027 - 20_000.0 * 0.00000075;
+    assert!((display.estimated_savings - savings).abs() < 1e-9);
+    assert!((display.uncached_cost - display.calculated_cost - savings).abs() < 1e-9);
+}
+
+#[test]
+fn models_without_pricing_save_nothing() {
+    let opus = entry(at(2025, 6, 1, 9, 0), OPUS, "/work/app", "a", [0, 0, 1_000_000]);
+    let haiku = entry(at(2025, 6, 1, 9, 0), HAIKU, "/work/app", "a", [0, 1_000, 1_000_000]);
+    let saved = |entry| pricing::uncached_cost(entry) - pricing::calculate_cost(entry, CostMode::Calculate);
+    assert!((saved(&opus) - 13.5).abs() < 1e-9);
+    assert_eq!(saved(&haiku), 0.0);
+
+    let report = reports::cache_report(&[haiku], CostMode::Auto);
+    assert_eq!(report.totals.estimated_savings, 0.0);
+    assert!(report.totals.cache_read_ratio > 0.99);
+
+    let empty = reports::cache_report(&[], CostMode::Auto);
+    assert!(empty.days.is_empty() && empty.projects.is_empty() && empty.poor_reuse_sessions.is_empty());
+    assert_eq!((empty.totals.cache_read_ratio, empty.totals.write_amortization), (0.0, 0.0));
+}
+
+#[test]
+fn cache_export_has_a_row_per_project() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    let report = reports::cache_report(&entries, CostMode::Auto);
+
+    let mut json = Cursor::new(Vec::new());
+    export::write_report(
+        &mut json,
+        ReportKind::parse("cache").unwrap(),
+        ExportFormat::Json,
+        &DateRange::default(),
+        &entries,
+        CostMode::Auto,
+        at(2025, 7, 1, 0, 0),
+    )
+    .unwrap();
+    let rows: Vec<serde_json::Value> = serde_json::from_slice(&json.into_inner()).unwrap();
+    assert_eq!(rows.len(), report.projects.len());
+    for (row, project) in rows.iter().zip(&report.projects) {
+        assert_eq!(row["projectPath"], project.project_path.as_str());
+        assert_eq!(row["cacheReadTokens"], project.cache.totals.cache_read_tokens);
+        assert!((row["estimatedSavings"].as_f64().unwrap() - project.cache.estimated_savings).abs() < 1e-6);
+        assert!(row["cacheReadRatio"].as_f64().unwrap() <= 1.0);
+    }
+}
@@ -0,0 +1,23 @@
+use tauri_app_lib::cli;
+
+#[test]
+fn append_rate_must_be_a_positive_number() {
+    let output = tempfile::tempdir().unwrap();
+    for rate in ["NaN", "inf", "-inf", "0", "-1", "1e-320"] {
+        let args: Vec<String> = ["generate-fixtures", "--output", output.path().to_str().unwrap(), "--append-rate", rate]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(cli::run(&args), Some(2), "{}", rate);
+    }
+    assert_eq!(std::fs::read_dir(output.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn only_subcommands_count_as_cli_invocations() {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+    assert!(cli::is_command(&args(&["export", "--report", "daily"])));
+    assert!(cli::is_command(&args(&["generate-fixtures"])));
+    assert!(!cli::is_command(&args(&[])));
+    assert!(!cli::is_command(&args(&["--verbose"])));
+}
@@ -0,0 +1,122 @@
+#![allow(dead_code)]
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri_app_lib::fixtures::{FixtureDir, FixtureMessage};
+use tempfile::TempDir;
+
+pub const SONNET: &str = "claude-sonnet-4-20250514";
+pub const OPUS: &str = "claude-opus-4-20250514";
+pub const HAIKU: &str = "claude-3-5-haiku-20241022";
+
+pub fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+}
+
+/// Two Claude config directories with a realistic mix of usage:
+/// - `claude-a/projects/-home-dev-alpha`: a Sonnet session with cache tokens and a
+///   resumed session that repeats one of its messages, plus user lines and a
+///   malformed line
+/// - `claude-b/projects/-home-dev-beta`: Opus and Haiku usage with and without
+///   `costUSD`, spanning two months
+pub struct Fixture {
+    pub dir: TempDir,
+    pub config_dirs: Vec<PathBuf>,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let a = FixtureDir::new(dir.path().join("claude-a"));
+        let b = FixtureDir::new(dir.path().join("claude-b"));
+
+        let first = FixtureMessage::new(at(2025, 5, 30, 9, 0), SONNET, "a1")
+            .tokens(1200, 300)
+            .cache(5000, 0)
+            .cost(0.05);
+
+        let mut alpha1 = a.session("-home-dev-alpha", "session-alpha-1").unwrap();
+        alpha1.user(at(2025, 5, 30, 8, 59), "Refactor the parser").unwrap();
+        alpha1.assistant(&first).unwrap();
+        alpha1
+            .assistant(
+                &FixtureMessage::new(at(2025, 5, 30, 9, 5), SONNET, "a2")
+                    .tokens(800, 450)
+                    .cache(0, 5000),
+            )
+            .unwrap();
+        alpha1.raw_line("{\"timestamp\":\"2025-05-30T09:06:00Z\",\"message\":").unwrap();
+        alpha1
+            .assistant(
+                &FixtureMessage::new(at(2025, 5, 31, 23, 50), SONNET, "a3")
+                    .tokens(100, 50)
+                    .cache(0, 12000),
+            )
+            .unwrap();
+
+        // A resumed session copies earlier history before continuing
+        let mut alpha2 = a.session("-home-dev-alpha", "session-alpha-2").unwrap();
+        alpha2.assistant(&first).unwrap();
+        alpha2.user(at(2025, 6, 1, 10, 0), "Continue").unwrap();
+        alpha2
+            .assistant(
+                &FixtureMessage::new(at(2025, 6, 1, 10, 1), OPUS, "a4")
+                    .tokens(2000, 1000)
+                    .cache(3000, 1000),
+            )
+            .unwrap();
+        alpha2.raw_line("not json at all").unwrap();
+
+        let mut beta = b.session("-home-dev-beta", "session-beta-1").unwrap();
+        beta.assistant(
+            &FixtureMessage::new(at(2025, 6, 1, 14, 30), OPUS, "b1")
+                .tokens(500, 200)
+                .cost(0.12),
+        )
+        .unwrap();
+        beta.assistant(
+            &FixtureMessage::new(at(2025, 6, 1, 16, 0), HAIKU, "b2")
+                .tokens(300, 100)
+                .cost(0.001),
+        )
+        .unwrap();
+        beta.assistant(
+            &FixtureMessage::new(at(2025, 7, 2, 8, 15), OPUS, "b3")
+                .tokens(50, 25)
+                .cache(0, 400),
+        )
+        .unwrap();
+
+        Self {
+            config_dirs: vec![a.root().to_path_buf(), b.root().to_path_buf()],
+            dir,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Compares `value` with `tests/golden/<name>.json`. Run with `UPDATE_GOLDEN=1` to
+/// rewrite the golden file after an intentional change.
+pub fn assert_golden<T: Serialize>(name: &str, value: &T) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.json", name));
+    let actual = serde_json::to_value(value).unwrap();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        return;
+    }
+
+    let expected: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("missing golden file {:?}, run with UPDATE_GOLDEN=1", path)),
+    )
+    .unwrap();
+
+    assert_eq!(actual, expected, "output differs from golden file {:?}", path);
+}
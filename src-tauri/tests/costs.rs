@@ -0,0 +1,66 @@
+mod common;
+
+use common::{at, HAIKU, OPUS, SONNET};
+use tauri_app_lib::fixtures::{assistant_line, FixtureMessage};
+use tauri_app_lib::pricing::{calculate_cost, CostMode};
+use tauri_app_lib::usage::{parse_line, UsageEntry};
+
+fn entry(message: FixtureMessage) -> UsageEntry {
+    parse_line(&assistant_line(&message, "s", "/tmp"), "p", "s")
+        .unwrap()
+        .unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-12, "{} != {}", actual, expected);
+}
+
+#[test]
+fn calculate_mode_prices_every_token_type() {
+    let sonnet = entry(
+        FixtureMessage::new(at(2025, 6, 1, 0, 0), SONNET, "s")
+            .tokens(1_000_000, 1_000_000)
+            .cache(1_000_000, 1_000_000)
+            .cost(99.0),
+    );
+    assert_close(calculate_cost(&sonnet, CostMode::Calculate), 3.0 + 15.0 + 3.75 + 0.3);
+
+    let opus = entry(
+        FixtureMessage::new(at(2025, 6, 1, 0, 0), OPUS, "o")
+            .tokens(1_000_000, 1_000_000)
+            .cache(1_000_000, 1_000_000),
+    );
+    assert_close(calculate_cost(&opus, CostMode::Calculate), 15.0 + 75.0 + 18.75 + 1.5);
+}
+
+#[test]
+fn auto_mode_prefers_recorded_cost() {
+    let with_cost = entry(FixtureMessage::new(at(2025, 6, 1, 0, 0), SONNET, "a").tokens(1000, 0).cost(0.5));
+    let without_cost = entry(FixtureMessage::new(at(2025, 6, 1, 0, 0), SONNET, "b").tokens(1000, 0));
+
+    assert_close(calculate_cost(&with_cost, CostMode::Auto), 0.5);
+    assert_close(calculate_cost(&without_cost, CostMode::Auto), 0.003);
+}
+
+#[test]
+fn display_mode_only_uses_recorded_cost() {
+    let with_cost = entry(FixtureMessage::new(at(2025, 6, 1, 0, 0), SONNET, "a").tokens(1000, 0).cost(0.5));
+    let without_cost = entry(FixtureMessage::new(at(2025, 6, 1, 0, 0), SONNET, "b").tokens(1000, 0));
+
+    assert_close(calculate_cost(&with_cost, CostMode::Display), 0.5);
+    assert_close(calculate_cost(&without_cost, CostMode::Display), 0.0);
+}
+
+#[test]
+fn unknown_models_cost_nothing_when_calculated() {
+    let haiku = entry(FixtureMessage::new(at(2025, 6, 1, 0, 0), HAIKU, "h").tokens(1000, 1000));
+    assert_close(calculate_cost(&haiku, CostMode::Calculate), 0.0);
+}
+
+#[test]
+fn cost_mode_parses_settings_values() {
+    assert_eq!(CostMode::parse("calculate"), CostMode::Calculate);
+    assert_eq!(CostMode::parse("display"), CostMode::Display);
+    assert_eq!(CostMode::parse("auto"), CostMode::Auto);
+    assert_eq!(CostMode::parse("bogus"), CostMode::Auto);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::Fixture;
+use std::fs;
+use tauri_app_lib::core::{claude_path_candidates_from, discover_claude_paths_from, find_jsonl_files};
+
+#[test]
+fn claude_config_dir_paths_are_discovered_in_order() {
+    let fixture = Fixture::new();
+    let missing = fixture.path().join("missing");
+    let value = format!(
+        "{}, {},{}",
+        fixture.config_dirs[0].display(),
+        missing.display(),
+        fixture.config_dirs[1].display()
+    );
+
+    let home = fixture.path().join("home");
+    fs::create_dir_all(home.join(".claude")).unwrap();
+
+    let paths = discover_claude_paths_from(Some(&value), Some(&home));
+    let expected = [fixture.config_dirs[0].clone(), fixture.config_dirs[1].clone(), home.join(".claude")];
+    assert_eq!(paths, expected);
+
+    // Missing directories are still candidates, for scoping and watching
+    let candidates = claude_path_candidates_from(Some(&value), Some(&home));
+    assert_eq!(candidates.len(), 5);
+    assert_eq!(candidates[1], missing);
+    assert_eq!(candidates[3], home.join(".config/claude"));
+}
+
+#[test]
+fn jsonl_files_are_found_recursively() {
+    let fixture = Fixture::new();
+    let projects = fixture.config_dirs[0].join("projects");
+    fs::write(projects.join("-home-dev-alpha/notes.txt"), "ignored").unwrap();
+    fs::create_dir_all(projects.join("-home-dev-alpha/nested")).unwrap();
+    fs::write(projects.join("-home-dev-alpha/nested/extra.jsonl"), "").unwrap();
+
+    let mut files = find_jsonl_files(&projects).unwrap();
+    files.sort();
+
+    let names: Vec<_> = files
+        .iter()
+        .map(|f| f.strip_prefix(&projects).unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "-home-dev-alpha/nested/extra.jsonl",
+            "-home-dev-alpha/session-alpha-1.jsonl",
+            "-home-dev-alpha/session-alpha-2.jsonl",
+        ]
+    );
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::UInt64Type;
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, TimeUnit};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::io::{Cursor, Read};
+
+use common::{assert_golden, at, Fixture};
+use tauri_app_lib::core::load_entries_from;
+use tauri_app_lib::export::{self, csv_field, escape_html, ExportFormat, OverwritePolicy, ReportKind};
+use tauri_app_lib::pricing::CostMode;
+use tauri_app_lib::reports::DateRange;
+
+fn render_bytes(report: ReportKind, format: ExportFormat, range: &DateRange) -> Vec<u8> {
+    let fixture = Fixture::new();
+    let entries: Vec<_> = load_entries_from(&fixture.config_dirs)
+        .into_iter()
+        .filter(|entry| range.contains(entry))
+        .collect();
+    let mut out = Cursor::new(Vec::new());
+    export::write_report(&mut out, report, format, range, &entries, CostMode::Auto, at(2025, 7, 2, 10, 0)).unwrap();
+    out.into_inner()
+}
+
+fn render(report: ReportKind, format: ExportFormat, range: &DateRange) -> String {
+    String::from_utf8(render_bytes(report, format, range)).unwrap()
+}
+
+#[test]
+fn csv_fields_are_escaped_per_rfc_4180() {
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    assert_eq!(csv_field("cr\r"), "\"cr\r\"");
+}
+
+#[test]
+fn daily_csv_matches_golden() {
+    let csv = render(ReportKind::Daily, ExportFormat::Csv, &DateRange::default());
+    assert!(csv.split_terminator('\n').all(|line| line.ends_with('\r')));
+    assert!(csv.starts_with("Date,Models,Input Tokens,"));
+    let lines: Vec<&str> = csv.split("\r\n").filter(|line| !line.is_empty()).collect();
+    assert_golden("export_daily_csv", &lines);
+}
+
+#[test]
+fn json_and_ndjson_carry_the_same_rows() {
+    let range = DateRange::parse(Some("2025-06-01"), None).unwrap();
+    let json: Vec<serde_json::Value> =
+        serde_json::from_str(&render(ReportKind::Monthly, ExportFormat::Json, &range)).unwrap();
+    let ndjson: Vec<serde_json::Value> = render(ReportKind::Monthly, ExportFormat::Ndjson, &range)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(json, ndjson);
+    let months: Vec<_> = json.iter().map(|row| row["month"].as_str().unwrap()).collect();
+    assert_eq!(months, vec!["2025-07", "2025-06"]);
+    assert!(json[0]["models"].is_array());
+    assert!(json[0]["totalCost"].is_number());
+}
+
+#[test]
+fn markdown_has_aligned_table_and_totals() {
+    let markdown = render(ReportKind::Session, ExportFormat::Markdown, &DateRange::default());
+    assert!(markdown.starts_with("# Session Usage\n\nAll dates"));
+    assert!(markdown.contains("| --- | --- | --- | ---: |"));
+    assert!(markdown.lines().last().unwrap().starts_with("| **Total** |"));
+}
+
+#[test]
+fn html_is_self_contained_with_charts() {
+    let html = render(ReportKind::Daily, ExportFormat::Html, &DateRange::default());
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert_eq!(html.matches("<svg").count(), 2);
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("src=\"http"));
+    assert!(html.contains("<tfoot>"));
+
+    assert_eq!(escape_html("<a href=\"x\">&'"), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;");
+}
+
+#[test]
+fn formats_and_file_names() {
+    assert_eq!(ExportFormat::parse("MD").unwrap(), ExportFormat::Markdown);
+    assert_eq!(ExportFormat::parse("jsonl").unwrap(), ExportFormat::Ndjson);
+    assert!(ExportFormat::parse("pdf").is_err());
+    assert!(ReportKind::parse("weekly").is_err());
+
+    let range = DateRange::parse(Some("2025-06-01"), Some("2025-06-30")).unwrap();
+    assert_eq!(
+        export::file_name(ReportKind::Daily, &range, ExportFormat::Csv),
+        "claude-daily-report-2025-06-01-to-2025-06-30.csv"
+    );
+    assert_eq!(
+        export::file_name(ReportKind::Blocks, &DateRange::default(), ExportFormat::Html),
+        "claude-blocks-report.html"
+    );
+}
+
+#[test]
+fn models_and_projects_reports_sum_to_the_same_totals() {
+    let models = render(ReportKind::Models, ExportFormat::Json, &DateRange::default());
+    let projects = render(ReportKind::Projects, ExportFormat::Json, &DateRange::default());
+    let models: Vec<serde_json::Value> = serde_json::from_str(&models).unwrap();
+    let projects: Vec<serde_json::Value> = serde_json::from_str(&projects).unwrap();
+
+    let sum = |rows: &[serde_json::Value], key: &str| rows.iter().map(|row| row[key].as_u64().unwrap()).sum::<u64>();
+    assert_eq!(sum(&models, "totalTokens"), sum(&projects, "totalTokens"));
+    assert!(models.iter().all(|row| row["requests"].as_u64().unwrap() > 0));
+    assert!(projects.iter().all(|row| row["sessions"].as_u64().unwrap() > 0));
+    let share: f64 = models.iter().map(|row| row["costShare"].as_f64().unwrap()).sum();
+    assert!((share - 1.0).abs() < 1e-3);
+    assert!(models.iter().all(|row| row["cacheHitRatio"].as_f64().unwrap() <= 1.0));
+    assert!(models.iter().all(|row| row["averageTokensPerRequest"].as_f64().unwrap() > 0.0));
+
+    let costs: Vec<f64> = models.iter().map(|row| row["totalCost"].as_f64().unwrap()).collect();
+    assert!(costs.windows(2).all(|pair| pair[0] >= pair[1]));
+}
+
+#[test]
+fn xlsx_has_a_sheet_per_report_with_typed_cells() {
+    let bytes = render_bytes(ReportKind::Session, ExportFormat::Xlsx, &DateRange::default());
+    assert!(bytes.starts_with(b"PK"));
+
+    let text = |name: &str| {
+        let mut archive = zip::ZipArchive::new(Cursor::new(&bytes)).unwrap();
+        let mut xml = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut xml).unwrap();
+        xml
+    };
+    let workbook = text("xl/workbook.xml");
+    let sheets: Vec<&str> = ["Sessions", "Daily", "Monthly", "Models", "Projects"]
+        .into_iter()
+        .filter(|name| workbook.contains(&format!("name=\"{}\"", name)))
+        .collect();
+    assert_eq!(sheets.len(), 5);
+    assert!(workbook.find("\"Sessions\"").unwrap() < workbook.find("\"Daily\"").unwrap());
+
+    // Dates and token counts are numeric cells rather than strings
+    let daily = text("xl/worksheets/sheet2.xml");
+    assert!(daily.contains("<pane "));
+    for cell in ["A2", "C2"] {
+        let start = daily.find(&format!("<c r=\"{}\"", cell)).unwrap();
+        let tag = &daily[start..start + daily[start..].find('>').unwrap()];
+        assert!(!tag.contains("t=\""), "{} is not numeric: {}", cell, tag);
+    }
+
+    // Averages keep a decimal place instead of rounding to whole tokens
+    assert!(text("xl/styles.xml").contains("formatCode=\"#,##0.0\""));
+}
+
+fn read_parquet(bytes: Vec<u8>) -> Vec<RecordBatch> {
+    ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(bytes))
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+}
+
+#[test]
+fn parquet_keeps_column_types() {
+    let batches = read_parquet(render_bytes(ReportKind::Daily, ExportFormat::Parquet, &DateRange::default()));
+    let csv = render(ReportKind::Daily, ExportFormat::Csv, &DateRange::default());
+    let rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+    assert_eq!(rows, csv.split_terminator('\n').count() - 1);
+
+    let schema = batches[0].schema();
+    assert_eq!(schema.field_with_name("date").unwrap().data_type(), &DataType::Date32);
+    assert_eq!(schema.field_with_name("totalTokens").unwrap().data_type(), &DataType::UInt64);
+    assert_eq!(schema.field_with_name("totalCost").unwrap().data_type(), &DataType::Float64);
+    assert!(matches!(schema.field_with_name("models").unwrap().data_type(), DataType::List(_)));
+
+    let total: u64 = batches
+        .iter()
+        .flat_map(|batch| batch.column_by_name("totalTokens").unwrap().as_primitive::<UInt64Type>().values().to_vec())
+        .sum();
+    assert!(total > 0);
+}
+
+#[test]
+fn entries_export_one_row_per_entry() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    let batches = read_parquet(render_bytes(ReportKind::Entries, ExportFormat::Parquet, &DateRange::default()));
+    assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), entries.len());
+
+    let schema = batches[0].schema();
+    assert_eq!(
+        schema.field_with_name("timestamp").unwrap().data_type(),
+        &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+    );
+    assert!(schema.field_with_name("messageId").unwrap().is_nullable());
+
+    let arrow = render_bytes(ReportKind::Entries, ExportFormat::Arrow, &DateRange::default());
+    let reader = arrow_ipc::reader::FileReader::try_new(Cursor::new(arrow), None).unwrap();
+    assert_eq!(reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>(), entries.len());
+}
+
+#[test]
+fn atomic_writes_follow_the_overwrite_policy() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.csv");
+    let write = |overwrite, content: &'static str| {
+        export::write_atomically(&path, overwrite, |out| std::io::Write::write_all(out, content.as_bytes()))
+    };
+
+    assert_eq!(write(OverwritePolicy::Fail, "first").unwrap(), path);
+    let error = write(OverwritePolicy::Fail, "second").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+    assert_eq!(write(OverwritePolicy::Replace, "replaced").unwrap(), path);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "replaced");
+
+    assert_eq!(write(OverwritePolicy::Rename, "renamed").unwrap(), dir.path().join("report (1).csv"));
+    assert_eq!(write(OverwritePolicy::Rename, "again").unwrap(), dir.path().join("report (2).csv"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "replaced");
+
+    // A failed export leaves neither the destination nor a temporary file behind
+    let failed = dir.path().join("failed.csv");
+    let result = export::write_atomically(&failed, OverwritePolicy::Fail, |_| Err(std::io::Error::other("boom")));
+    assert!(result.is_err());
+    let names: Vec<String> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names.len(), 3, "{:?}", names);
+
+    assert!(OverwritePolicy::parse("skip").is_err());
+}
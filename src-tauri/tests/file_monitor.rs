@@ -0,0 +1,136 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri_app_lib::file_monitor::{is_remote_fuse_mount, ChangeKind, Debouncer, FileChangeBatch};
+
+const WINDOW: Duration = Duration::from_millis(500);
+
+fn append(path: &Path, line: &str) {
+    let mut file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+    writeln!(file, "{}", line).unwrap();
+}
+
+fn names(paths: &[String]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| Path::new(p).file_name().unwrap().to_string_lossy().to_string())
+        .collect()
+}
+
+#[test]
+fn events_for_one_path_merge_until_the_window_is_quiet() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    let start = Instant::now();
+    let mut debouncer = Debouncer::new();
+
+    append(&path, "{}");
+    debouncer.push(path.clone(), ChangeKind::Created, start);
+    append(&path, "{}");
+    debouncer.push(path.clone(), ChangeKind::Modified, start + Duration::from_millis(300));
+
+    assert!(debouncer.flush(WINDOW, start + Duration::from_millis(600)).is_empty());
+    assert_eq!(debouncer.next_deadline(WINDOW), Some(start + Duration::from_millis(800)));
+
+    let batch = debouncer.flush(WINDOW, start + Duration::from_millis(800));
+    assert_eq!(names(&batch.created), vec!["session.jsonl"]);
+    assert!(batch.modified.is_empty() && batch.removed.is_empty());
+    assert_eq!(debouncer.next_deadline(WINDOW), None);
+}
+
+#[test]
+fn batches_group_paths_by_kind() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b, c) = (dir.path().join("a.jsonl"), dir.path().join("b.jsonl"), dir.path().join("c.jsonl"));
+    append(&a, "{}");
+    append(&b, "{}");
+    let start = Instant::now();
+    let mut debouncer = Debouncer::new();
+
+    debouncer.push(a.clone(), ChangeKind::Created, start);
+    debouncer.push(b.clone(), ChangeKind::Modified, start);
+    debouncer.push(c.clone(), ChangeKind::Removed, start);
+
+    let batch = debouncer.flush(WINDOW, start + WINDOW);
+    assert_eq!(
+        (names(&batch.created), names(&batch.modified), names(&batch.removed)),
+        (vec!["a.jsonl".to_string()], vec!["b.jsonl".to_string()], vec!["c.jsonl".to_string()])
+    );
+}
+
+#[test]
+fn unchanged_files_and_short_lived_files_are_dropped() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    let temp = dir.path().join("temp.jsonl");
+    append(&path, "{}");
+    let start = Instant::now();
+    let mut debouncer = Debouncer::new();
+
+    debouncer.push(path.clone(), ChangeKind::Modified, start);
+    assert_eq!(names(&debouncer.flush(WINDOW, start + WINDOW).modified), vec!["session.jsonl"]);
+
+    // Metadata-only events leave size and mtime alone
+    debouncer.push(path.clone(), ChangeKind::Modified, start + WINDOW);
+    assert!(debouncer.flush(WINDOW, start + WINDOW * 2).is_empty());
+
+    // Created then deleted inside one window
+    debouncer.push(temp.clone(), ChangeKind::Created, start + WINDOW * 2);
+    debouncer.push(temp.clone(), ChangeKind::Removed, start + WINDOW * 2);
+    assert_eq!(debouncer.flush(WINDOW, start + WINDOW * 3), FileChangeBatch::default());
+}
+
+#[test]
+fn busy_files_are_flushed_after_the_maximum_wait() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    let start = Instant::now();
+    let mut debouncer = Debouncer::new();
+
+    let mut flushed = None;
+    for step in 0..20u32 {
+        let now = start + Duration::from_millis(200) * step;
+        append(&path, "{}");
+        debouncer.push(path.clone(), ChangeKind::Modified, now);
+        if !debouncer.flush(WINDOW, now).is_empty() {
+            flushed = Some(now - start);
+            break;
+        }
+    }
+    assert_eq!(flushed, Some(Duration::from_millis(2000)));
+}
+
+#[test]
+fn replaced_files_are_reported_as_modified() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    append(&path, "{}");
+    let start = Instant::now();
+    let mut debouncer = Debouncer::new();
+
+    debouncer.push(path.clone(), ChangeKind::Removed, start);
+    fs::write(&path, "{}\n{}\n").unwrap();
+    debouncer.push(path.clone(), ChangeKind::Created, start);
+
+    let batch = debouncer.flush(WINDOW, start + WINDOW);
+    assert_eq!(names(&batch.modified), vec!["session.jsonl"]);
+    assert!(batch.removed.is_empty());
+}
+
+#[test]
+fn only_remote_fuse_mounts_count_as_network_file_systems() {
+    let mounts = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+/home/.alice /home/alice fuse.gocryptfs rw,nosuid,nodev 0 0
+alice@server:/srv /home/alice/remote fuse.sshfs rw,nosuid,nodev 0 0
+sshfs#bob@server:/srv /mnt/old fuse rw 0 0
+/dev/sdb1 /mnt/usb\\040stick fuseblk rw 0 0
+";
+    let remote = |path: &str| is_remote_fuse_mount(mounts, Path::new(path));
+    assert!(!remote("/home/alice/.claude"));
+    assert!(remote("/home/alice/remote/.claude"));
+    assert!(remote("/mnt/old/.claude"));
+    assert!(!remote("/mnt/usb stick/.claude"));
+    assert!(!remote("/tmp"));
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::Fixture;
+use std::path::Path;
+use tauri_app_lib::fs_scope::{
+    check_export_path, protect_data_directories, validate_data_directory, validate_export_directory,
+};
+use tauri_plugin_fs::FsExt;
+
+#[test]
+fn broad_custom_directories_are_rejected() {
+    let home = tempfile::tempdir().unwrap();
+    let home = home.path();
+    let check = |dir: &str| validate_data_directory(dir, Some(home));
+
+    assert!(check("/").is_err());
+    assert!(check("").is_err());
+    assert!(check("relative/claude").is_err());
+    assert!(check(&home.to_string_lossy()).is_err());
+    assert!(check(&format!("{}/", home.display())).is_err());
+    assert!(check(&format!("{}/projects/..", home.display())).is_err());
+    assert!(check(&home.parent().unwrap().to_string_lossy()).is_err());
+
+    assert!(check(&home.join(".claude").to_string_lossy()).is_ok());
+    assert!(check(&home.join("work/claude-data").to_string_lossy()).is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_to_broad_directories_are_rejected() {
+    let home = tempfile::tempdir().unwrap();
+    let link = home.path().join("claude-link");
+    std::os::unix::fs::symlink(Path::new("/"), &link).unwrap();
+
+    assert!(validate_data_directory(&link.to_string_lossy(), Some(home.path())).is_err());
+}
+
+#[test]
+fn export_paths_must_be_inside_an_allowed_directory() {
+    let root = tempfile::tempdir().unwrap();
+    let allowed = root.path().join("exports");
+    std::fs::create_dir_all(allowed.join("2025")).unwrap();
+    std::fs::create_dir_all(root.path().join("elsewhere")).unwrap();
+    let check = |path: &Path| check_export_path(&path.to_string_lossy(), std::slice::from_ref(&allowed));
+    let canonical = allowed.canonicalize().unwrap();
+
+    assert_eq!(check(&allowed.join("daily.csv")).unwrap(), canonical.join("daily.csv"));
+    assert_eq!(check(&allowed.join("2025/daily.csv")).unwrap(), canonical.join("2025/daily.csv"));
+    assert!(check(&allowed.join("../elsewhere/daily.csv")).is_err());
+    assert!(check(&root.path().join("elsewhere/daily.csv")).is_err());
+    assert!(check(&allowed.join("missing/daily.csv")).is_err());
+    assert!(check(&allowed.join("2025")).is_err());
+    assert!(check_export_path("daily.csv", std::slice::from_ref(&allowed)).is_err());
+
+    let home = root.path();
+    assert!(validate_export_directory(&home.to_string_lossy(), Some(home)).is_err());
+    assert!(validate_export_directory(&allowed.to_string_lossy(), Some(home)).is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn export_paths_through_symlinks_are_checked_at_their_target() {
+    let root = tempfile::tempdir().unwrap();
+    let allowed = root.path().join("exports");
+    std::fs::create_dir_all(&allowed).unwrap();
+    std::os::unix::fs::symlink(root.path(), allowed.join("escape")).unwrap();
+
+    let path = allowed.join("escape/daily.csv");
+    assert!(check_export_path(&path.to_string_lossy(), &[allowed]).is_err());
+}
+
+#[test]
+fn privacy_mode_keeps_data_directories_out_of_the_fs_scope() {
+    let fixture = Fixture::new();
+    let data_dir = fixture.config_dirs[0].to_string_lossy().to_string();
+    let transcript = fixture.config_dirs[0].join("projects/app/session.jsonl");
+    let other = fixture.path().join("notes.txt");
+    let mock_app = || {
+        let app = tauri::test::mock_builder()
+            .plugin(tauri_plugin_fs::init())
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        // As if a capability granted everything around the data directories
+        app.fs_scope().allow_directory(fixture.path(), true).unwrap();
+        app
+    };
+
+    let app = mock_app();
+    protect_data_directories(app.handle(), true, std::slice::from_ref(&data_dir));
+    let scope = app.fs_scope();
+    assert!(!scope.is_allowed(&transcript));
+    assert!(scope.is_allowed(&other));
+    for dir in tauri_app_lib::core::claude_path_candidates() {
+        assert!(!scope.is_allowed(dir.join("projects/app/session.jsonl")), "{}", dir.display());
+    }
+
+    let app = mock_app();
+    protect_data_directories(app.handle(), false, &[data_dir]);
+    assert!(app.fs_scope().is_allowed(&transcript));
+}
@@ -0,0 +1,212 @@
+mod common;
+
+use common::{at, Fixture, OPUS, SONNET};
+use std::io::Cursor;
+use tauri_app_lib::archive::{self, archive_key};
+use tauri_app_lib::core::load_entries_from;
+use tauri_app_lib::export::{self, ExportFormat, ReportKind};
+use tauri_app_lib::import::{parse_import, validate_machine, ImportFormat, Imports};
+use tauri_app_lib::pricing::CostMode;
+use tauri_app_lib::reports::{self, DateRange, LOCAL_MACHINE};
+use tauri_app_lib::usage::UsageEntry;
+
+fn export_entries(entries: &[UsageEntry], format: ExportFormat) -> String {
+    let mut out = Cursor::new(Vec::new());
+    export::write_report(
+        &mut out,
+        ReportKind::Entries,
+        format,
+        &DateRange::default(),
+        entries,
+        CostMode::Auto,
+        at(2025, 7, 1, 0, 0),
+    )
+    .unwrap();
+    String::from_utf8(out.into_inner()).unwrap()
+}
+
+const CCUSAGE_DAILY: &str = r#"{
+  "daily": [
+    {
+      "date": "2025-06-02",
+      "inputTokens": 1500,
+      "outputTokens": 700,
+      "cacheCreationTokens": 2000,
+      "cacheReadTokens": 9000,
+      "totalTokens": 13200,
+      "totalCost": 0.9,
+      "modelsUsed": ["claude-sonnet-4-20250514", "claude-opus-4-20250514"],
+      "modelBreakdowns": [
+        { "modelName": "claude-opus-4-20250514", "inputTokens": 500, "outputTokens": 200,
+          "cacheCreationTokens": 0, "cacheReadTokens": 1000, "cost": 0.6 },
+        { "modelName": "claude-sonnet-4-20250514", "inputTokens": 1000, "outputTokens": 500,
+          "cacheCreationTokens": 2000, "cacheReadTokens": 8000, "cost": 0.3 }
+      ]
+    },
+    {
+      "date": "2025-06-03",
+      "inputTokens": 100,
+      "outputTokens": 50,
+      "cacheCreationTokens": 0,
+      "cacheReadTokens": 0,
+      "totalTokens": 150,
+      "totalCost": 0.01,
+      "modelsUsed": ["claude-sonnet-4-20250514"]
+    }
+  ],
+  "totals": { "inputTokens": 1600, "outputTokens": 750, "totalCost": 0.91 }
+}"#;
+
+#[test]
+fn claude_deck_exports_import_once_per_message() {
+    let fixture = Fixture::new();
+    let live = load_entries_from(&fixture.config_dirs);
+    let imports = Imports::new(fixture.path().join("imports"));
+
+    for format in [ExportFormat::Ndjson, ExportFormat::Json] {
+        let (kind, entries) = parse_import(&export_entries(&live, format), "devbox").unwrap();
+        assert_eq!(kind, ImportFormat::ClaudeDeck);
+        assert_eq!(entries.len(), live.len());
+        assert!(entries.iter().all(|entry| entry.machine.as_deref() == Some("devbox")));
+    }
+
+    let path = fixture.path().join("usage.ndjson");
+    std::fs::write(&path, export_entries(&live, ExportFormat::Ndjson)).unwrap();
+    let first = imports.import_file(&path, "devbox").unwrap();
+    assert_eq!((first.records, first.added, first.duplicates), (live.len(), live.len(), 0));
+    let again = imports.import_file(&path, " devbox ").unwrap();
+    assert_eq!((again.added, again.duplicates), (0, live.len()));
+
+    let stored = imports.entries();
+    let keys = |entries: &[UsageEntry]| entries.iter().map(archive_key).collect::<Vec<_>>();
+    assert_eq!(keys(&stored), keys(&live));
+    let first = &stored[0];
+    assert_eq!((first.input_tokens, first.cost_usd), (live[0].input_tokens, Some(0.05)));
+
+    // Records this machine still has in its transcripts are counted once
+    let mut merged = live.clone();
+    assert_eq!(archive::merge(&mut merged, stored), 0);
+
+    let machines = imports.machines();
+    assert_eq!(machines.len(), 1);
+    assert_eq!((machines[0].machine.as_str(), machines[0].entries), ("devbox", live.len()));
+    assert_eq!(machines[0].last, live.last().map(|entry| entry.timestamp));
+
+    assert!(imports.remove("devbox").unwrap());
+    assert!(!imports.remove("devbox").unwrap());
+    assert!(imports.entries().is_empty());
+}
+
+#[test]
+fn ccusage_reports_become_per_model_records() {
+    let (kind, entries) = parse_import(CCUSAGE_DAILY, "laptop").unwrap();
+    assert_eq!(kind, ImportFormat::Ccusage);
+    assert_eq!(entries.len(), 3);
+
+    let models = reports::model_report(&entries, CostMode::Auto);
+    assert_eq!(models[0].model, OPUS);
+    assert_eq!((models[0].totals.input_tokens, models[0].totals.total_cost), (500, 0.6));
+    let sonnet = models.iter().find(|model| model.model == SONNET).unwrap();
+    assert_eq!((sonnet.totals.input_tokens, sonnet.requests), (1100, 2));
+
+    let daily = reports::daily_report(&entries, CostMode::Auto);
+    let dates: Vec<&str> = daily.iter().map(|day| day.date.as_str()).collect();
+    assert_eq!(dates, ["2025-06-03", "2025-06-02"]);
+    assert_eq!(daily[1].totals.total_tokens, 13200);
+    assert!((daily[1].totals.total_cost - 0.9).abs() < 1e-9);
+
+    let blocks = r#"{"blocks": [
+        {"id": "2025-06-02T10:00:00.000Z", "startTime": "2025-06-02T10:00:00.000Z", "isGap": false,
+         "tokenCounts": {"inputTokens": 10, "outputTokens": 20, "cacheCreationInputTokens": 30, "cacheReadInputTokens": 40},
+         "costUSD": 0.2, "models": ["claude-sonnet-4-20250514"]},
+        {"id": "gap-2025-06-02T15:00:00.000Z", "startTime": "2025-06-02T15:00:00.000Z", "isGap": true,
+         "tokenCounts": {}, "costUSD": 0, "models": []}
+    ]}"#;
+    let (_, entries) = parse_import(blocks, "laptop").unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].timestamp, at(2025, 6, 2, 10, 0));
+    assert_eq!((entries[0].total_tokens(), entries[0].model.as_deref()), (100, Some(SONNET)));
+}
+
+#[test]
+fn reimported_ccusage_reports_replace_earlier_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    let imports = Imports::new(dir.path());
+
+    let (format, entries) = parse_import(CCUSAGE_DAILY, "laptop").unwrap();
+    let first = imports.import("laptop", format, entries).unwrap();
+    assert_eq!((first.added, first.duplicates), (3, 0));
+
+    // The next day's report has grown and covers one more day
+    let updated = CCUSAGE_DAILY
+        .replace(r#""inputTokens": 100,"#, r#""inputTokens": 400,"#)
+        .replace("\"2025-06-03\"", "\"2025-06-04\"");
+    let (format, entries) = parse_import(&updated, "laptop").unwrap();
+    let second = imports.import("laptop", format, entries).unwrap();
+    assert_eq!((second.added, second.duplicates), (1, 2));
+
+    let stored = imports.entries();
+    assert_eq!(stored.len(), 3);
+    assert_eq!(stored.last().map(|entry| (entry.timestamp, entry.input_tokens)), Some((at(2025, 6, 4, 12, 0), 400)));
+
+    // A monthly report covers the same usage, so it replaces the daily one
+    let monthly = r#"{"monthly": [{"month": "2025-06", "inputTokens": 1900, "outputTokens": 750,
+        "cacheCreationTokens": 2000, "cacheReadTokens": 9000, "totalCost": 0.95, "modelsUsed": []}]}"#;
+    let (format, entries) = parse_import(monthly, "laptop").unwrap();
+    imports.import("laptop", format, entries).unwrap();
+    let stored = imports.entries();
+    assert_eq!(stored.len(), 1);
+    assert_eq!((stored[0].timestamp, stored[0].model.as_deref()), (at(2025, 6, 1, 12, 0), None));
+}
+
+#[test]
+fn machine_report_separates_local_and_imported_usage() {
+    let fixture = Fixture::new();
+    let mut entries = load_entries_from(&fixture.config_dirs);
+    let local = reports::daily_report(&entries, CostMode::Auto).len();
+    let (_, imported) = parse_import(CCUSAGE_DAILY, "laptop").unwrap();
+    assert_eq!(archive::merge(&mut entries, imported), 3);
+    assert!(reports::daily_report(&entries, CostMode::Auto).len() > local);
+
+    let machines = reports::machine_report(&entries, CostMode::Auto);
+    let names: Vec<&str> = machines.iter().map(|machine| machine.machine.as_str()).collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&LOCAL_MACHINE) && names.contains(&"laptop"));
+    let laptop = machines.iter().find(|machine| machine.machine == "laptop").unwrap();
+    assert_eq!((laptop.totals.total_tokens, laptop.sessions), (13350, 2));
+
+    // Imported records keep their label through the usage-only transcripts sent to the frontend
+    let line = entries.iter().find(|entry| entry.machine.is_some()).unwrap().to_transcript_line();
+    let parsed = tauri_app_lib::usage::parse_line(&line, "p", "s").unwrap().unwrap();
+    assert_eq!(parsed.machine.as_deref(), Some("laptop"));
+}
+
+#[test]
+fn unusable_imports_are_rejected() {
+    for label in ["", "   ", "local", "LOCAL", "dev box", "../etc", ".hidden", &"x".repeat(65)] {
+        assert!(validate_machine(label).is_err(), "{:?} should be rejected", label);
+    }
+    assert_eq!(validate_machine("dev-box_2.lan").unwrap(), "dev-box_2.lan");
+
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    let mut daily = Cursor::new(Vec::new());
+    export::write_report(
+        &mut daily,
+        ReportKind::Daily,
+        ExportFormat::Json,
+        &DateRange::default(),
+        &entries,
+        CostMode::Auto,
+        at(2025, 7, 1, 0, 0),
+    )
+    .unwrap();
+    let daily = String::from_utf8(daily.into_inner()).unwrap();
+    let error = parse_import(&daily, "devbox").unwrap_err();
+    assert!(error.contains("export the entries report"), "{}", error);
+
+    assert!(parse_import("date,inputTokens\n2025-06-01,5\n", "devbox").is_err());
+    assert!(parse_import("{\"timestamp\": \"2025-06-01T00:00:00Z\"}\n{\"timest", "devbox")
+        .unwrap_err()
+        .starts_with("Failed to parse NDJSON line 2"));
+}
@@ -0,0 +1,42 @@
+#![cfg(unix)]
+
+use std::io::ErrorKind;
+use std::os::unix::net::UnixListener;
+use tauri_app_lib::ipc::{remove_stale_socket, METHODS};
+
+#[test]
+fn every_command_is_served_on_the_socket() {
+    let lib = include_str!("../src/lib.rs");
+    let ipc = include_str!("../src/ipc.rs");
+    let commands: Vec<&str> = lib
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("commands::"))
+        .map(|name| name.trim_end_matches(','))
+        .collect();
+    assert!(commands.len() > 20);
+
+    for command in &commands {
+        assert!(METHODS.contains(command), "{} is missing from ipc::METHODS", command);
+    }
+    for method in METHODS {
+        assert!(commands.contains(method), "{} is not a registered command", method);
+        assert!(ipc.contains(&format!("\"{}\" =>", method)), "{} has no dispatch arm", method);
+    }
+}
+
+#[test]
+fn only_stale_sockets_are_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("claudedeck.sock");
+    remove_stale_socket(&path).unwrap();
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let err = remove_stale_socket(&path).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AddrInUse);
+    assert!(path.exists());
+
+    // Closing the listener leaves the file behind, refusing connections
+    drop(listener);
+    remove_stale_socket(&path).unwrap();
+    assert!(!path.exists());
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use common::{at, Fixture, OPUS, SONNET};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri_app_lib::core::{last_load_stats, load_entries_from, usage_transcripts, EntryCache};
+use tauri_app_lib::fixtures::{assistant_line, generate, FixtureDir, FixtureMessage, GenerateOptions};
+use tauri_app_lib::usage::{parse_content, parse_line};
+
+#[test]
+fn loads_entries_from_every_config_dir_oldest_first() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+
+    let ids: Vec<_> = entries.iter().map(|e| e.message_id.as_deref().unwrap()).collect();
+    assert_eq!(ids, vec!["msg_a1", "msg_a2", "msg_a3", "msg_a4", "msg_b1", "msg_b2", "msg_b3"]);
+    assert!(entries.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+}
+
+#[test]
+fn duplicated_messages_are_counted_once_for_the_original_session() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+
+    let copies: Vec<_> = entries
+        .iter()
+        .filter(|e| e.message_id.as_deref() == Some("msg_a1"))
+        .collect();
+    assert_eq!(copies.len(), 1);
+    assert_eq!(copies[0].session_id, "session-alpha-1");
+    assert_eq!(copies[0].project, "-home-dev-alpha");
+}
+
+#[test]
+fn load_stats_count_files_and_malformed_lines() {
+    let fixture = Fixture::new();
+    load_entries_from(&fixture.config_dirs);
+
+    let stats = last_load_stats().unwrap();
+    assert_eq!(stats.files_scanned, 3);
+    assert_eq!(stats.parse_errors, 2);
+    assert!(stats.finished_at.is_some());
+}
+
+#[test]
+fn parse_content_skips_user_and_malformed_lines() {
+    let message = FixtureMessage::new(at(2025, 6, 1, 12, 0), SONNET, "x").tokens(10, 5);
+    let content = format!(
+        "{}\n{{\"timestamp\":\"2025-06-01T12:00:00Z\",\"type\":\"user\",\"message\":{{\"role\":\"user\",\"content\":\"hi\"}}}}\n\n{{broken\n",
+        assistant_line(&message, "s", "/tmp")
+    );
+
+    let parsed = parse_content(&content, Path::new("/data/projects/-tmp/s.jsonl"));
+    assert_eq!(parsed.entries.len(), 1);
+    assert_eq!(parsed.malformed_lines, 1);
+    assert_eq!(parsed.entries[0].project, "-tmp");
+    assert_eq!(parsed.entries[0].session_id, "s");
+}
+
+#[test]
+fn parse_line_reads_usage_and_optional_cost() {
+    let with_cost = FixtureMessage::new(at(2025, 6, 1, 12, 0), OPUS, "c")
+        .tokens(10, 20)
+        .cache(30, 40)
+        .cost(0.25);
+    let entry = parse_line(&assistant_line(&with_cost, "s", "/tmp"), "p", "s")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(entry.model.as_deref(), Some(OPUS));
+    assert_eq!(
+        (entry.input_tokens, entry.output_tokens, entry.cache_creation_tokens, entry.cache_read_tokens),
+        (10, 20, 30, 40)
+    );
+    assert_eq!(entry.total_tokens(), 100);
+    assert_eq!(entry.cost_usd, Some(0.25));
+    assert_eq!(entry.dedup_key().as_deref(), Some("msg_c:req_c"));
+
+    let without_cost = FixtureMessage::new(at(2025, 6, 1, 12, 0), OPUS, "d").tokens(1, 1);
+    let entry = parse_line(&assistant_line(&without_cost, "s", "/tmp"), "p", "s")
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.cost_usd, None);
+}
+
+#[test]
+fn generated_workload_loads_every_message() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions {
+        projects: 3,
+        sessions: 4,
+        messages: 25,
+        ..GenerateOptions::default()
+    };
+    let summary = generate(&FixtureDir::new(dir.path()), &options).unwrap();
+    assert_eq!(summary.files, 12);
+
+    let entries = load_entries_from(&[dir.path().to_path_buf()]);
+    assert_eq!(entries.len(), 3 * 4 * 25);
+    assert!(entries
+        .iter()
+        .all(|e| e.timestamp >= options.start && e.timestamp <= options.end + chrono::Duration::days(1)));
+}
+
+#[test]
+fn usage_transcripts_carry_usage_without_message_text() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    let transcripts = usage_transcripts(&entries);
+
+    // One transcript per session that has usage
+    assert_eq!(transcripts.len(), 3);
+    assert!(transcripts.iter().all(|t| !t.contains("Refactor the parser") && !t.contains("content")));
+
+    let reparsed: Vec<_> = transcripts
+        .iter()
+        .flat_map(|t| parse_content(t, Path::new("p/s.jsonl")).entries)
+        .collect();
+    assert_eq!(reparsed.len(), entries.len());
+    assert_eq!(
+        reparsed.iter().map(|e| e.total_tokens()).sum::<u64>(),
+        entries.iter().map(|e| e.total_tokens()).sum::<u64>()
+    );
+}
+
+#[test]
+fn entry_cache_rescans_only_after_invalidation() {
+    let fixture = Fixture::new();
+    let cache = EntryCache::new(Duration::from_secs(60));
+    let scans = AtomicUsize::new(0);
+    let load = || {
+        scans.fetch_add(1, Ordering::SeqCst);
+        load_entries_from(&fixture.config_dirs)
+    };
+
+    let first = cache.get(load);
+    let second = cache.get(load);
+    assert_eq!(scans.load(Ordering::SeqCst), 1);
+    assert!(Arc::ptr_eq(&first, &second));
+
+    cache.invalidate();
+    let third = cache.get(load);
+    assert_eq!(scans.load(Ordering::SeqCst), 2);
+    assert_eq!(third.len(), first.len());
+
+    // Stale scans are not reused even without invalidation
+    let expiring = EntryCache::new(Duration::ZERO);
+    expiring.get(load);
+    expiring.get(load);
+    assert_eq!(scans.load(Ordering::SeqCst), 4);
+}
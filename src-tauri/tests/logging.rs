@@ -0,0 +1,23 @@
+use std::collections::BTreeMap;
+use tauri_app_lib::logging::check_settings;
+use tauri_app_lib::settings::LoggingSettings;
+
+#[test]
+fn invalid_log_filters_are_rejected_before_saving() {
+    let settings = |level: &str, module: &str| LoggingSettings {
+        level: level.to_string(),
+        modules: BTreeMap::from([("file_monitor".to_string(), module.to_string())]),
+    };
+
+    assert!(check_settings(&LoggingSettings::default()).is_ok());
+    assert!(check_settings(&settings("warn", "trace")).is_ok());
+    assert!(check_settings(&settings("info", "verbose")).is_err());
+}
+
+#[test]
+fn partial_stored_settings_keep_their_defaults() {
+    let settings: LoggingSettings =
+        serde_json::from_value(serde_json::json!({ "modules": { "api": "debug" } })).unwrap();
+    assert_eq!(settings.level, "info");
+    assert_eq!(settings.modules["api"], "debug");
+}
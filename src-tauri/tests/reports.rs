@@ -0,0 +1,109 @@
+mod common;
+
+use common::{assert_golden, at, Fixture};
+use tauri_app_lib::core::load_entries_from;
+use tauri_app_lib::pricing::CostMode;
+use tauri_app_lib::reports::{self, DateRange};
+
+#[test]
+fn daily_report_matches_golden() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    assert_golden("daily", &reports::daily_report(&entries, CostMode::Auto));
+}
+
+#[test]
+fn daily_report_in_calculate_mode_matches_golden() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    assert_golden("daily_calculate", &reports::daily_report(&entries, CostMode::Calculate));
+}
+
+#[test]
+fn monthly_report_matches_golden() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    assert_golden("monthly", &reports::monthly_report(&entries, CostMode::Auto));
+}
+
+#[test]
+fn session_report_matches_golden() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    assert_golden("sessions", &reports::session_report(&entries, CostMode::Auto));
+}
+
+#[test]
+fn block_report_matches_golden() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    let now = at(2025, 7, 2, 9, 0);
+
+    assert_golden("blocks", &reports::block_report(&entries, CostMode::Auto, now));
+
+    let active = reports::active_block(&entries, CostMode::Auto, now).unwrap();
+    assert_eq!(active.block_id, "2025-07-02T06:00:00.000Z");
+    assert!(reports::active_block(&entries, CostMode::Auto, at(2025, 7, 3, 9, 0)).is_none());
+}
+
+#[test]
+fn model_breakdowns_split_each_row_by_model() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+
+    let days = reports::daily_report(&entries, CostMode::Auto);
+    let months = reports::monthly_report(&entries, CostMode::Auto);
+    let blocks = reports::block_report(&entries, CostMode::Auto, at(2025, 7, 2, 9, 0));
+    let rows = days
+        .iter()
+        .map(|day| (&day.totals, &day.model_breakdowns))
+        .chain(months.iter().map(|month| (&month.totals, &month.model_breakdowns)))
+        .chain(blocks.iter().map(|block| (&block.totals, &block.model_breakdowns)));
+    for (totals, breakdowns) in rows {
+        let tokens: u64 = breakdowns
+            .iter()
+            .map(|model| model.input_tokens + model.output_tokens + model.cache_creation_tokens + model.cache_read_tokens)
+            .sum();
+        let cost: f64 = breakdowns.iter().map(|model| model.cost).sum();
+        assert_eq!(tokens, totals.total_tokens);
+        assert!((cost - totals.total_cost).abs() < 1e-9);
+        assert!(breakdowns.windows(2).all(|pair| pair[0].cost >= pair[1].cost));
+    }
+}
+
+#[test]
+fn model_report_describes_each_models_token_mix() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    let models = reports::model_report(&entries, CostMode::Auto);
+
+    let share: f64 = models.iter().map(|model| model.cost_share).sum();
+    assert!((share - 1.0).abs() < 1e-9);
+    for model in &models {
+        let totals = &model.totals;
+        let prompt = totals.input_tokens + totals.cache_creation_tokens + totals.cache_read_tokens;
+        let hit_ratio = if prompt > 0 { totals.cache_read_tokens as f64 / prompt as f64 } else { 0.0 };
+        assert!((model.cache_hit_ratio - hit_ratio).abs() < 1e-12, "{}", model.model);
+        assert!((0.0..=1.0).contains(&model.cache_hit_ratio));
+        let average = totals.total_tokens as f64 / model.requests as f64;
+        assert!((model.average_tokens_per_request - average).abs() < 1e-9);
+    }
+
+    assert!(reports::model_report(&[], CostMode::Auto).is_empty());
+}
+
+#[test]
+fn date_range_limits_entries_inclusively() {
+    let fixture = Fixture::new();
+    let mut entries = load_entries_from(&fixture.config_dirs);
+
+    let range = DateRange::parse(Some("2025-05-31"), Some("20250601")).unwrap();
+    entries.retain(|entry| range.contains(entry));
+
+    let days: Vec<_> = reports::daily_report(&entries, CostMode::Auto)
+        .into_iter()
+        .map(|day| day.date)
+        .collect();
+    assert_eq!(days, vec!["2025-06-01", "2025-05-31"]);
+    assert!(DateRange::parse(Some("June"), None).is_err());
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::Fixture;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tauri_app_lib::core::load_entries_from;
+use std::path::Path;
+use tauri_app_lib::scanner::{CancelToken, ScanPhase, ScanProgress, Scanner, CANCELLED};
+use tauri_app_lib::usage::parse_content;
+
+#[test]
+fn scan_matches_the_sequential_loader() {
+    let fixture = Fixture::new();
+    let result = Scanner::new().scan(&fixture.config_dirs).unwrap();
+
+    let scanned: Vec<_> = result.entries.iter().map(|e| e.message_id.clone()).collect();
+    let loaded: Vec<_> = load_entries_from(&fixture.config_dirs)
+        .iter()
+        .map(|e| e.message_id.clone())
+        .collect();
+    assert_eq!(scanned, loaded);
+    assert_eq!(result.files_scanned, 3);
+    assert_eq!(result.parse_errors, 2);
+}
+
+#[test]
+fn transcripts_keep_only_the_fields_the_frontend_reads() {
+    let fixture = Fixture::new();
+    let transcripts = Scanner::new().read_transcripts(&fixture.config_dirs).unwrap();
+    assert_eq!(transcripts.len(), 3);
+
+    let entries: usize = transcripts.iter().map(|content| parse_content(content, Path::new("")).entries.len()).sum();
+    // Lines are slimmed, not deduplicated, so the resumed session's copy of a1 is kept
+    assert_eq!(entries, 8);
+    // User lines stay for their timestamps, but message text and other metadata are dropped
+    let lines: Vec<&str> = transcripts.iter().flat_map(|content| content.lines()).collect();
+    assert_eq!(lines.len(), 10);
+    assert!(lines.iter().all(|line| !line.contains("Refactor the parser") && !line.contains("cwd")));
+    assert!(lines.iter().all(|line| line.contains("\"timestamp\"")));
+}
+
+#[test]
+fn progress_ends_with_every_file_and_byte_parsed() {
+    let fixture = Fixture::new();
+    let updates: Arc<Mutex<Vec<ScanProgress>>> = Arc::default();
+    let sink = updates.clone();
+
+    Scanner::new()
+        .on_progress(move |progress| sink.lock().unwrap().push(progress.clone()))
+        .scan(&fixture.config_dirs)
+        .unwrap();
+
+    let updates = updates.lock().unwrap();
+    let last = updates.last().unwrap();
+    assert_eq!(updates.first().unwrap().phase, ScanPhase::Discovering);
+    assert_eq!(last.phase, ScanPhase::Done);
+    assert_eq!(last.files_discovered, 3);
+    assert_eq!(last.files_parsed, 3);
+    assert!(last.bytes_parsed > 0);
+    assert_eq!(last.bytes_parsed, last.bytes_discovered);
+    assert_eq!(last.percent, 100.0);
+    assert!(updates.windows(2).all(|w| w[0].percent <= w[1].percent));
+}
+
+#[test]
+fn cancelled_scans_stop_with_an_error() {
+    let fixture = Fixture::new();
+    let token = CancelToken::new();
+    let cancel = token.clone();
+
+    // Cancel from the first progress update, as a UI would on a view switch
+    let result = Scanner::new()
+        .cancel_token(token)
+        .on_progress(move |_| cancel.cancel())
+        .scan(&fixture.config_dirs);
+
+    assert_eq!(result.unwrap_err(), CANCELLED);
+}
+
+#[test]
+fn invalid_utf8_lines_count_as_parse_errors() {
+    let fixture = Fixture::new();
+    let session = fixture.config_dirs[1].join("projects/-home-dev-beta/session-beta-1.jsonl");
+    let mut content = fs::read(&session).unwrap();
+    content.extend_from_slice(b"\xff\xfe broken\n");
+    fs::write(&session, content).unwrap();
+
+    let result = Scanner::new().scan(&fixture.config_dirs).unwrap();
+    assert_eq!(result.parse_errors, 3);
+    assert_eq!(result.entries.len(), 7);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_cycles_and_aliases_are_scanned_once() {
+    use std::os::unix::fs::symlink;
+
+    let fixture = Fixture::new();
+    let projects = fixture.config_dirs[0].join("projects");
+    let alpha = projects.join("-home-dev-alpha");
+
+    // A link back to the projects root, and a second name for an existing project
+    symlink(&projects, alpha.join("loop")).unwrap();
+    symlink(&alpha, projects.join("-home-dev-alias")).unwrap();
+
+    let files = Scanner::new().discover(std::slice::from_ref(&projects)).unwrap();
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|file| file.starts_with(&alpha)));
+
+    let result = Scanner::new().scan(&fixture.config_dirs).unwrap();
+    assert_eq!(result.files_scanned, 3);
+    assert!(result.entries.iter().all(|e| e.project != "-home-dev-alias"));
+}
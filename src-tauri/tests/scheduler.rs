@@ -0,0 +1,118 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{at, Fixture};
+use tauri_app_lib::core::load_entries_from;
+use tauri_app_lib::export::{ExportFormat, ReportKind};
+use tauri_app_lib::pricing::CostMode;
+use tauri_app_lib::scheduler::{self, render_file_name, ExportPeriod, Schedule, MAX_CATCH_UP};
+use tauri_app_lib::settings::ScheduledExport;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn cron_expressions_find_the_next_run() {
+    let monthly = Schedule::parse("0 9 1 * *").unwrap();
+    assert_eq!(monthly.next_after(&at(2025, 6, 15, 12, 0)), Some(at(2025, 7, 1, 9, 0)));
+    assert_eq!(monthly.next_after(&at(2025, 7, 1, 9, 0)), Some(at(2025, 8, 1, 9, 0)));
+    assert_eq!(monthly.next_after(&at(2025, 12, 1, 10, 0)), Some(at(2026, 1, 1, 9, 0)));
+
+    let weekdays = Schedule::parse("*/15 8-17 * * mon-fri").unwrap();
+    // 2025-06-14 is a Saturday
+    assert_eq!(weekdays.next_after(&at(2025, 6, 14, 9, 0)), Some(at(2025, 6, 16, 8, 0)));
+    assert_eq!(weekdays.next_after(&at(2025, 6, 16, 8, 7)), Some(at(2025, 6, 16, 8, 15)));
+
+    // Restricting both day fields fires on either
+    let either = Schedule::parse("0 0 13 * 5").unwrap();
+    assert_eq!(either.next_after(&at(2025, 6, 1, 0, 0)), Some(at(2025, 6, 6, 0, 0)));
+    assert_eq!(either.next_after(&at(2025, 6, 12, 0, 0)), Some(at(2025, 6, 13, 0, 0)));
+    // A stepped `*` still counts as unrestricted, so only the 13th matches
+    let stepped = Schedule::parse("0 0 13 * */1").unwrap();
+    assert_eq!(stepped.next_after(&at(2025, 6, 1, 0, 0)), Some(at(2025, 6, 13, 0, 0)));
+
+    assert_eq!(Schedule::parse("@monthly").unwrap(), Schedule::parse("0 0 1 * *").unwrap());
+    assert_eq!(Schedule::parse("0 0 * * 7").unwrap(), Schedule::parse("0 0 * * sun").unwrap());
+    assert_eq!(Schedule::parse("0 0 31 2 *").unwrap().next_after(&at(2025, 1, 1, 0, 0)), None);
+
+    for invalid in ["", "0 9 1 *", "60 * * * *", "0 24 * * *", "0 0 0 * *", "*/0 * * * *", "0 0 * foo *", "5-1 * * * *"] {
+        assert!(Schedule::parse(invalid).is_err(), "{:?} should be rejected", invalid);
+    }
+}
+
+#[test]
+fn missed_runs_are_caught_up_up_to_a_limit() {
+    let daily = Schedule::parse("30 6 * * *").unwrap();
+    // Asleep from the evening of June 1 until midday June 4
+    let runs = daily.missed_runs(&at(2025, 6, 1, 20, 0), &at(2025, 6, 4, 12, 0), MAX_CATCH_UP);
+    assert_eq!(runs, vec![at(2025, 6, 2, 6, 30), at(2025, 6, 3, 6, 30), at(2025, 6, 4, 6, 30)]);
+
+    assert!(daily.missed_runs(&at(2025, 6, 4, 6, 30), &at(2025, 6, 4, 12, 0), MAX_CATCH_UP).is_empty());
+
+    // Closed for two months: only the most recent runs are written
+    let runs = daily.missed_runs(&at(2025, 4, 1, 0, 0), &at(2025, 6, 1, 0, 0), MAX_CATCH_UP);
+    assert_eq!(runs.len(), MAX_CATCH_UP);
+    assert_eq!(runs.last(), Some(&at(2025, 5, 31, 6, 30)));
+}
+
+#[test]
+fn periods_resolve_relative_to_the_run_date() {
+    let run = date(2025, 7, 1); // A Tuesday
+    let range = |period| {
+        let range = ExportPeriod::range(period, run);
+        (range.since, range.until)
+    };
+    assert_eq!(range(ExportPeriod::PreviousDay), (Some(date(2025, 6, 30)), Some(date(2025, 6, 30))));
+    assert_eq!(range(ExportPeriod::PreviousWeek), (Some(date(2025, 6, 23)), Some(date(2025, 6, 29))));
+    assert_eq!(range(ExportPeriod::PreviousMonth), (Some(date(2025, 6, 1)), Some(date(2025, 6, 30))));
+    assert_eq!(range(ExportPeriod::MonthToDate), (Some(date(2025, 7, 1)), Some(date(2025, 7, 1))));
+    assert_eq!(range(ExportPeriod::All), (None, None));
+
+    let march = ExportPeriod::range(ExportPeriod::PreviousMonth, date(2024, 3, 1));
+    assert_eq!((march.since, march.until), (Some(date(2024, 2, 1)), Some(date(2024, 2, 29))));
+}
+
+#[test]
+fn file_name_templates_expand_date_tokens() {
+    let run = at(2025, 7, 1, 9, 0);
+    let name = |template: &str, format| {
+        render_file_name(template, ReportKind::Monthly, format, ExportPeriod::PreviousMonth, &run)
+    };
+    assert_eq!(name("claude-{report}-{period}", ExportFormat::Csv).unwrap(), "claude-monthly-2025-06.csv");
+    assert_eq!(
+        name("invoice_{since}_{until}_{date}T{time}", ExportFormat::Xlsx).unwrap(),
+        "invoice_2025-06-01_2025-06-30_2025-07-01T0900.xlsx"
+    );
+    assert_eq!(name("{year}/{month}", ExportFormat::Csv).unwrap_err(), "Invalid export file name: 2025/07");
+    assert_eq!(name("usage.json", ExportFormat::Json).unwrap(), "usage.json");
+    assert!(name("claude-{nope}", ExportFormat::Csv).is_err());
+    assert!(name("claude-{report", ExportFormat::Csv).is_err());
+    assert!(name("", ExportFormat::Csv).is_err());
+}
+
+#[test]
+fn jobs_write_the_period_into_the_destination() {
+    let fixture = Fixture::new();
+    let entries = load_entries_from(&fixture.config_dirs);
+    let out = tempfile::tempdir().unwrap();
+    let job = ScheduledExport {
+        id: "invoice".to_string(),
+        report: "daily".to_string(),
+        destination: out.path().join("reports").to_string_lossy().to_string(),
+        ..ScheduledExport::default()
+    };
+    scheduler::validate_job(&job).unwrap();
+
+    let path = scheduler::run_job(&job, &at(2025, 7, 1, 9, 0), &entries, CostMode::Auto).unwrap();
+    assert_eq!(path, out.path().join("reports/claude-daily-2025-06.csv"));
+    let csv = std::fs::read_to_string(&path).unwrap();
+    let dates: Vec<&str> = csv.lines().skip(1).map(|line| &line[..10]).collect();
+    assert!(!dates.is_empty());
+    assert!(dates.iter().all(|date| date.starts_with("2025-06")));
+
+    let relative = ScheduledExport { destination: "reports".to_string(), ..job.clone() };
+    assert!(scheduler::validate_job(&relative).is_err());
+    let bad_schedule = ScheduledExport { schedule: "monthly".to_string(), ..job };
+    assert!(scheduler::check_jobs(&[bad_schedule]).unwrap_err().starts_with("Scheduled export invoice:"));
+}
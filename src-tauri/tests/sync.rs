@@ -0,0 +1,177 @@
+mod common;
+
+use common::{at, Fixture};
+use std::fs;
+use tauri_app_lib::archive;
+use tauri_app_lib::core::load_entries_from;
+use tauri_app_lib::pricing::CostMode;
+use tauri_app_lib::reports;
+use tauri_app_lib::settings::SyncSettings;
+use tauri_app_lib::sync::{self, read_header, read_snapshot, snapshot_path, SyncFolder};
+use tauri_app_lib::usage::UsageEntry;
+
+/// Usage of the fixture's first and second config directories, as two machines would see it
+fn machine_entries(fixture: &Fixture) -> (Vec<UsageEntry>, Vec<UsageEntry>) {
+    (
+        load_entries_from(&fixture.config_dirs[..1]),
+        load_entries_from(&fixture.config_dirs[1..]),
+    )
+}
+
+#[test]
+fn machines_read_each_others_snapshots() {
+    let fixture = Fixture::new();
+    let (alpha, beta) = machine_entries(&fixture);
+    let shared = fixture.path().join("Dropbox/claudedeck");
+    fs::create_dir_all(&shared).unwrap();
+    let a = SyncFolder::open(&shared, fixture.path().join("a")).unwrap();
+    let b = SyncFolder::open(&shared, fixture.path().join("b")).unwrap();
+    assert_ne!(a.machine_id(), b.machine_id());
+
+    assert!(a.publish("alpha-box", &alpha, at(2025, 6, 2, 9, 0)).unwrap());
+    assert!(b.publish("beta-box", &beta, at(2025, 6, 2, 9, 0)).unwrap());
+    // Unchanged usage is not written again
+    assert!(!a.publish("alpha-box", &alpha, at(2025, 6, 2, 9, 5)).unwrap());
+
+    let peers = a.pull();
+    assert_eq!(peers.len(), 1);
+    assert_eq!((peers[0].machine.as_str(), peers[0].entries, peers[0].sequence), ("beta-box", beta.len(), Some(1)));
+    assert!(peers[0].error.is_none());
+
+    let synced = a.peer_entries();
+    assert_eq!(synced.len(), beta.len());
+    assert!(synced.iter().all(|entry| entry.machine.as_deref() == Some("beta-box")));
+
+    // Snapshots hold usage only, and merge into this machine's reports under the peer's label
+    let snapshot = read_snapshot(&snapshot_path(&shared, &b.machine_id())).unwrap();
+    assert!(snapshot.entries.iter().all(|entry| entry.machine.is_none()));
+    let mut merged = alpha.clone();
+    assert_eq!(archive::merge(&mut merged, synced), beta.len());
+    let machines = reports::machine_report(&merged, CostMode::Auto);
+    let mut names: Vec<&str> = machines.iter().map(|machine| machine.machine.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["beta-box", reports::LOCAL_MACHINE]);
+
+    // A restarted app continues its machine's sequence
+    drop(a);
+    let a = SyncFolder::open(&shared, fixture.path().join("a")).unwrap();
+    assert!(a.publish("alpha-box", &alpha, at(2025, 6, 2, 10, 0)).unwrap());
+    assert_eq!(read_header(&snapshot_path(&shared, &a.machine_id())).unwrap().sequence, 2);
+    assert_eq!(b.pull()[0].machine, "alpha-box");
+}
+
+#[test]
+fn partial_snapshots_keep_the_last_complete_copy() {
+    let fixture = Fixture::new();
+    let (alpha, beta) = machine_entries(&fixture);
+    let shared = fixture.path().join("shared");
+    fs::create_dir_all(&shared).unwrap();
+    let a = SyncFolder::open(&shared, fixture.path().join("a")).unwrap();
+    let b = SyncFolder::open(&shared, fixture.path().join("b")).unwrap();
+
+    b.publish("beta-box", &beta[..1], at(2025, 6, 2, 9, 0)).unwrap();
+    a.pull();
+    assert_eq!(a.peer_entries().len(), 1);
+
+    // The sync tool has only copied part of the next snapshot
+    b.publish("beta-box", &beta, at(2025, 6, 2, 10, 0)).unwrap();
+    let path = snapshot_path(&shared, &b.machine_id());
+    let complete = fs::read(&path).unwrap();
+    fs::write(&path, &complete[..complete.len() - 12]).unwrap();
+    let peers = a.pull();
+    assert!(peers[0].error.as_deref().unwrap().contains("incomplete"), "{:?}", peers[0].error);
+    assert_eq!((peers[0].sequence, peers[0].entries), (Some(1), 1));
+    assert_eq!(a.peer_entries().len(), 1);
+
+    // Conflicted copies and temporary files are not snapshots
+    fs::write(shared.join("0123abcd (conflicted copy 2025-06-02).claudedeck.ndjson.gz"), &complete).unwrap();
+    fs::write(shared.join(".0123abcd.x1y2.tmp"), b"").unwrap();
+    fs::write(&path, &complete).unwrap();
+    let peers = a.pull();
+    assert_eq!(peers.len(), 1);
+    assert_eq!((peers[0].sequence, peers[0].error.as_deref()), (Some(2), None));
+    assert_eq!(a.peer_entries().len(), beta.len());
+
+    // A machine that leaves the folder is forgotten
+    fs::remove_file(&path).unwrap();
+    assert!(a.pull().is_empty());
+    assert!(a.peer_entries().is_empty());
+    assert!(a.publish("alpha-box", &alpha, at(2025, 6, 2, 11, 0)).unwrap());
+}
+
+#[test]
+fn snapshots_are_ordered_by_sequence_not_clock() {
+    let fixture = Fixture::new();
+    let (_, beta) = machine_entries(&fixture);
+    let shared = fixture.path().join("shared");
+    fs::create_dir_all(&shared).unwrap();
+    let a = SyncFolder::open(&shared, fixture.path().join("a")).unwrap();
+    let b = SyncFolder::open(&shared, fixture.path().join("b")).unwrap();
+    let path = snapshot_path(&shared, &b.machine_id());
+
+    // The peer's clock runs years ahead, then is corrected
+    b.publish("beta-box", &beta[..1], at(2030, 1, 1, 0, 0)).unwrap();
+    let first = fs::read(&path).unwrap();
+    a.pull();
+    b.publish("beta-box", &beta, at(2025, 6, 2, 9, 0)).unwrap();
+    let peers = a.pull();
+    assert_eq!((peers[0].sequence, peers[0].written_at), (Some(2), Some(at(2025, 6, 2, 9, 0))));
+    assert_eq!(a.peer_entries().len(), beta.len());
+
+    // An older snapshot delivered late by the sync tool does not replace a newer one
+    fs::write(&path, first).unwrap();
+    let peers = a.pull();
+    assert_eq!(peers[0].sequence, Some(2));
+    assert_eq!(a.peer_entries().len(), beta.len());
+}
+
+#[test]
+fn copied_machine_ids_are_replaced() {
+    let fixture = Fixture::new();
+    let (alpha, beta) = machine_entries(&fixture);
+    let shared = fixture.path().join("shared");
+    fs::create_dir_all(&shared).unwrap();
+    let a = SyncFolder::open(&shared, fixture.path().join("a")).unwrap();
+    a.publish("alpha-box", &alpha, at(2025, 6, 2, 9, 0)).unwrap();
+
+    // A second machine set up from a copy of the first one's app data
+    fs::create_dir_all(fixture.path().join("c")).unwrap();
+    fs::copy(fixture.path().join("a/identity.json"), fixture.path().join("c/identity.json")).unwrap();
+    let c = SyncFolder::open(&shared, fixture.path().join("c")).unwrap();
+    assert_eq!(c.machine_id(), a.machine_id());
+    c.publish("gamma-box", &beta, at(2025, 6, 2, 9, 1)).unwrap();
+
+    // The first machine sees a newer snapshot it did not write and moves to a new id
+    let shared_id = a.machine_id();
+    assert!(a.publish("alpha-box", &alpha, at(2025, 6, 2, 9, 2)).unwrap());
+    assert_ne!(a.machine_id(), shared_id);
+    let reopened = SyncFolder::open(&shared, fixture.path().join("a")).unwrap();
+    assert_eq!(reopened.machine_id(), a.machine_id());
+
+    let peers = a.pull();
+    assert_eq!(peers.len(), 1);
+    assert_eq!((peers[0].machine.as_str(), peers[0].entries), ("gamma-box", beta.len()));
+    assert_eq!(c.pull()[0].machine, "alpha-box");
+}
+
+#[test]
+fn unusable_sync_settings_are_rejected() {
+    let fixture = Fixture::new();
+    let folder = fixture.path().to_string_lossy().to_string();
+    let settings = |folder: &str, machine_name: &str, interval_secs: u64| SyncSettings {
+        enabled: true,
+        folder: folder.to_string(),
+        machine_name: machine_name.to_string(),
+        interval_secs,
+    };
+
+    assert!(sync::check_settings(&settings(&folder, "", 300)).is_ok());
+    assert!(sync::check_settings(&settings(&folder, "dev-box", 60)).is_ok());
+    assert!(sync::check_settings(&SyncSettings::default()).is_ok());
+    assert!(sync::check_settings(&settings("", "", 300)).is_err());
+    assert!(sync::check_settings(&settings("/", "", 300)).is_err());
+    let missing = fixture.path().join("missing").to_string_lossy().to_string();
+    assert!(sync::check_settings(&settings(&missing, "", 300)).unwrap_err().contains("does not exist"));
+    assert!(sync::check_settings(&settings(&folder, "dev box", 300)).is_err());
+    assert!(sync::check_settings(&settings(&folder, "", 10)).is_err());
+}
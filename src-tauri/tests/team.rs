@@ -0,0 +1,201 @@
+mod common;
+
+use common::{at, Fixture, HAIKU, OPUS, SONNET};
+use std::fs::{self, File};
+use std::io::Cursor;
+use std::path::Path;
+use tauri_app_lib::core::load_entries_from;
+use tauri_app_lib::export::{self, ExportFormat, ReportKind};
+use tauri_app_lib::pricing::CostMode;
+use tauri_app_lib::reports::{self, DateRange, LeaderboardMetric};
+use tauri_app_lib::sync::SyncFolder;
+use tauri_app_lib::team::{self, load_team, snapshot_file_name, team_report, validate_user};
+use tauri_app_lib::usage::UsageEntry;
+
+fn write_member(dir: &Path, user: &str, machine: &str, entries: &[UsageEntry]) {
+    let file = File::create(dir.join(snapshot_file_name(user, machine))).unwrap();
+    team::write_snapshot(file, user, machine, entries, at(2025, 6, 2, 18, 0)).unwrap();
+}
+
+/// alice works on both fixture machines, bob only on the second
+fn team_dir(fixture: &Fixture) -> std::path::PathBuf {
+    let dir = fixture.path().join("team");
+    fs::create_dir_all(&dir).unwrap();
+    let alpha = load_entries_from(&fixture.config_dirs[..1]);
+    let beta = load_entries_from(&fixture.config_dirs[1..]);
+    write_member(&dir, "alice", "laptop", &alpha);
+    write_member(&dir, "alice", "desktop", &beta[..2]);
+    write_member(&dir, "bob", "devbox", &beta);
+    dir
+}
+
+#[test]
+fn member_snapshots_roll_up_per_user() {
+    let fixture = Fixture::new();
+    let dir = team_dir(&fixture);
+    let alpha = load_entries_from(&fixture.config_dirs[..1]);
+    let beta = load_entries_from(&fixture.config_dirs[1..]);
+
+    // alice sent her laptop snapshot twice under another name
+    fs::copy(dir.join(snapshot_file_name("alice", "laptop")), dir.join("alice-again.claudedeck.ndjson.gz")).unwrap();
+    // A folder sync snapshot carries no user, and a partial upload cannot be read
+    let sync_dir = fixture.path().join("sync");
+    fs::create_dir_all(&sync_dir).unwrap();
+    let folder = SyncFolder::open(&dir, &sync_dir).unwrap();
+    folder.publish("laptop", &alpha, at(2025, 6, 2, 18, 0)).unwrap();
+    let bob = fs::read(dir.join(snapshot_file_name("bob", "devbox"))).unwrap();
+    fs::write(dir.join("carol.laptop.claudedeck.ndjson.gz"), &bob[..bob.len() / 2]).unwrap();
+
+    let data = load_team(&dir, false).unwrap();
+    assert_eq!(data.snapshots.len(), 4);
+    assert_eq!(data.skipped.len(), 2);
+    assert!(data.skipped.iter().any(|skipped| skipped.error.contains("no user label")));
+    assert_eq!(data.entries.len(), alpha.len() + 2 + beta.len());
+    assert!(data.entries.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+
+    let report = team_report(data, CostMode::Auto);
+    assert_eq!(report.users.len(), 2);
+    let alice = report.users.iter().find(|user| user.user == "alice").unwrap();
+    assert_eq!(alice.machines, ["desktop", "laptop"]);
+    assert_eq!((alice.projects, alice.sessions), (2, 3));
+    let bob = report.users.iter().find(|user| user.user == "bob").unwrap();
+    assert_eq!((bob.machines.as_slice(), bob.projects), (["devbox".to_string()].as_slice(), 1));
+
+    // The same engine as personal reports: team totals are the members' totals
+    let personal_cost: f64 = [&alpha[..], &beta[..2], &beta[..]]
+        .iter()
+        .flat_map(|entries| reports::model_report(entries, CostMode::Auto))
+        .map(|model| model.totals.total_cost)
+        .sum();
+    assert!((report.totals.total_cost - personal_cost).abs() < 1e-9);
+    assert_eq!(report.projects.len(), 2);
+    let beta_project = report.projects.iter().find(|project| project.project_path.ends_with("beta")).unwrap();
+    assert_eq!(beta_project.sessions, 1);
+    let models: Vec<&str> = report.models.iter().map(|model| model.model.as_str()).collect();
+    assert!(models.contains(&SONNET) && models.contains(&OPUS) && models.contains(&HAIKU));
+
+    let metrics: Vec<LeaderboardMetric> = report.leaderboards.iter().map(|board| board.metric).collect();
+    assert_eq!(metrics, LeaderboardMetric::ALL);
+    let cost = &report.leaderboards[0].rows;
+    assert_eq!(cost[0].user, report.users[0].user);
+    assert_eq!((cost[0].rank, cost[1].rank), (1, 2));
+    let projects = &report.leaderboards[3].rows;
+    assert_eq!((projects[0].user.as_str(), projects[0].value), ("alice", 2.0));
+}
+
+#[test]
+fn anonymized_reports_hide_user_and_machine_labels() {
+    let fixture = Fixture::new();
+    let dir = team_dir(&fixture);
+    fs::write(dir.join("carol.laptop.claudedeck.ndjson.gz"), b"not gzip").unwrap();
+    // An invalid user label ends up in the skip reason
+    let file = File::create(dir.join("erin.claudedeck.ndjson.gz")).unwrap();
+    team::write_snapshot(file, "erin/../x", "laptop", &[], at(2025, 6, 2, 18, 0)).unwrap();
+    // Project directory names spell out the member's home directory
+    let mut shared = load_entries_from(&fixture.config_dirs[1..]);
+    for (index, entry) in shared.iter_mut().enumerate() {
+        entry.project = format!("-Users-dana-src-{}", if index % 2 == 0 { "app" } else { "tool" });
+    }
+    write_member(&dir, "dana", "studio", &shared);
+
+    let data = load_team(&dir, true).unwrap();
+    for entry in &data.entries {
+        let (user, machine) = (entry.user.as_deref().unwrap(), entry.machine.as_deref().unwrap());
+        assert!(user.starts_with("user-") && machine.starts_with("machine-"), "{} on {}", user, machine);
+    }
+    assert_eq!(data.skipped.len(), 2);
+    assert_eq!(data.skipped[0].file, "snapshot-1");
+    assert!(data.skipped.iter().all(|skipped| !skipped.error.contains("erin")));
+    let names = ["alice", "bob", "dana", "home", "dev", "Users"];
+    for entry in &data.entries {
+        assert!(entry.project.starts_with("project-"), "{}", entry.project);
+    }
+
+    // Numbered by first activity: alice's laptop usage comes first
+    let report = team_report(data, CostMode::Auto);
+    let first = report.users.iter().find(|user| user.user == "user-1").unwrap();
+    assert_eq!(first.machines, ["machine-1", "machine-2"]);
+    // Machine aliases are numbered across the team, not per member
+    let others: Vec<&String> = report
+        .users
+        .iter()
+        .filter(|user| user.user != "user-1")
+        .flat_map(|user| &user.machines)
+        .collect();
+    assert_eq!(others.len(), 2);
+    assert!(others.iter().all(|machine| !first.machines.contains(machine)), "{:?}", others);
+    assert_eq!(first.projects, 2);
+    // The fixture's two project directories and dana's two
+    assert_eq!(report.projects.len(), 4);
+    for project in &report.projects {
+        assert!(!names.iter().any(|name| project.project_path.contains(name)), "{}", project.project_path);
+    }
+
+    let mut csv = Cursor::new(Vec::new());
+    let entries = load_team(&dir, true).unwrap().entries;
+    export::write_report(
+        &mut csv,
+        ReportKind::Users,
+        ExportFormat::Csv,
+        &DateRange::default(),
+        &entries,
+        CostMode::Auto,
+        at(2025, 7, 1, 0, 0),
+    )
+    .unwrap();
+    let csv = String::from_utf8(csv.into_inner()).unwrap();
+    assert!(csv.starts_with("User,Machines,Models,"), "{}", csv);
+    assert!(!csv.contains("alice") && !csv.contains("laptop"));
+    assert_eq!(csv.lines().count(), 4);
+    for report in [ReportKind::Projects, ReportKind::Session] {
+        let mut csv = Cursor::new(Vec::new());
+        export::write_report(
+            &mut csv,
+            report,
+            ExportFormat::Csv,
+            &DateRange::default(),
+            &entries,
+            CostMode::Auto,
+            at(2025, 7, 1, 0, 0),
+        )
+        .unwrap();
+        let csv = String::from_utf8(csv.into_inner()).unwrap();
+        assert!(!names.iter().any(|name| csv.contains(name)), "{}", csv);
+    }
+}
+
+#[test]
+fn leaderboard_ties_share_a_rank() {
+    let fixture = Fixture::new();
+    let dir = fixture.path().join("team");
+    fs::create_dir_all(&dir).unwrap();
+    let beta = load_entries_from(&fixture.config_dirs[1..]);
+    write_member(&dir, "bob", "devbox", &beta);
+    write_member(&dir, "dana", "devbox", &beta);
+    write_member(&dir, "erin", "devbox", &beta[..1]);
+
+    let users = reports::user_report(&load_team(&dir, false).unwrap().entries, CostMode::Auto);
+    let rows = reports::leaderboard(&users, LeaderboardMetric::Tokens, 10);
+    let ranks: Vec<(usize, &str)> = rows.iter().map(|row| (row.rank, row.user.as_str())).collect();
+    assert_eq!(ranks, [(1, "bob"), (1, "dana"), (3, "erin")]);
+    assert_eq!(reports::leaderboard(&users, LeaderboardMetric::Cost, 2).len(), 2);
+}
+
+#[test]
+fn unusable_team_settings_are_rejected() {
+    assert_eq!(validate_user(" alice ").unwrap(), "alice");
+    assert!(validate_user("").unwrap_err().contains("User label"));
+    assert!(validate_user("alice smith").unwrap_err().contains("user label"));
+    assert!(validate_user("local").is_err());
+
+    let settings = |directory: &str, user_name: &str| tauri_app_lib::settings::TeamSettings {
+        directory: directory.to_string(),
+        anonymize: false,
+        user_name: user_name.to_string(),
+    };
+    assert!(team::check_settings(&settings("", "")).is_ok());
+    assert!(team::check_settings(&settings("/srv/team", "alice")).is_ok());
+    assert!(team::check_settings(&settings("/", "")).is_err());
+    assert!(team::check_settings(&settings("", "../alice")).is_err());
+    assert!(load_team(Path::new("/nonexistent/team"), false).is_err());
+}
@@ -8,6 +8,7 @@ import { MonthlyReport } from './views/MonthlyReport';
 import { SessionReport } from './views/SessionReport';
 import { BlocksReport } from './views/BlocksReport';
 import { Settings } from './views/Settings';
+import { WatcherDiagnostic } from './components/WatcherDiagnostic';
 // import { DebugConsole } from './components/DebugConsole';
 
 type View = 'dashboard' | 'daily' | 'monthly' | 'session' | 'blocks' | 'settings';
@@ -25,6 +26,8 @@ function App() {
   // Store hooks - always call these
   const isLoadingData = useUsageDataStore(state => state.isLoading);
   const isLoadingSettings = useSettingsStore(state => state.isLoading);
+  const loadProgress = useUsageDataStore(state => state.loadProgress);
+  const cancelLoad = useUsageDataStore(state => state.cancelLoad);
   const dataError = useUsageDataStore(state => state.error);
   const settingsError = useSettingsStore(state => state.error);
   const theme = useSettingsStore(state => state.settings.theme);
@@ -137,6 +140,27 @@ function App() {
           <p className="text-muted-foreground">
             {isLoadingSettings ? 'Loading settings...' : 'Loading usage data...'}
           </p>
+          {!isLoadingSettings && loadProgress && (
+            <div className="mt-4 w-64 mx-auto">
+              <div className="h-2 rounded bg-muted overflow-hidden">
+                <div
+                  className="h-full bg-primary transition-all"
+                  style={{ width: `${loadProgress.percent}%` }}
+                />
+              </div>
+              <p className="mt-2 text-xs text-muted-foreground">
+                {loadProgress.phase === 'discovering'
+                  ? `Found ${loadProgress.filesDiscovered} files...`
+                  : `${loadProgress.filesParsed} / ${loadProgress.filesDiscovered} files (${(loadProgress.bytesParsed / 1048576).toFixed(1)} MB)`}
+              </p>
+              <button
+                onClick={() => cancelLoad()}
+                className="mt-3 px-3 py-1 text-sm border rounded hover:bg-muted"
+              >
+                Cancel
+              </button>
+            </div>
+          )}
         </div>
       </div>
     );
@@ -167,6 +191,7 @@ function App() {
     return (
       <div className="min-h-screen bg-background">
         <Header />
+        <WatcherDiagnostic />
         <main className="container mx-auto p-4">
           {renderView()}
         </main>
@@ -1,4 +1,5 @@
 import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 
 interface LogEntry {
   type: 'log' | 'error' | 'warn';
@@ -6,8 +7,19 @@ interface LogEntry {
   timestamp: Date;
 }
 
+// Shape returned by the `get_logs` command
+interface BackendLogEntry {
+  timestamp: string;
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+  target: string;
+  message: string;
+}
+
+const BACKEND_POLL_INTERVAL = 2000;
+
 export function DebugConsole() {
   const [logs, setLogs] = useState<LogEntry[]>([]);
+  const [backendLogs, setBackendLogs] = useState<LogEntry[]>([]);
   const [renderCount, setRenderCount] = useState(0);
 
   useEffect(() => {
@@ -50,13 +62,37 @@ export function DebugConsole() {
     };
   }, []);
 
+  useEffect(() => {
+    // Poll the Rust log buffer so backend messages show up alongside frontend ones
+    const fetchBackendLogs = async () => {
+      try {
+        const entries = await invoke<BackendLogEntry[]>('get_logs', { limit: 50 });
+        setBackendLogs(entries.map(entry => ({
+          type: entry.level === 'ERROR' ? 'error' : entry.level === 'WARN' ? 'warn' : 'log',
+          message: `[${entry.target}] ${entry.message}`,
+          timestamp: new Date(entry.timestamp),
+        })));
+      } catch {
+        // Backend unavailable (e.g. running in a plain browser)
+      }
+    };
+
+    fetchBackendLogs();
+    const interval = setInterval(fetchBackendLogs, BACKEND_POLL_INTERVAL);
+    return () => clearInterval(interval);
+  }, []);
+
+  const allLogs = [...logs, ...backendLogs]
+    .sort((a, b) => a.timestamp.getTime() - b.timestamp.getTime())
+    .slice(-100);
+
   return (
     <div className="fixed bottom-0 right-0 w-96 h-64 bg-background border rounded-tl-lg shadow-lg overflow-hidden">
       <div className="bg-muted px-2 py-1 text-xs font-mono">
         Debug Console (Renders: {renderCount})
       </div>
       <div className="overflow-y-auto h-full p-2 text-xs font-mono">
-        {logs.map((log, i) => (
+        {allLogs.map((log, i) => (
           <div 
             key={i} 
             className={`mb-1 ${
@@ -0,0 +1,65 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+interface WatchLimit {
+  maxUserWatches: number | null;
+  recentProjects: number;
+}
+
+interface WatcherHealth {
+  state: 'watching' | 'degraded' | 'waiting' | 'stopped';
+  watchLimit?: WatchLimit;
+}
+
+// Explains degraded file watching when the Linux inotify watch limit was reached
+export function WatcherDiagnostic() {
+  const [watchLimit, setWatchLimit] = useState<WatchLimit | null>(null);
+  const [dismissed, setDismissed] = useState(false);
+
+  useEffect(() => {
+    invoke<WatcherHealth>('get_watcher_health')
+      .then(health => setWatchLimit(health.watchLimit ?? null))
+      .catch(error => console.error('[WatcherDiagnostic] Failed to get watcher health:', error));
+
+    const unlisten = listen<WatcherHealth>('watcher-health', (event) => {
+      setWatchLimit(event.payload.watchLimit ?? null);
+    });
+
+    return () => {
+      unlisten.then(fn => fn()).catch(console.error);
+    };
+  }, []);
+
+  if (!watchLimit || dismissed) return null;
+
+  const limit = watchLimit.maxUserWatches?.toLocaleString() ?? 'unknown';
+
+  return (
+    <div className="container mx-auto px-4 pt-4">
+      <div className="rounded-lg border border-yellow-500/50 bg-yellow-500/10 p-4 text-sm">
+        <div className="flex items-start justify-between gap-4">
+          <div>
+            <p className="font-medium">File watching is limited</p>
+            <p className="mt-1 text-muted-foreground">
+              The inotify watch limit (fs.inotify.max_user_watches = {limit}) was reached.
+              {' '}{watchLimit.recentProjects} recently active projects update immediately; other projects
+              are polled and may take a few seconds to refresh.
+            </p>
+            <p className="mt-2 text-muted-foreground">To raise the limit:</p>
+            <pre className="mt-1 overflow-x-auto rounded bg-muted p-2 text-xs">
+              echo fs.inotify.max_user_watches=524288 | sudo tee /etc/sysctl.d/60-inotify.conf && sudo sysctl --system
+            </pre>
+          </div>
+          <button
+            onClick={() => setDismissed(true)}
+            className="text-muted-foreground hover:text-foreground"
+            aria-label="Dismiss"
+          >
+            ✕
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
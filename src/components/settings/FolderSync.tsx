@@ -0,0 +1,139 @@
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { FolderSync as FolderSyncIcon, RefreshCw } from 'lucide-react';
+import { useEffect, useState } from 'react';
+import { SyncStatus } from '@/lib/types';
+import { AppSettings, SyncSettings } from '@/store/settingsStore';
+
+interface FolderSyncProps {
+  settings: AppSettings;
+  onUpdateSettings: (partial: Partial<AppSettings>) => void;
+}
+
+const DEFAULT_SYNC: SyncSettings = {
+  enabled: false,
+  folder: '',
+  machineName: '',
+  intervalSecs: 300,
+};
+
+const inputClass =
+  'h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm focus:outline-none focus:ring-2 focus:ring-ring focus:ring-offset-2';
+
+export function FolderSync({ settings, onUpdateSettings }: FolderSyncProps) {
+  const sync = { ...DEFAULT_SYNC, ...settings.sync };
+  const [status, setStatus] = useState<SyncStatus | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [syncing, setSyncing] = useState(false);
+
+  useEffect(() => {
+    invoke<SyncStatus | null>('get_sync_status')
+      .then(setStatus)
+      .catch(e => console.error('[FolderSync] Failed to get sync status:', e));
+    const unlisten = listen<SyncStatus>('sync-updated', event => setStatus(event.payload));
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  const update = (partial: Partial<SyncSettings>) => {
+    onUpdateSettings({ sync: { ...sync, ...partial } });
+  };
+
+  const syncNow = async () => {
+    setError(null);
+    setSyncing(true);
+    try {
+      setStatus(await invoke<SyncStatus>('sync_now'));
+    } catch (e) {
+      setError(typeof e === 'string' ? e : 'Sync failed');
+    } finally {
+      setSyncing(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <FolderSyncIcon className="h-5 w-5" />
+          Folder Sync
+        </CardTitle>
+        <CardDescription>
+          Share usage with your other machines through a folder synced by Syncthing, Dropbox or a network drive. Each
+          machine writes a usage-only snapshot of its own and reads the others'. No message text leaves this machine.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="flex items-center justify-between">
+          <span className="text-sm font-medium">Sync usage through a shared folder</span>
+          <Button variant={sync.enabled ? 'default' : 'outline'} size="sm" onClick={() => update({ enabled: !sync.enabled })}>
+            {sync.enabled ? 'Enabled' : 'Disabled'}
+          </Button>
+        </div>
+
+        <div>
+          <label className="text-sm font-medium block mb-2">Shared folder</label>
+          <input
+            type="text"
+            value={sync.folder}
+            onChange={(e) => update({ folder: e.target.value })}
+            placeholder="/home/me/Dropbox/claudedeck"
+            className={inputClass}
+          />
+        </div>
+
+        <div className="grid grid-cols-2 gap-3">
+          <div>
+            <label className="text-sm font-medium block mb-2">Machine name</label>
+            <input
+              type="text"
+              value={sync.machineName}
+              onChange={(e) => update({ machineName: e.target.value })}
+              placeholder="Host name"
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <label className="text-sm font-medium block mb-2">Interval (seconds)</label>
+            <input
+              type="number"
+              min={60}
+              value={sync.intervalSecs}
+              onChange={(e) => update({ intervalSecs: Number(e.target.value) })}
+              className={inputClass}
+            />
+          </div>
+        </div>
+
+        {status && (
+          <div className="space-y-2">
+            <p className="text-xs text-muted-foreground">
+              Last synced {new Date(status.lastSync).toLocaleString()} as {status.machine} (
+              {status.publishedEntries.toLocaleString()} records)
+            </p>
+            {status.error && <p className="text-sm text-destructive">{status.error}</p>}
+            {status.peers.map(peer => (
+              <div key={peer.machineId} className="rounded-md border px-4 py-2">
+                <p className="text-sm font-medium">{peer.machine}</p>
+                <p className="text-xs text-muted-foreground">
+                  {peer.entries.toLocaleString()} records
+                  {peer.writtenAt && `, written ${new Date(peer.writtenAt).toLocaleString()} by its clock`}
+                </p>
+                {peer.error && <p className="text-xs text-destructive">{peer.error}</p>}
+              </div>
+            ))}
+          </div>
+        )}
+
+        <Button variant="outline" size="sm" onClick={syncNow} disabled={!settings.sync?.enabled || syncing}>
+          <RefreshCw className={`h-4 w-4 mr-2 ${syncing ? 'animate-spin' : ''}`} />
+          Sync now
+        </Button>
+        {error && <p className="text-sm text-destructive">{error}</p>}
+      </CardContent>
+    </Card>
+  );
+}
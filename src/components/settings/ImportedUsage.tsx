@@ -0,0 +1,102 @@
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { invoke } from '@tauri-apps/api/core';
+import { Laptop, Upload, X } from 'lucide-react';
+import { useEffect, useState } from 'react';
+import { ImportedMachine, ImportSummary } from '@/lib/types';
+import { useUsageDataStore } from '@/store';
+
+const inputClass =
+  'h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm focus:outline-none focus:ring-2 focus:ring-ring focus:ring-offset-2';
+
+export function ImportedUsage() {
+  const loadUsageData = useUsageDataStore(state => state.loadUsageData);
+  const [machines, setMachines] = useState<ImportedMachine[]>([]);
+  const [machine, setMachine] = useState('');
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = () => {
+    invoke<ImportedMachine[]>('get_imports')
+      .then(setMachines)
+      .catch(e => console.error('[ImportedUsage] Failed to list imports:', e));
+  };
+
+  useEffect(refresh, []);
+
+  const importFile = async () => {
+    setMessage(null);
+    setError(null);
+    try {
+      // The backend shows the open dialog
+      const summary = await invoke<ImportSummary | null>('import_usage', { machine });
+      if (!summary) return;
+      setMessage(
+        `Imported ${summary.added} of ${summary.records} records for ${summary.machine}` +
+          (summary.duplicates > 0 ? ` (${summary.duplicates} already imported)` : ''),
+      );
+      refresh();
+      loadUsageData();
+    } catch (e) {
+      setError(typeof e === 'string' ? e : 'Import failed');
+    }
+  };
+
+  const removeMachine = async (label: string) => {
+    try {
+      await invoke<boolean>('remove_import', { machine: label });
+      refresh();
+      loadUsageData();
+    } catch (e) {
+      setError(typeof e === 'string' ? e : 'Failed to remove imported usage');
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Laptop className="h-5 w-5" />
+          Other Machines
+        </CardTitle>
+        <CardDescription>
+          Import usage from another machine: the output of `ccusage daily --json` (or monthly, session, blocks) or a
+          JSON or NDJSON export of the entries report. Records already seen are skipped by message ID.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {machines.map(imported => (
+          <div key={imported.machine} className="flex items-center justify-between rounded-md border px-4 py-2">
+            <div>
+              <p className="text-sm font-medium">{imported.machine}</p>
+              <p className="text-xs text-muted-foreground">
+                {imported.entries.toLocaleString()} records
+                {imported.first && imported.last &&
+                  `, ${imported.first.slice(0, 10)} to ${imported.last.slice(0, 10)}`}
+              </p>
+            </div>
+            <Button variant="outline" size="sm" onClick={() => removeMachine(imported.machine)}>
+              <X className="h-4 w-4" />
+            </Button>
+          </div>
+        ))}
+
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={machine}
+            onChange={(e) => setMachine(e.target.value)}
+            placeholder="Machine label, e.g. devbox"
+            className={inputClass}
+          />
+          <Button variant="outline" onClick={importFile} disabled={!machine.trim()}>
+            <Upload className="h-4 w-4 mr-2" />
+            Import file
+          </Button>
+        </div>
+        {message && <p className="text-sm text-muted-foreground">{message}</p>}
+        {error && <p className="text-sm text-destructive">{error}</p>}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,185 @@
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import {
+  AppSettings,
+  EXPORT_FORMATS,
+  ExportPeriod,
+  ExportReport,
+  ScheduledExport,
+} from '@/store/settingsStore';
+import { CalendarClock, Plus, X } from 'lucide-react';
+
+interface ScheduledExportsProps {
+  settings: AppSettings;
+  onUpdateSettings: (partial: Partial<AppSettings>) => void;
+}
+
+const REPORTS: { value: ExportReport; label: string }[] = [
+  { value: 'daily', label: 'Daily' },
+  { value: 'monthly', label: 'Monthly' },
+  { value: 'session', label: 'Sessions' },
+  { value: 'blocks', label: 'Blocks' },
+  { value: 'models', label: 'Models' },
+  { value: 'projects', label: 'Projects' },
+  { value: 'machines', label: 'Machines' },
+  { value: 'cache', label: 'Cache efficiency' },
+  { value: 'entries', label: 'All entries' },
+];
+
+const PERIODS: { value: ExportPeriod; label: string }[] = [
+  { value: 'previousDay', label: 'Previous day' },
+  { value: 'previousWeek', label: 'Previous week' },
+  { value: 'previousMonth', label: 'Previous month' },
+  { value: 'monthToDate', label: 'Month to date' },
+  { value: 'all', label: 'All dates' },
+];
+
+const inputClass =
+  'h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm focus:outline-none focus:ring-2 focus:ring-ring focus:ring-offset-2';
+
+function newJob(): ScheduledExport {
+  return {
+    id: `export-${Date.now().toString(36)}`,
+    enabled: true,
+    report: 'monthly',
+    format: 'csv',
+    period: 'previousMonth',
+    schedule: '0 9 1 * *',
+    destination: '',
+    fileName: 'claude-{report}-{period}',
+  };
+}
+
+export function ScheduledExports({ settings, onUpdateSettings }: ScheduledExportsProps) {
+  const jobs = settings.scheduledExports ?? [];
+
+  const updateJob = (index: number, partial: Partial<ScheduledExport>) => {
+    onUpdateSettings({
+      scheduledExports: jobs.map((job, i) => (i === index ? { ...job, ...partial } : job)),
+    });
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <CalendarClock className="h-5 w-5" />
+          Scheduled Exports
+        </CardTitle>
+        <CardDescription>
+          Write reports to a folder on a schedule while ClaudeDeck is running. Runs missed while the app was closed or
+          asleep are caught up on the next start.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {jobs.map((job, index) => (
+          <div key={job.id} className="space-y-3 rounded-md border p-4">
+            <div className="flex items-center justify-between">
+              <Button
+                variant={job.enabled ? 'default' : 'outline'}
+                size="sm"
+                onClick={() => updateJob(index, { enabled: !job.enabled })}
+              >
+                {job.enabled ? 'Enabled' : 'Disabled'}
+              </Button>
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => onUpdateSettings({ scheduledExports: jobs.filter((_, i) => i !== index) })}
+              >
+                <X className="h-4 w-4" />
+              </Button>
+            </div>
+
+            <div className="grid grid-cols-3 gap-3">
+              <div>
+                <label className="text-sm font-medium block mb-2">Report</label>
+                <select
+                  value={job.report}
+                  onChange={(e) => updateJob(index, { report: e.target.value as ExportReport })}
+                  className={inputClass}
+                >
+                  {REPORTS.map(({ value, label }) => (
+                    <option key={value} value={value}>{label}</option>
+                  ))}
+                </select>
+              </div>
+              <div>
+                <label className="text-sm font-medium block mb-2">Format</label>
+                <select
+                  value={job.format}
+                  onChange={(e) => updateJob(index, { format: e.target.value as ScheduledExport['format'] })}
+                  className={inputClass}
+                >
+                  {EXPORT_FORMATS.map(({ value, label }) => (
+                    <option key={value} value={value}>{label}</option>
+                  ))}
+                </select>
+              </div>
+              <div>
+                <label className="text-sm font-medium block mb-2">Period</label>
+                <select
+                  value={job.period}
+                  onChange={(e) => updateJob(index, { period: e.target.value as ExportPeriod })}
+                  className={inputClass}
+                >
+                  {PERIODS.map(({ value, label }) => (
+                    <option key={value} value={value}>{label}</option>
+                  ))}
+                </select>
+              </div>
+            </div>
+
+            <div>
+              <label className="text-sm font-medium block mb-2">Schedule</label>
+              <input
+                type="text"
+                value={job.schedule}
+                onChange={(e) => updateJob(index, { schedule: e.target.value })}
+                placeholder="0 9 1 * *"
+                className={inputClass}
+              />
+              <p className="text-xs text-muted-foreground mt-1">
+                Cron expression in local time: minute hour day month weekday. "0 9 1 * *" runs at 09:00 on the 1st.
+              </p>
+            </div>
+
+            <div>
+              <label className="text-sm font-medium block mb-2">Destination folder</label>
+              <input
+                type="text"
+                value={job.destination}
+                onChange={(e) => updateJob(index, { destination: e.target.value })}
+                placeholder="/path/to/invoices"
+                className={inputClass}
+              />
+            </div>
+
+            <div>
+              <label className="text-sm font-medium block mb-2">File name</label>
+              <input
+                type="text"
+                value={job.fileName}
+                onChange={(e) => updateJob(index, { fileName: e.target.value })}
+                className={inputClass}
+              />
+              <p className="text-xs text-muted-foreground mt-1">
+                Tokens: {'{report}'}, {'{period}'}, {'{since}'}, {'{until}'}, {'{date}'}, {'{time}'}, {'{year}'},{' '}
+                {'{month}'}, {'{day}'}. The extension is added automatically.
+              </p>
+            </div>
+          </div>
+        ))}
+
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={() => onUpdateSettings({ scheduledExports: [...jobs, newJob()] })}
+        >
+          <Plus className="h-4 w-4 mr-2" />
+          Add scheduled export
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
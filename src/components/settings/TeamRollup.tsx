@@ -0,0 +1,179 @@
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { invoke } from '@tauri-apps/api/core';
+import { Download, RefreshCw, Trophy, Users } from 'lucide-react';
+import { useState } from 'react';
+import { LeaderboardMetric, TeamReport } from '@/lib/types';
+import { formatCurrency, formatTokenCount } from '@/lib/formatters';
+import { AppSettings, TeamSettings } from '@/store/settingsStore';
+
+interface TeamRollupProps {
+  settings: AppSettings;
+  onUpdateSettings: (partial: Partial<AppSettings>) => void;
+}
+
+const DEFAULT_TEAM: TeamSettings = {
+  directory: '',
+  anonymize: false,
+  userName: '',
+};
+
+const METRICS: { value: LeaderboardMetric; label: string }[] = [
+  { value: 'cost', label: 'Cost' },
+  { value: 'tokens', label: 'Tokens' },
+  { value: 'sessions', label: 'Sessions' },
+  { value: 'projects', label: 'Projects' },
+];
+
+const inputClass =
+  'h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm focus:outline-none focus:ring-2 focus:ring-ring focus:ring-offset-2';
+
+export function TeamRollup({ settings, onUpdateSettings }: TeamRollupProps) {
+  const team = { ...DEFAULT_TEAM, ...settings.team };
+  const [report, setReport] = useState<TeamReport | null>(null);
+  const [metric, setMetric] = useState<LeaderboardMetric>('cost');
+  const [loading, setLoading] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const update = (partial: Partial<TeamSettings>) => {
+    onUpdateSettings({ team: { ...team, ...partial } });
+  };
+
+  const run = async (action: () => Promise<void>, failure: string) => {
+    setMessage(null);
+    setError(null);
+    try {
+      await action();
+    } catch (e) {
+      setError(typeof e === 'string' ? e : failure);
+    }
+  };
+
+  const loadReport = () =>
+    run(async () => {
+      setLoading(true);
+      try {
+        setReport(await invoke<TeamReport>('get_team_report'));
+      } finally {
+        setLoading(false);
+      }
+    }, 'Failed to load team usage');
+
+  const exportSnapshot = () =>
+    run(async () => {
+      // The backend shows the save dialog
+      const path = await invoke<string | null>('export_team_snapshot');
+      if (path) setMessage(`Saved your snapshot to ${path}. Add it to the team directory.`);
+    }, 'Failed to write team snapshot');
+
+  const exportUsers = () =>
+    run(async () => {
+      const path = await invoke<string | null>('export_report', { report: 'users', team: true });
+      if (path) setMessage(`Exported the users report to ${path}`);
+    }, 'Export failed');
+
+  const leaderboard = report?.leaderboards.find(board => board.metric === metric);
+  const formatValue = (value: number) =>
+    metric === 'cost' ? formatCurrency(value) : metric === 'tokens' ? formatTokenCount(value) : value.toLocaleString();
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Users className="h-5 w-5" />
+          Team Roll-up
+        </CardTitle>
+        <CardDescription>
+          Each member exports a usage-only snapshot and drops it into a shared team directory. Reports then cover the
+          whole team per user, project and model.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="grid grid-cols-2 gap-3">
+          <div>
+            <label className="text-sm font-medium block mb-2">Your name in team reports</label>
+            <input
+              type="text"
+              value={team.userName}
+              onChange={(e) => update({ userName: e.target.value })}
+              placeholder="alice"
+              className={inputClass}
+            />
+          </div>
+          <div className="flex items-end">
+            <Button variant="outline" onClick={exportSnapshot} disabled={!settings.team?.userName?.trim()}>
+              <Download className="h-4 w-4 mr-2" />
+              Export my snapshot
+            </Button>
+          </div>
+        </div>
+
+        <div>
+          <label className="text-sm font-medium block mb-2">Team directory</label>
+          <input
+            type="text"
+            value={team.directory}
+            onChange={(e) => update({ directory: e.target.value })}
+            placeholder="/shared/claude-team"
+            className={inputClass}
+          />
+        </div>
+
+        <div className="flex items-center justify-between">
+          <span className="text-sm font-medium">Anonymize users and machines</span>
+          <Button variant={team.anonymize ? 'default' : 'outline'} size="sm" onClick={() => update({ anonymize: !team.anonymize })}>
+            {team.anonymize ? 'On' : 'Off'}
+          </Button>
+        </div>
+
+        <div className="flex gap-2">
+          <Button variant="outline" size="sm" onClick={loadReport} disabled={!settings.team?.directory?.trim() || loading}>
+            <RefreshCw className={`h-4 w-4 mr-2 ${loading ? 'animate-spin' : ''}`} />
+            Load team report
+          </Button>
+          <Button variant="outline" size="sm" onClick={exportUsers} disabled={!report}>
+            <Download className="h-4 w-4 mr-2" />
+            Export users report
+          </Button>
+        </div>
+
+        {report && (
+          <div className="space-y-3">
+            <p className="text-sm text-muted-foreground">
+              {report.users.length} users, {report.snapshots.length} snapshots, {formatCurrency(report.totals.totalCost)}{' '}
+              and {formatTokenCount(report.totals.totalTokens)} tokens in total
+            </p>
+
+            <div className="flex items-center gap-2">
+              <Trophy className="h-4 w-4" />
+              {METRICS.map(({ value, label }) => (
+                <Button key={value} variant={metric === value ? 'default' : 'outline'} size="sm" onClick={() => setMetric(value)}>
+                  {label}
+                </Button>
+              ))}
+            </div>
+            {leaderboard?.rows.map(row => (
+              <div key={row.user} className="flex items-center justify-between rounded-md border px-4 py-2 text-sm">
+                <span>
+                  <span className="text-muted-foreground mr-2">#{row.rank}</span>
+                  {row.user}
+                </span>
+                <span className="font-medium">{formatValue(row.value)}</span>
+              </div>
+            ))}
+
+            {report.skipped.map(skipped => (
+              <p key={skipped.file} className="text-xs text-destructive">
+                Skipped {skipped.file}: {skipped.error}
+              </p>
+            ))}
+          </div>
+        )}
+
+        {message && <p className="text-sm text-muted-foreground">{message}</p>}
+        {error && <p className="text-sm text-destructive">{error}</p>}
+      </CardContent>
+    </Card>
+  );
+}
@@ -1,53 +1,33 @@
 import { Button } from "@/components/ui/button"
 import { useSettingsStore } from "@/store"
 import { invoke } from "@tauri-apps/api/core"
+import type { ExportFormat, ExportReport } from "@/store/settingsStore"
 
 interface ExportButtonProps {
-  data: any[]
-  filename: string
-  format?: 'csv' | 'json'
+  report: ExportReport
+  format?: ExportFormat
+  since?: string
+  until?: string
 }
 
-export function ExportButton({ data, filename, format }: ExportButtonProps) {
+// Rendered by the backend exporter so every format uses the Rust report engine
+export function ExportButton({ report, format, since, until }: ExportButtonProps) {
   const defaultFormat = useSettingsStore(state => state.settings.defaultExportFormat)
+  const costMode = useSettingsStore(state => state.settings.costMode)
   const exportFormat = format || defaultFormat
 
   const handleExport = async () => {
     try {
-      let content: string
-      let exportFilename: string
-
-      if (exportFormat === 'csv') {
-        // Convert data to CSV
-        if (data.length === 0) {
-          content = ''
-        } else {
-          const headers = Object.keys(data[0])
-          const csvHeaders = headers.join(',')
-          const csvRows = data.map(row => 
-            headers.map(header => {
-              const value = row[header]
-              // Handle values that might contain commas
-              if (typeof value === 'string' && value.includes(',')) {
-                return `"${value}"`
-              }
-              return value ?? ''
-            }).join(',')
-          )
-          content = [csvHeaders, ...csvRows].join('\n')
-        }
-        exportFilename = `${filename}.csv`
-      } else {
-        // Export as JSON
-        content = JSON.stringify(data, null, 2)
-        exportFilename = `${filename}.json`
-      }
-
-      await invoke('export_data', {
+      const path = await invoke<string | null>('export_report', {
+        report,
         format: exportFormat,
-        content,
-        defaultFilename: exportFilename
+        since,
+        until,
+        mode: costMode,
       })
+      if (path) {
+        console.log('[ExportButton] Exported', report, 'report to', path)
+      }
     } catch (error) {
       console.error('Export failed:', error)
     }
@@ -62,4 +42,4 @@ export function ExportButton({ data, filename, format }: ExportButtonProps) {
       Export {exportFormat.toUpperCase()}
     </Button>
   )
-}
\ No newline at end of file
+}
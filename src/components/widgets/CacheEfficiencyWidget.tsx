@@ -0,0 +1,76 @@
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card"
+import { useSettingsStore, useUsageDataStore } from "@/store"
+import { invoke } from "@tauri-apps/api/core"
+import { Database } from "lucide-react"
+import { formatCurrency, formatTokenCount } from "@/lib/formatters"
+import { CacheReport } from "@/lib/types"
+import { useEffect, useState } from "react"
+
+// Most poorly reused sessions listed
+const MAX_SESSIONS = 3
+
+// Prompt cache reuse and what it saved compared to uncached pricing
+export function CacheEfficiencyWidget() {
+  const lastLoadTime = useUsageDataStore(state => state.lastLoadTime)
+  const costMode = useSettingsStore(state => state.settings.costMode)
+  const [report, setReport] = useState<CacheReport | null>(null)
+
+  useEffect(() => {
+    invoke<CacheReport>('get_cache_report', { mode: costMode })
+      .then(setReport)
+      .catch(error => console.error('[CacheEfficiencyWidget] Failed to load cache report:', error))
+  }, [lastLoadTime, costMode])
+
+  if (!report || report.totals.cacheCreationTokens + report.totals.cacheReadTokens === 0) {
+    return null
+  }
+
+  const { totals } = report
+  const projectName = (path: string) => path.split(/[\\/]/).filter(Boolean).pop() ?? path
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between space-y-0 pb-2">
+        <div>
+          <CardTitle>Cache Efficiency</CardTitle>
+          <CardDescription>Prompt caching compared to uncached pricing</CardDescription>
+        </div>
+        <Database className="h-4 w-4 text-muted-foreground" />
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="grid grid-cols-3 gap-3 text-sm">
+          <div>
+            <p className="text-muted-foreground">Read ratio</p>
+            <p className="text-lg font-bold">{(totals.cacheReadRatio * 100).toFixed(1)}%</p>
+          </div>
+          <div>
+            <p className="text-muted-foreground">Reads per write</p>
+            <p className="text-lg font-bold">{totals.writeAmortization.toFixed(1)}×</p>
+          </div>
+          <div>
+            <p className="text-muted-foreground">Saved</p>
+            <p className="text-lg font-bold">{formatCurrency(totals.estimatedSavings)}</p>
+          </div>
+        </div>
+        <p className="text-xs text-muted-foreground">
+          {formatCurrency(totals.calculatedCost)} at list prices instead of an estimated {formatCurrency(totals.uncachedCost)} without
+          caching
+        </p>
+
+        {report.poorReuseSessions.length > 0 && (
+          <div className="space-y-2">
+            <p className="text-sm font-medium">Sessions with poor cache reuse</p>
+            {report.poorReuseSessions.slice(0, MAX_SESSIONS).map(session => (
+              <div key={`${session.projectPath}/${session.sessionId}`} className="flex items-center justify-between text-xs">
+                <span className="truncate">{projectName(session.projectPath)}</span>
+                <span className="text-muted-foreground">
+                  {formatTokenCount(session.cacheCreationTokens)} written · {session.writeAmortization.toFixed(1)}× read
+                </span>
+              </div>
+            ))}
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  )
+}
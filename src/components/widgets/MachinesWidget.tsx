@@ -0,0 +1,61 @@
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card"
+import { useSettingsStore, useUsageDataStore } from "@/store"
+import { invoke } from "@tauri-apps/api/core"
+import { Monitor } from "lucide-react"
+import { formatCurrency, formatTokenCount } from "@/lib/formatters"
+import { LOCAL_MACHINE, MachineUsage } from "@/lib/types"
+import { useEffect, useState } from "react"
+
+// Usage per source machine; only shown once usage from another machine has been imported
+export function MachinesWidget() {
+  const lastLoadTime = useUsageDataStore(state => state.lastLoadTime)
+  const costMode = useSettingsStore(state => state.settings.costMode)
+  const [machines, setMachines] = useState<MachineUsage[]>([])
+
+  useEffect(() => {
+    invoke<MachineUsage[]>('get_machine_report', { mode: costMode })
+      .then(setMachines)
+      .catch(error => console.error('[MachinesWidget] Failed to load machine report:', error))
+  }, [lastLoadTime, costMode])
+
+  if (machines.length < 2) {
+    return null
+  }
+
+  const totalCost = machines.reduce((sum, machine) => sum + machine.totalCost, 0)
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between space-y-0 pb-2">
+        <div>
+          <CardTitle>Machines</CardTitle>
+          <CardDescription>Usage by source machine</CardDescription>
+        </div>
+        <Monitor className="h-4 w-4 text-muted-foreground" />
+      </CardHeader>
+      <CardContent>
+        <div className="space-y-3">
+          {machines.map(machine => {
+            const share = totalCost > 0 ? (machine.totalCost / totalCost) * 100 : 0
+            return (
+              <div key={machine.machine} className="space-y-1">
+                <div className="flex items-center justify-between text-sm">
+                  <span className="font-medium">
+                    {machine.machine === LOCAL_MACHINE ? 'This machine' : machine.machine}
+                  </span>
+                  <span>{formatCurrency(machine.totalCost)}</span>
+                </div>
+                <div className="h-2 rounded bg-muted">
+                  <div className="h-2 rounded bg-primary" style={{ width: `${share}%` }} />
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  {formatTokenCount(machine.totalTokens)} tokens · {machine.sessions} sessions
+                </p>
+              </div>
+            )
+          })}
+        </div>
+      </CardContent>
+    </Card>
+  )
+}
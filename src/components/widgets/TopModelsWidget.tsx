@@ -1,11 +1,13 @@
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card"
 import { Skeleton } from "@/components/ui/skeleton"
 import { Button } from "@/components/ui/button"
-import { useUsageDataStore } from "@/store"
+import { useSettingsStore, useUsageDataStore } from "@/store"
+import { invoke } from "@tauri-apps/api/core"
 import { PieChart, Pie, Cell, Tooltip, ResponsiveContainer, Legend } from 'recharts'
 import { formatModelName } from "@/lib/data-processor"
-import { formatTokenCount } from "@/lib/formatters"
-import { useState } from "react"
+import { formatCurrency, formatTokenCount } from "@/lib/formatters"
+import { ModelUsage } from "@/lib/types"
+import { useEffect, useState } from "react"
 
 const COLORS = [
   'hsl(var(--primary))',
@@ -16,10 +18,26 @@ const COLORS = [
 
 type TimeWindow = '7d' | '30d' | 'all'
 
+// First day of the window as YYYY-MM-DD, counting today
+function windowStart(timeWindow: TimeWindow): string | undefined {
+  if (timeWindow === 'all') return undefined
+  const start = new Date()
+  start.setDate(start.getDate() - (timeWindow === '7d' ? 6 : 29))
+  return start.toISOString().slice(0, 10)
+}
+
 export function TopModelsWidget() {
-  const dailyReport = useUsageDataStore(state => state.dailyReport)
   const isLoading = useUsageDataStore(state => state.isLoading)
+  const lastLoadTime = useUsageDataStore(state => state.lastLoadTime)
+  const costMode = useSettingsStore(state => state.settings.costMode)
   const [timeWindow, setTimeWindow] = useState<TimeWindow>('30d')
+  const [models, setModels] = useState<ModelUsage[]>([])
+
+  useEffect(() => {
+    invoke<ModelUsage[]>('get_model_report', { since: windowStart(timeWindow), mode: costMode })
+      .then(setModels)
+      .catch(error => console.error('[TopModelsWidget] Failed to load model report:', error))
+  }, [timeWindow, lastLoadTime, costMode])
 
   if (isLoading) {
     return (
@@ -35,31 +53,12 @@ export function TopModelsWidget() {
     )
   }
 
-  // Aggregate model usage based on selected time window
-  const modelUsage = new Map<string, { tokens: number, cost: number }>()
-  
-  const daysToInclude = timeWindow === '7d' ? 7 : timeWindow === '30d' ? 30 : dailyReport.length
-  dailyReport.slice(0, daysToInclude).forEach(day => {
-    // Note: We need to enhance data processing to track model-specific usage
-    // For now, we'll show all models used
-    day.models.forEach(model => {
-      if (!modelUsage.has(model)) {
-        modelUsage.set(model, { tokens: 0, cost: 0 })
-      }
-      // This is a simplification - in reality we'd need per-model breakdowns
-      const usage = modelUsage.get(model)!
-      if (day.models.length > 0) {
-        usage.tokens += day.totalTokens / day.models.length
-        usage.cost += day.totalCost / day.models.length
-      }
-    })
-  })
-
-  const chartData = Array.from(modelUsage.entries())
-    .map(([model, usage]) => ({
-      name: formatModelName(model),
-      value: usage.tokens,
-      cost: usage.cost
+  const chartData = models
+    .map(model => ({
+      name: formatModelName(model.model),
+      value: model.totalTokens,
+      cost: model.totalCost,
+      cacheHitRatio: model.cacheHitRatio
     }))
     .sort((a, b) => b.value - a.value)
     .slice(0, 4)
@@ -145,8 +144,8 @@ export function TopModelsWidget() {
                   borderRadius: '6px',
                   color: 'hsl(var(--foreground))'
                 }}
-                formatter={(value: number, name: string) => [
-                  `${formatTokenCount(value)} tokens`,
+                formatter={(value: number, name: string, entry: any) => [
+                  `${formatTokenCount(value)} tokens, ${formatCurrency(entry.payload.cost)}, ${(entry.payload.cacheHitRatio * 100).toFixed(0)}% cache hits`,
                   name
                 ]}
                 labelStyle={{
@@ -3,9 +3,11 @@ import { listen } from '@tauri-apps/api/event';
 import { useUsageDataStore } from '../store/usageDataStore';
 import { useSettingsStore } from '../store/settingsStore';
 
-interface FileChangeEvent {
-  path: string;
-  kind: 'created' | 'modified' | 'removed';
+// Debounced batch of JSONL transcript changes from the backend
+interface FilesChangedEvent {
+  created: string[];
+  modified: string[];
+  removed: string[];
 }
 
 export function useFileMonitoring() {
@@ -18,11 +20,12 @@ export function useFileMonitoring() {
     try {
       console.log('[useFileMonitoring] Setting up file monitoring...');
       // Listen for file changes from the backend
-      const unlisten = listen<FileChangeEvent>('file-changed', async (event) => {
-        console.log('[useFileMonitoring] File changed:', event.payload);
+      const unlisten = listen<FilesChangedEvent>('files-changed', async (event) => {
+        console.log('[useFileMonitoring] Files changed:', event.payload);
         
-        // Reload data when Claude files change
-        if (event.payload.path.includes('Claude/') && event.payload.path.endsWith('.jsonl')) {
+        // Reload once per batch rather than once per file
+        const { created, modified, removed } = event.payload;
+        if (created.length + modified.length + removed.length > 0) {
           // Load only recent data for performance
           const thirtyDaysAgo = new Date();
           thirtyDaysAgo.setDate(thirtyDaysAgo.getDate() - 30);
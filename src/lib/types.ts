@@ -44,6 +44,7 @@ export interface UsageData {
   };
   costUSD?: number;
   version?: string;
+  machine?: string; // Label of the machine imported usage came from; absent for this machine
 }
 
 // Aggregated data structures
@@ -56,6 +57,7 @@ export interface DailyUsage {
   cacheReadTokens: number;
   totalTokens: number;
   totalCost: number;
+  modelBreakdowns?: ModelBreakdown[]; // Set by the backend report commands
 }
 
 export interface MonthlyUsage {
@@ -67,6 +69,7 @@ export interface MonthlyUsage {
   cacheReadTokens: number;
   totalTokens: number;
   totalCost: number;
+  modelBreakdowns?: ModelBreakdown[];
 }
 
 export interface SessionUsage {
@@ -95,8 +98,97 @@ export interface BlockUsage {
   totalTokens: number;
   totalCost: number;
   isActive: boolean;
+  modelBreakdowns?: ModelBreakdown[];
 }
 
+// Label the backend reports for usage recorded on this machine
+export const LOCAL_MACHINE = 'local';
+
+export interface MachineUsage {
+  machine: string;
+  models: ModelName[];
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationTokens: number;
+  cacheReadTokens: number;
+  totalTokens: number;
+  totalCost: number;
+  sessions: number;
+  lastActivity: ISOTimestamp;
+}
+
+// Result of the backend `import_usage` command
+export interface ImportSummary {
+  machine: string;
+  format: 'ccusage' | 'claudeDeck';
+  records: number;
+  added: number;
+  duplicates: number;
+}
+
+// Usage stored for one machine, from `get_imports`
+export interface ImportedMachine {
+  machine: string;
+  entries: number;
+  first: ISOTimestamp | null;
+  last: ISOTimestamp | null;
+}
+
+export interface UserUsage {
+  user: string;
+  machines: string[];
+  models: ModelName[];
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationTokens: number;
+  cacheReadTokens: number;
+  totalTokens: number;
+  totalCost: number;
+  sessions: number;
+  projects: number;
+  lastActivity: ISOTimestamp;
+}
+
+export type LeaderboardMetric = 'cost' | 'tokens' | 'sessions' | 'projects';
+
+export interface Leaderboard {
+  metric: LeaderboardMetric;
+  rows: { rank: number; user: string; value: number }[]; // Ties share a rank
+}
+
+// Team roll-up of the member snapshots in the team directory, from `get_team_report`
+export interface TeamReport {
+  totals: { inputTokens: number; outputTokens: number; cacheCreationTokens: number; cacheReadTokens: number; totalTokens: number; totalCost: number };
+  users: UserUsage[];
+  projects: { projectPath: string; models: ModelName[]; totalTokens: number; totalCost: number; sessions: number }[];
+  models: { model: ModelName; totalTokens: number; totalCost: number; requests: number }[];
+  leaderboards: Leaderboard[];
+  snapshots: { user: string; machine: string; entries: number; writtenAt: ISOTimestamp }[];
+  skipped: { file: string; error: string }[];
+}
+
+// Last snapshot read from another machine in the sync folder
+export interface PeerStatus {
+  machineId: string;
+  machine: string;
+  sequence: number | null;
+  writtenAt: ISOTimestamp | null; // The peer's clock, for display only
+  entries: number;
+  error: string | null; // The last complete snapshot is still used
+}
+
+// Outcome of the last folder sync, from `get_sync_status`, `sync_now` and `sync-updated`
+export interface SyncStatus {
+  machineId: string;
+  machine: string;
+  folder: string;
+  lastSync: ISOTimestamp;
+  publishedEntries: number;
+  error: string | null;
+  peers: PeerStatus[];
+}
+
+// One model's share of a daily, monthly or block row, most expensive first
 export interface ModelBreakdown {
   modelName: ModelName;
   inputTokens: number;
@@ -106,6 +198,56 @@ export interface ModelBreakdown {
   cost: number;
 }
 
+// Usage per model from `get_model_report`, most expensive first
+export interface ModelUsage {
+  model: ModelName;
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationTokens: number;
+  cacheReadTokens: number;
+  totalTokens: number;
+  totalCost: number;
+  requests: number;
+  costShare: number; // Fraction of the range's cost
+  cacheHitRatio: number; // Cache reads over all prompt tokens
+  averageTokensPerRequest: number;
+}
+
+// Prompt cache use of a group of entries, alongside its usual totals
+export interface CacheEfficiency {
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationTokens: number;
+  cacheReadTokens: number;
+  totalTokens: number;
+  totalCost: number;
+  cacheReadRatio: number; // Cache reads over all prompt tokens
+  writeAmortization: number; // Cache read tokens per cache creation token
+  uncachedCost: number; // Estimated cost without prompt caching, at list prices
+  calculatedCost: number; // Cost with prompt caching, at list prices
+  estimatedSavings: number; // Negative when cache writes did not pay off
+}
+
+// Prompt cache efficiency from `get_cache_report`
+export interface CacheReport {
+  totals: CacheEfficiency;
+  days: (CacheEfficiency & { date: DailyDate })[]; // Newest first
+  projects: (CacheEfficiency & { projectPath: string; sessions: number; poorReuseSessions: number })[];
+  poorReuseSessions: (CacheEfficiency & { sessionId: SessionId; projectPath: string; lastActivity: ISOTimestamp })[];
+}
+
+// Payload of the backend `load-progress` event
+export interface LoadProgress {
+  loadId: number;
+  command: string;
+  phase: 'discovering' | 'parsing' | 'done';
+  filesDiscovered: number;
+  filesParsed: number;
+  bytesDiscovered: number;
+  bytesParsed: number;
+  percent: number;
+}
+
 // Model pricing structure
 export interface ModelPricing {
   input_cost_per_token?: number;
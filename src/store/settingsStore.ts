@@ -50,6 +50,72 @@ export interface SystemTraySettings {
   };
 }
 
+export interface LocalApiSettings {
+  enabled: boolean;
+  bindAddress: string; // Loopback only, e.g. '127.0.0.1'
+  port: number;
+  token?: string; // Bearer token for the /v1 endpoints, generated by the backend when the API is enabled
+}
+
+export interface LoggingSettings {
+  level: 'error' | 'warn' | 'info' | 'debug' | 'trace';
+  modules?: Record<string, string>; // Per-module overrides, e.g. { file_monitor: 'debug' }
+}
+
+export interface FileMonitorSettings {
+  debounceMs: number; // Changes to a file within this window are merged into one event
+  forcePolling?: boolean; // Poll instead of native events, e.g. for network home directories
+  pollIntervalSecs?: number;
+}
+
+export interface ArchiveSettings {
+  enabled: boolean; // Keep usage records after Claude Code deletes old transcripts
+}
+
+export type ExportFormat = 'csv' | 'json' | 'ndjson' | 'markdown' | 'html' | 'xlsx' | 'parquet' | 'arrow';
+export type ExportReport = 'daily' | 'monthly' | 'session' | 'blocks' | 'models' | 'projects' | 'machines' | 'users' | 'cache' | 'entries';
+
+export const EXPORT_FORMATS: { value: ExportFormat; label: string }[] = [
+  { value: 'csv', label: 'CSV' },
+  { value: 'json', label: 'JSON' },
+  { value: 'ndjson', label: 'NDJSON' },
+  { value: 'markdown', label: 'Markdown' },
+  { value: 'html', label: 'HTML' },
+  { value: 'xlsx', label: 'Excel' },
+  { value: 'parquet', label: 'Parquet' },
+  { value: 'arrow', label: 'Arrow' },
+];
+
+export interface ExportSettings {
+  allowedDirectories: string[]; // Where exports with an explicit path may write, besides Downloads and Documents
+}
+
+export type ExportPeriod = 'previousDay' | 'previousWeek' | 'previousMonth' | 'monthToDate' | 'all';
+
+export interface ScheduledExport {
+  id: string;
+  enabled: boolean;
+  report: ExportReport;
+  format: ExportFormat;
+  period: ExportPeriod; // Resolved against the local date of each run
+  schedule: string; // Cron expression in local time, e.g. '0 9 1 * *'
+  destination: string; // Absolute directory
+  fileName: string; // Template with {report}, {period}, {since}, {until}, {date}, {time}, {year}, {month}, {day}
+}
+
+export interface SyncSettings {
+  enabled: boolean;
+  folder: string; // Shared folder, e.g. in Dropbox or a Syncthing share
+  machineName: string; // Label other machines show for this one; empty uses the host name
+  intervalSecs: number; // How often to write this machine's snapshot and read the others, at least 60
+}
+
+export interface TeamSettings {
+  directory: string; // Folder of member snapshots to roll up; empty turns team reports off
+  anonymize: boolean; // Replace user and machine labels with user-1, user-2, ...
+  userName: string; // Label this machine's usage carries in the team snapshots it writes
+}
+
 export interface AppSettings {
   theme: 'light' | 'dark' | 'system';
   customDataDirectories: string[];
@@ -64,8 +130,17 @@ export interface AppSettings {
   showInSystemTray: boolean; // Keep for backward compatibility
   systemTray?: SystemTraySettings; // New structure
   launchAtStartup: boolean;
-  defaultExportFormat: 'csv' | 'json';
+  defaultExportFormat: ExportFormat;
   compactMode: boolean; // Show only total tokens in tables
+  privacyMode?: boolean; // Backend sends only usage metadata, never transcript text
+  localApi?: LocalApiSettings; // Opt-in loopback HTTP API
+  logging?: LoggingSettings; // Backend log levels
+  fileMonitor?: FileMonitorSettings; // Watcher debouncing
+  archive?: ArchiveSettings; // Usage archive in the app data directory
+  export?: ExportSettings; // Headless export destinations
+  scheduledExports?: ScheduledExport[]; // Run by the backend while the app is open
+  sync?: SyncSettings; // Folder-based sync with other machines
+  team?: TeamSettings; // Team roll-up of member snapshots
 }
 
 interface SettingsState {
@@ -145,6 +220,7 @@ const defaultSettings: AppSettings = {
   launchAtStartup: false,
   defaultExportFormat: 'csv',
   compactMode: false,
+  privacyMode: false,
 };
 
 // Helper function to migrate old settings to new format
@@ -244,7 +320,8 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       console.log('[SettingsStore] Settings saved successfully');
       set({ settings, isSaving: false });
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to save settings';
+      // The backend rejects settings with a string, e.g. for a custom directory of `/`
+      const errorMessage = typeof error === 'string' ? error : error instanceof Error ? error.message : 'Failed to save settings';
       console.error('[SettingsStore] Failed to save settings:', error);
       set({ 
         error: errorMessage,
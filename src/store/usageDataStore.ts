@@ -1,7 +1,14 @@
 import { create } from 'zustand';
-import { UsageData, DailyUsage, MonthlyUsage, SessionUsage, BlockUsage, CostMode } from '../lib/types';
+import { UsageData, DailyUsage, MonthlyUsage, SessionUsage, BlockUsage, CostMode, LoadProgress, SyncStatus } from '../lib/types';
 import { parseJSONLContent, processDailyUsage, processMonthlyUsage, processSessionUsage, processBlockUsage } from '../lib/data-processor';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+// Error returned by the backend when a scan is cancelled or superseded by a newer one
+const LOAD_CANCELLED = 'Load cancelled';
+
+// Command whose scan progress is shown while loading
+const LOAD_COMMAND = 'load_usage_entries';
 
 interface UsageDataState {
   // Raw data
@@ -9,6 +16,11 @@ interface UsageDataState {
   isLoading: boolean;
   error: string | null;
   lastLoadTime: Date | null;
+  loadProgress: LoadProgress | null;
+  // Sequence number of the newest loadUsageData call; older calls drop their results
+  loadId: number;
+  // Lowest backend loadId whose progress belongs to the newest load
+  progressLoadId: number;
   
   // Processed reports
   dailyReport: DailyUsage[];
@@ -21,6 +33,7 @@ interface UsageDataState {
   
   // Actions
   loadUsageData: (sinceDate?: string) => Promise<void>;
+  cancelLoad: () => Promise<void>;
   setError: (error: string | null) => void;
   clearData: () => void;
   processReports: (costMode: CostMode) => void;
@@ -32,6 +45,9 @@ export const useUsageDataStore = create<UsageDataState>((set, get) => ({
   isLoading: false, // Start with loading false to prevent initialization issues
   error: null,
   lastLoadTime: null,
+  loadProgress: null,
+  loadId: 0,
+  progressLoadId: 0,
   dailyReport: [],
   monthlyReport: [],
   sessionReport: [],
@@ -41,13 +57,20 @@ export const useUsageDataStore = create<UsageDataState>((set, get) => ({
   // Load usage data from backend
   loadUsageData: async (sinceDate?: string) => {
     console.log('[UsageDataStore] loadUsageData called with sinceDate:', sinceDate);
-    set({ isLoading: true, error: null });
+    // Backend load ids only grow, so the new scan's id is past every scan reported so far
+    const loadId = get().loadId + 1;
+    set({ loadId, progressLoadId: get().progressLoadId + 1, isLoading: true, error: null, loadProgress: null });
+    const isCurrent = () => get().loadId === loadId;
     
     try {
       // Fetch JSONL content from backend
       console.log('[UsageDataStore] Invoking load_usage_entries...');
       const jsonLines = await invoke<string[]>('load_usage_entries', { sinceDate });
       console.log('[UsageDataStore] Received', jsonLines.length, 'JSONL files');
+      if (!isCurrent()) {
+        console.log('[UsageDataStore] Dropping results of a superseded load');
+        return;
+      }
       
       // Handle case where no data is found
       if (jsonLines.length === 0) {
@@ -96,6 +119,17 @@ export const useUsageDataStore = create<UsageDataState>((set, get) => ({
       get().processReports('auto');
       console.log('[UsageDataStore] Data load complete');
     } catch (error) {
+      if (!isCurrent()) {
+        // A newer load replaced this one and owns the loading state
+        console.log('[UsageDataStore] Superseded load ended');
+        return;
+      }
+      if (error === LOAD_CANCELLED) {
+        // The user cancelled; keep the data we have
+        console.log('[UsageDataStore] Load cancelled');
+        set({ isLoading: false, loadProgress: null });
+        return;
+      }
       const errorMessage = error instanceof Error ? error.message : 'Failed to load usage data';
       console.error('[UsageDataStore] Failed to load data:', error);
       set({ 
@@ -105,6 +139,15 @@ export const useUsageDataStore = create<UsageDataState>((set, get) => ({
     }
   },
   
+  // Abort any scan still running in the backend
+  cancelLoad: async () => {
+    try {
+      await invoke<number>('cancel_load');
+    } catch (error) {
+      console.error('[UsageDataStore] Failed to cancel load:', error);
+    }
+  },
+  
   // Process reports from raw data
   processReports: (costMode: CostMode) => {
     const rawData = get().rawData;
@@ -149,4 +192,21 @@ export const useUsageDataStore = create<UsageDataState>((set, get) => ({
     lastLoadTime: null,
     error: null,
   }),
-}));
\ No newline at end of file
+}));
+
+// Track scan progress reported by the backend while a load is running
+listen<LoadProgress>('load-progress', (event) => {
+  const { isLoading, progressLoadId } = useUsageDataStore.getState();
+  // Ignore report scans and scans from loads that have since been superseded
+  if (!isLoading || event.payload.command !== LOAD_COMMAND || event.payload.loadId < progressLoadId) {
+    return;
+  }
+  useUsageDataStore.setState({ loadProgress: event.payload, progressLoadId: event.payload.loadId });
+}).catch(error => console.error('[UsageDataStore] Failed to listen for load progress:', error));
+
+// Reload when another machine's snapshot in the sync folder changed
+listen<SyncStatus>('sync-updated', () => {
+  if (!useUsageDataStore.getState().isLoading) {
+    useUsageDataStore.getState().loadUsageData();
+  }
+}).catch(error => console.error('[UsageDataStore] Failed to listen for sync updates:', error));
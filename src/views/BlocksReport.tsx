@@ -187,10 +187,7 @@ export function BlocksReport() {
               {compactMode ? <Maximize2 className="h-4 w-4" /> : <Minimize2 className="h-4 w-4" />}
               <span className="ml-2">{compactMode ? "Detailed" : "Compact"}</span>
             </Button>
-            <ExportButton 
-              data={blockReport.filter(b => b.totalTokens > 0)} 
-              filename="claude-blocks-report"
-            />
+            <ExportButton report="blocks" />
           </div>
         </div>
 
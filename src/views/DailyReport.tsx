@@ -174,10 +174,7 @@ export function DailyReport() {
               {compactMode ? <Maximize2 className="h-4 w-4" /> : <Minimize2 className="h-4 w-4" />}
               <span className="ml-2">{compactMode ? "Detailed" : "Compact"}</span>
             </Button>
-            <ExportButton 
-              data={dailyReport} 
-              filename="claude-daily-report"
-            />
+            <ExportButton report="daily" />
           </div>
         </div>
 
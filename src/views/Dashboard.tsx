@@ -5,6 +5,8 @@ import { TopModelsWidget } from "@/components/widgets/TopModelsWidget"
 import { CostModeWidget } from "@/components/widgets/CostModeWidget"
 import { MonthlySummaryWidget } from "@/components/widgets/MonthlySummaryWidget"
 import { TotalUsageWidget } from "@/components/widgets/TotalUsageWidget"
+import { MachinesWidget } from "@/components/widgets/MachinesWidget"
+import { CacheEfficiencyWidget } from "@/components/widgets/CacheEfficiencyWidget"
 
 export function Dashboard() {
   return (
@@ -27,6 +29,8 @@ export function Dashboard() {
         <div className="grid gap-4 md:grid-cols-2">
           <TopModelsWidget />
           <CostModeWidget />
+          <CacheEfficiencyWidget />
+          <MachinesWidget />
         </div>
       </div>
     </Container>
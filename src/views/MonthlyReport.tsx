@@ -77,10 +77,7 @@ export function MonthlyReport() {
               Token usage and costs by month
             </p>
           </div>
-          <ExportButton 
-            data={monthlyReport} 
-            filename="claude-monthly-report"
-          />
+          <ExportButton report="monthly" />
         </div>
 
         {/* Summary Cards */}
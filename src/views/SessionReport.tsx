@@ -108,10 +108,7 @@ export function SessionReport() {
               Usage grouped by work sessions
             </p>
           </div>
-          <ExportButton 
-            data={sessionReport} 
-            filename="claude-session-report"
-          />
+          <ExportButton report="session" />
         </div>
 
         {/* Summary Cards */}
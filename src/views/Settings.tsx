@@ -2,15 +2,21 @@ import { Container } from "@/components/layout/Container"
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card"
 import { Button } from "@/components/ui/button"
 import { useSettingsStore } from "@/store"
-import { Bell, FolderOpen, X, Plus } from "lucide-react"
+import { Bell, FolderOpen, X, Plus, ShieldCheck } from "lucide-react"
 import { useState } from "react"
 import { SystemTraySettings } from "@/components/settings/SystemTraySettings"
+import { ScheduledExports } from "@/components/settings/ScheduledExports"
+import { ImportedUsage } from "@/components/settings/ImportedUsage"
+import { FolderSync } from "@/components/settings/FolderSync"
+import { TeamRollup } from "@/components/settings/TeamRollup"
+import { EXPORT_FORMATS } from "@/store/settingsStore"
 
 export function Settings() {
   const settings = useSettingsStore(state => state.settings)
   const updateSettings = useSettingsStore(state => state.updateSettings)
   const saveSettings = useSettingsStore(state => state.saveSettings)
   const isSaving = useSettingsStore(state => state.isSaving)
+  const saveError = useSettingsStore(state => state.error)
   const [newDirectory, setNewDirectory] = useState('')
 
   const handleThemeChange = (theme: 'light' | 'dark' | 'system') => {
@@ -174,6 +180,76 @@ export function Settings() {
             </CardContent>
           </Card>
 
+          {/* Privacy */}
+          <Card>
+            <CardHeader>
+              <CardTitle className="flex items-center gap-2">
+                <ShieldCheck className="h-5 w-5" />
+                Privacy
+              </CardTitle>
+              <CardDescription>Control what leaves the backend</CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center justify-between">
+                <label className="text-sm font-medium">Privacy Mode</label>
+                <Button
+                  variant={settings.privacyMode ? 'default' : 'outline'}
+                  size="sm"
+                  onClick={() => updateSettings({ privacyMode: !settings.privacyMode })}
+                >
+                  {settings.privacyMode ? 'Enabled' : 'Disabled'}
+                </Button>
+              </div>
+              <p className="text-sm text-muted-foreground">
+                Only token counts, models, costs and timestamps are read from transcripts.
+                Prompts, code and tool output are never loaded into the window.
+              </p>
+            </CardContent>
+          </Card>
+
+          {/* Export */}
+          <Card>
+            <CardHeader>
+              <CardTitle>Export</CardTitle>
+              <CardDescription>Format used by the export buttons on each report</CardDescription>
+            </CardHeader>
+            <CardContent>
+              <div className="flex flex-wrap gap-2">
+                {EXPORT_FORMATS.map(({ value, label }) => (
+                  <Button
+                    key={value}
+                    variant={settings.defaultExportFormat === value ? 'default' : 'outline'}
+                    size="sm"
+                    onClick={() => updateSettings({ defaultExportFormat: value })}
+                  >
+                    {label}
+                  </Button>
+                ))}
+              </div>
+            </CardContent>
+          </Card>
+
+          {/* Scheduled Exports */}
+          <ScheduledExports
+            settings={settings}
+            onUpdateSettings={updateSettings}
+          />
+
+          {/* Usage imported from other machines */}
+          <ImportedUsage />
+
+          {/* Sync with other machines through a shared folder */}
+          <FolderSync
+            settings={settings}
+            onUpdateSettings={updateSettings}
+          />
+
+          {/* Team roll-up of member snapshots */}
+          <TeamRollup
+            settings={settings}
+            onUpdateSettings={updateSettings}
+          />
+
           {/* Notifications */}
           <Card>
             <CardHeader>
@@ -326,7 +402,10 @@ export function Settings() {
           </Card>
 
           {/* Save Button */}
-          <div className="flex justify-end">
+          <div className="flex items-center justify-end gap-4">
+            {saveError && (
+              <p className="text-sm text-destructive">{saveError}</p>
+            )}
             <Button onClick={handleSave} disabled={isSaving}>
               {isSaving ? 'Saving...' : 'Save Settings'}
             </Button>